@@ -1,6 +1,10 @@
 pub trait ExposeError {
     fn code(&self) -> u32;
     fn module(&self) -> &str;
+    /// 错误附带的结构化数据，默认没有
+    fn data(&self) -> Option<serde_json::Value> {
+        None
+    }
 }
 
 impl std::fmt::Display for dyn ExposeError {
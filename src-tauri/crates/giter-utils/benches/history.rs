@@ -0,0 +1,115 @@
+//! 在生成的仓库上测量提交遍历的耗时，用于发现性能退化
+//!
+//! 运行: cargo bench --bench history
+//! 提交数量可以通过环境变量 GITER_BENCH_COMMITS 设置，默认 20000
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use git2::{Oid, Repository, Signature, Time};
+use giter_utils::types::git_data_provider::GitDataProvider;
+
+const DEFAULT_COMMITS: usize = 20000;
+
+/// 生成一个线性主线加侧分支的仓库，每10个提交合并一次侧分支，
+/// 每个提交修改一个文件，返回主线最后的提交
+fn generate(repo: &Repository, count: usize) -> Oid {
+    let mut main: Option<Oid> = None;
+    let mut side: Option<Oid> = None;
+    for i in 0..count {
+        let sig = Signature::new("bench", "bench@giter.dev", &Time::new(i as i64, 0)).unwrap();
+        let blob = repo.blob(format!("{}\n", i).as_bytes()).unwrap();
+        let mut builder = repo.treebuilder(None).unwrap();
+        builder
+            .insert(format!("file{}.txt", i % 100), blob, 0o100644)
+            .unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let on_side = i % 10 == 5;
+        let mut parents = vec![];
+        match (on_side, main, side) {
+            (true, _, Some(side)) => parents.push(side),
+            (true, Some(main), None) => parents.push(main),
+            (false, Some(main), _) => parents.push(main),
+            _ => {}
+        }
+        if i % 10 == 9 {
+            parents.extend(side);
+        }
+        let parents = parents
+            .iter()
+            .map(|id| repo.find_commit(*id).unwrap())
+            .collect::<Vec<_>>();
+        let parents = parents.iter().collect::<Vec<_>>();
+        let id = repo
+            .commit(None, &sig, &sig, &format!("commit {}", i), &tree, &parents)
+            .unwrap();
+        if on_side {
+            side = Some(id);
+        } else {
+            main = Some(id);
+        }
+    }
+    let head = main.unwrap();
+    repo.reference("refs/heads/master", head, true, "").unwrap();
+    repo.set_head("refs/heads/master").unwrap();
+    head
+}
+
+/// 至少运行1秒(最少3次)，输出平均耗时
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) {
+    let start = Instant::now();
+    let mut iterations = 0u32;
+    while iterations < 3 || start.elapsed() < Duration::from_secs(1) {
+        black_box(f());
+        iterations += 1;
+    }
+    let mean = start.elapsed() / iterations;
+    println!("{:<48} {:>12.3?} ({} iterations)", name, mean, iterations);
+}
+
+fn run(provider: &GitDataProvider, middle: Oid, suffix: &str) {
+    let refs = ["master".to_string()];
+    bench(&format!("commits(500){}", suffix), || {
+        provider.commits(500).unwrap()
+    });
+    bench(&format!("get_commits_before(500){}", suffix), || {
+        provider.get_commits_before(middle, 500).unwrap()
+    });
+    bench(&format!("commits_count{}", suffix), || {
+        provider.commits_count("master").unwrap()
+    });
+    bench(&format!("before_reference_commits_count{}", suffix), || {
+        provider.before_reference_commits_count("master").unwrap()
+    });
+    bench(&format!("commit_graph(200){}", suffix), || {
+        provider.commit_graph(&refs, 200, None).unwrap()
+    });
+}
+
+fn main() {
+    let count = std::env::var("GITER_BENCH_COMMITS")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .unwrap_or(DEFAULT_COMMITS);
+    let dir = tempfile::tempdir().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    let start = Instant::now();
+    generate(&repo, count);
+    println!("generated {} commits in {:.3?}", count, start.elapsed());
+    // 与实际仓库一样使用打包后的对象
+    let _ = std::process::Command::new("git")
+        .args(["repack", "-adq"])
+        .current_dir(dir.path())
+        .status();
+
+    let provider = GitDataProvider::new(dir.path()).unwrap();
+    let mut revwalk = repo.revwalk().unwrap();
+    revwalk.push_head().unwrap();
+    let middle = revwalk.nth(count / 2).unwrap().unwrap();
+
+    run(&provider, middle, "");
+    if provider.write_commit_graph().is_ok() {
+        assert!(provider.has_commit_graph());
+        run(&provider, middle, " (commit-graph)");
+    }
+}
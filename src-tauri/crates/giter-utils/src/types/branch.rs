@@ -3,12 +3,15 @@ use serde::{Deserialize, Serialize};
 ///就像是 refs/remotes/origin/HEAD 这样完整的名字
 ///
 type Reference = String;
+/// worktree: 检出该本地分支的工作树目录，没有被检出时为空
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Branch {
     pub name: String,
     pub is_remote: bool,
     pub reference: Reference,
+    #[serde(default)]
+    pub worktree: Option<String>,
 }
 
 impl Branch {
@@ -17,6 +20,7 @@ impl Branch {
             name,
             is_remote,
             reference,
+            worktree: None,
         }
     }
 }
@@ -30,6 +34,7 @@ impl<'a> From<git2::Reference<'a>> for Branch {
             name,
             is_remote: reference_str.starts_with("refs/remotes/"),
             reference: reference_str,
+            worktree: None,
         }
     }
 }
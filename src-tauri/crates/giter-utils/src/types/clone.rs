@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// 克隆选项
+///
+/// - branch: 检出的分支，为空时使用远程的默认分支
+/// - depth: 浅克隆深度，为空或者0时克隆完整历史
+/// - recurse_submodules: 克隆后递归初始化并更新子模块
+/// - bare: 克隆为裸仓库
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CloneOptions {
+    pub branch: Option<String>,
+    pub depth: Option<i32>,
+    pub recurse_submodules: bool,
+    pub bare: bool,
+}
@@ -8,6 +8,7 @@ use serde::{
 };
 
 use super::error::GitUtilsErrorCode;
+use super::sign::{SignatureStatus, has_signature};
 
 #[derive(Debug, Clone)]
 pub struct Commit {
@@ -21,61 +22,58 @@ pub struct Commit {
     pub datetime: i64,
     pub parents: Vec<Oid>,
     pub repo: String,
+    pub signature_status: SignatureStatus,
 }
 
 impl Commit {
-    pub fn new(
-        commit_id: String,
-        author_name: String,
-        author_email: String,
-        committer_name: String,
-        committer_email: String,
-        title: String,
-        message: String,
-        datetime: i64,
-        parents: Vec<Oid>,
-        repo: String,
-    ) -> Self {
+    /// 从 git2 的提交生成，合并提交的父提交按时间倒序排列
+    ///
+    /// 不会验证签名，有签名的提交为 SignatureStatus::Unverified，返回提交列表时再验证
+    pub fn new(commit: &git2::Commit, repo: &Repository) -> Self {
+        let author = commit.author();
+        let committer = commit.committer();
+        let message = String::from_utf8_lossy(commit.message_bytes()).to_string();
+        let mut parents = commit.parent_ids().collect::<Vec<Oid>>();
+        // 只有合并提交需要按时间排序父提交，每个父提交只读取一次；
+        // 浅克隆中缺失的父提交排在最后
+        if parents.len() > 1 {
+            let mut times = (0..parents.len())
+                .map(|idx| commit.parent(idx).map_or(i64::MIN, |p| p.time().seconds()))
+                .zip(parents)
+                .collect::<Vec<_>>();
+            times.sort_by_key(|t| std::cmp::Reverse(t.0));
+            parents = times.into_iter().map(|(_, id)| id).collect();
+        }
+        // 裸仓库没有工作目录，使用仓库目录
+        let repo = repo
+            .workdir()
+            .unwrap_or(repo.path())
+            .to_string_lossy()
+            .to_string();
         Commit {
-            commit_id,
-            author_name,
-            author_email,
-            committer_name,
-            committer_email,
-            title,
+            commit_id: commit.id().to_string(),
+            author_name: String::from_utf8_lossy(author.name_bytes()).to_string(),
+            author_email: String::from_utf8_lossy(author.email_bytes()).to_string(),
+            committer_name: String::from_utf8_lossy(committer.name_bytes()).to_string(),
+            committer_email: String::from_utf8_lossy(committer.email_bytes()).to_string(),
+            title: message.lines().next().unwrap_or("").to_string(),
             message,
-            datetime,
+            datetime: commit.time().seconds() * 1000,
             parents,
             repo,
+            signature_status: if has_signature(commit) {
+                SignatureStatus::Unverified
+            } else {
+                SignatureStatus::None
+            },
         }
     }
 
     pub fn from_oid(oid: Oid, repo: &Repository) -> Result<Self, GitUtilsErrorCode> {
         let commit = repo.find_commit(oid)?;
-        let time = commit.time().seconds();
-        let author = commit.author();
-        let author_name = String::from_utf8_lossy(author.name_bytes()).to_string();
-        let author_email = String::from_utf8_lossy(author.email_bytes()).to_string();
-        let committer = commit.committer();
-        let comitter_name = String::from_utf8_lossy(committer.name_bytes()).to_string();
-        let comitter_email = String::from_utf8_lossy(committer.email_bytes()).to_string();
-        let parents = commit.parent_ids().into_iter().collect::<Vec<Oid>>();
-        let message = String::from_utf8_lossy(commit.message_bytes()).to_string();
-        let title = message.lines().next().unwrap_or("").to_string();
-        let commit_id = commit.id().to_string();
-        let repo = repo.path().to_str().unwrap().to_string();
-        Ok(Commit::new(
-            commit_id,
-            author_name,
-            author_email,
-            comitter_name,
-            comitter_email,
-            title,
-            message,
-            time * 1000,
-            parents,
-            repo,
-        ))
+        let mut commit = Commit::new(&commit, repo);
+        commit.repo = repo.path().to_string_lossy().to_string();
+        Ok(commit)
     }
 }
 
@@ -84,7 +82,7 @@ impl Serialize for Commit {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Commit", 10)?;
+        let mut state = serializer.serialize_struct("Commit", 11)?;
         state.serialize_field("commitId", &self.commit_id)?;
         state.serialize_field("authorName", &self.author_name)?;
         state.serialize_field("authorEmail", &self.author_email)?;
@@ -102,6 +100,7 @@ impl Serialize for Commit {
                 .collect::<Vec<_>>(),
         )?;
         state.serialize_field("repo", &self.repo)?;
+        state.serialize_field("signatureStatus", &self.signature_status)?;
         state.end()
     }
 }
@@ -134,6 +133,7 @@ impl<'de> Deserialize<'de> for Commit {
                 let mut datetime = None;
                 let mut parents: Option<Vec<String>> = None;
                 let mut repo = None;
+                let mut signature_status = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -147,6 +147,7 @@ impl<'de> Deserialize<'de> for Commit {
                         "datetime" => datetime = Some(map.next_value()?),
                         "parents" => parents = Some(map.next_value()?),
                         "repo" => repo = Some(map.next_value()?),
+                        "signatureStatus" => signature_status = Some(map.next_value()?),
                         _ => (),
                     }
                 }
@@ -171,6 +172,7 @@ impl<'de> Deserialize<'de> for Commit {
                     datetime: datetime.ok_or_else(|| DeError::missing_field("datetime"))?,
                     parents,
                     repo: repo.ok_or_else(|| DeError::missing_field("repo"))?,
+                    signature_status: signature_status.unwrap_or(SignatureStatus::None),
                 })
             }
         }
@@ -186,6 +188,7 @@ impl<'de> Deserialize<'de> for Commit {
             "datetime",
             "parents",
             "repo",
+            "signatureStatus",
         ];
 
         deserializer.deserialize_struct("Commit", FIELDS, CommitVisitor)
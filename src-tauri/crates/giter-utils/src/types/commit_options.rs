@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use super::author::Author;
+
+/// 提交选项
+///
+/// - amend: 修改最后一次提交，message为空时沿用原提交的信息
+/// - author: 指定作者，为空时使用仓库配置的用户（amend时保留原作者）
+/// - allow_empty: 允许没有任何修改的提交
+/// - only_paths: 只提交这些路径在暂存区中的修改，其余暂存的修改保留在暂存区
+/// - sign: 是否签名，为空时根据 commit.gpgsign 配置决定
+/// - no_verify: 跳过 pre-commit、commit-msg 钩子和 Conventional Commits 检查
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CommitOptions {
+    pub amend: bool,
+    pub author: Option<Author>,
+    pub allow_empty: bool,
+    pub only_paths: Option<Vec<String>>,
+    pub sign: Option<bool>,
+    pub no_verify: bool,
+}
@@ -1,5 +1,414 @@
-#[derive(Debug, Clone)]
+use std::collections::VecDeque;
+use std::io::Write;
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, LazyLock};
+
+use git2::{Cred, CredentialType};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+/// 令牌作为密码使用时，url中没有用户名时使用的用户名
+const TOKEN_USERNAME: &str = "x-access-token";
+/// 默认的ssh私钥文件，位于 ~/.ssh 下
+const DEFAULT_SSH_KEYS: &[&str] = &["id_ed25519", "id_ecdsa", "id_rsa"];
+
+static STORE: LazyLock<RwLock<Option<Arc<dyn CredentialStore>>>> =
+    LazyLock::new(|| RwLock::new(None));
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
 pub enum Credential {
     Token(String),
     UsernamePassword(String, String),
+    /// ssh私钥文件，public_key 为空时由 libgit2 从私钥推导
+    #[serde(rename_all = "camelCase")]
+    SshKey {
+        username: Option<String>,
+        private_key: PathBuf,
+        public_key: Option<PathBuf>,
+        passphrase: Option<String>,
+    },
+}
+
+/// 凭证来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CredentialSource {
+    /// 调用方直接传入的用户名密码
+    Explicit,
+    /// git credential helper
+    Helper,
+    /// 应用保存的凭证
+    Store,
+    /// ssh-agent
+    SshAgent,
+    /// ssh私钥文件
+    SshKeyFile,
+    /// 应用保存的令牌
+    Token,
+}
+
+/// 应用保存的凭证，由应用实现(如加密存储)后通过 set_credential_store 注册
+pub trait CredentialStore: Send + Sync {
+    /// 获取可用于 url 的凭证，按优先级排序
+    fn get(&self, url: &str) -> Vec<Credential>;
+}
+
+/// 注册应用的凭证存储
+pub fn set_credential_store(store: Arc<dyn CredentialStore>) {
+    *STORE.write() = Some(store);
+}
+
+fn credential_store() -> Option<Arc<dyn CredentialStore>> {
+    STORE.read().clone()
+}
+
+/// 获取 url 中的主机名，支持 scheme://[user@]host[:port]/path 和 scp 风格的 [user@]host:path
+pub fn url_host(url: &str) -> Option<String> {
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest,
+        // 本地路径没有主机名
+        None if url.contains(':') && !Path::new(url).is_absolute() => url,
+        None => return None,
+    };
+    let authority = rest.split(['/', '\\']).next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = if url.contains("://") {
+        host.split(':').next()?
+    } else {
+        host.split_once(':').map(|(h, _)| h).unwrap_or(host)
+    };
+    if host.is_empty() {
+        None
+    } else {
+        Some(host.to_lowercase())
+    }
+}
+
+/// git credential helper 返回的凭证，操作结束后需要原样回传给 approve/reject
+#[derive(Debug, Clone)]
+struct HelperCredential {
+    raw: String,
+}
+
+/// 候选凭证，Agent 表示使用 ssh-agent
+#[derive(Debug, Clone)]
+enum Candidate {
+    Agent,
+    Credential(Credential),
+}
+
+impl Candidate {
+    fn is_ssh(&self) -> bool {
+        matches!(
+            self,
+            Candidate::Agent | Candidate::Credential(Credential::SshKey { .. })
+        )
+    }
+}
+
+/// 远程操作的凭证会话
+///
+/// libgit2 在认证失败后会再次调用凭证回调，会话按照以下顺序依次返回候选凭证：
+/// - 用户名密码：调用方传入的凭证、git credential helper、应用保存的用户名密码、应用保存的令牌
+/// - ssh：ssh-agent、应用保存的ssh私钥、~/.ssh 下的默认私钥
+///
+/// 操作成功后调用 finish，同一会话中的后续操作会优先使用认证成功的凭证
+pub struct CredentialSession {
+    workdir: PathBuf,
+    store: Option<Arc<dyn CredentialStore>>,
+    preferred: Option<(CredentialSource, Candidate)>,
+    userpass: Option<VecDeque<(CredentialSource, Credential)>>,
+    ssh: Option<VecDeque<(CredentialSource, Candidate)>>,
+    helper_tried: bool,
+    helper: Option<HelperCredential>,
+    current: Option<(CredentialSource, Candidate)>,
+}
+
+impl CredentialSession {
+    pub fn new<P: AsRef<Path>>(workdir: P, explicit: &Option<(String, String)>) -> Self {
+        let preferred = explicit
+            .clone()
+            .filter(|(u, p)| !u.is_empty() && !p.is_empty())
+            .map(|(u, p)| {
+                (
+                    CredentialSource::Explicit,
+                    Candidate::Credential(Credential::UsernamePassword(u, p)),
+                )
+            });
+        CredentialSession {
+            workdir: workdir.as_ref().to_path_buf(),
+            store: credential_store(),
+            preferred,
+            userpass: None,
+            ssh: None,
+            helper_tried: false,
+            helper: None,
+            current: None,
+        }
+    }
+
+    /// 最近一次返回给 libgit2 的凭证来源
+    pub fn last_source(&self) -> Option<CredentialSource> {
+        self.current.as_ref().map(|(source, _)| *source)
+    }
+
+    /// 作为 RemoteCallbacks::credentials 的回调
+    pub fn credential(
+        &mut self,
+        url: &str,
+        username_from_url: Option<&str>,
+        allowed_types: CredentialType,
+    ) -> Result<Cred, git2::Error> {
+        if allowed_types.contains(CredentialType::USERNAME) {
+            return Cred::username(username_from_url.unwrap_or("git"));
+        }
+        if allowed_types.contains(CredentialType::SSH_KEY) {
+            return self.next_ssh(url, username_from_url);
+        }
+        if allowed_types.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            return self.next_userpass(url, username_from_url);
+        }
+        if allowed_types.contains(CredentialType::DEFAULT) {
+            return Cred::default();
+        }
+        Err(git2::Error::new(
+            git2::ErrorCode::Auth,
+            git2::ErrorClass::Net,
+            "No supported credential type",
+        ))
+    }
+
+    fn take_preferred(&mut self, ssh: bool) -> Option<(CredentialSource, Candidate)> {
+        match &self.preferred {
+            Some((_, candidate)) if candidate.is_ssh() == ssh => self.preferred.take(),
+            _ => None,
+        }
+    }
+
+    /// 按顺序获取下一个用户名密码凭证
+    pub fn next_userpass_credential(
+        &mut self,
+        url: &str,
+        username_from_url: Option<&str>,
+    ) -> Option<(CredentialSource, Credential)> {
+        if let Some((source, Candidate::Credential(credential))) = self.take_preferred(false) {
+            return Some((source, credential));
+        }
+        if !self.helper_tried {
+            self.helper_tried = true;
+            if let Some((helper, username, password)) =
+                credential_fill(&self.workdir, url, username_from_url)
+            {
+                self.helper = Some(helper);
+                return Some((
+                    CredentialSource::Helper,
+                    Credential::UsernamePassword(username, password),
+                ));
+            }
+        }
+        // helper 返回的凭证认证失败
+        if let Some(helper) = self.helper.take() {
+            credential_feedback(&self.workdir, "reject", &helper);
+        }
+        let candidates = self.userpass.get_or_insert_with(|| {
+            let stored = self.store.as_ref().map(|s| s.get(url)).unwrap_or_default();
+            let mut candidates = VecDeque::new();
+            let mut tokens = vec![];
+            for credential in stored {
+                match credential {
+                    Credential::UsernamePassword(..) => {
+                        candidates.push_back((CredentialSource::Store, credential))
+                    }
+                    Credential::Token(token) => tokens.push((
+                        CredentialSource::Token,
+                        Credential::UsernamePassword(
+                            username_from_url.unwrap_or(TOKEN_USERNAME).to_string(),
+                            token,
+                        ),
+                    )),
+                    Credential::SshKey { .. } => {}
+                }
+            }
+            candidates.extend(tokens);
+            candidates
+        });
+        candidates.pop_front()
+    }
+
+    fn next_userpass(
+        &mut self,
+        url: &str,
+        username_from_url: Option<&str>,
+    ) -> Result<Cred, git2::Error> {
+        match self.next_userpass_credential(url, username_from_url) {
+            Some((source, credential)) => {
+                let cred = match &credential {
+                    Credential::UsernamePassword(username, password) => {
+                        Cred::userpass_plaintext(username, password)
+                    }
+                    _ => unreachable!(),
+                };
+                self.current = Some((source, Candidate::Credential(credential)));
+                cred
+            }
+            None => Err(git2::Error::new(
+                git2::ErrorCode::User,
+                git2::ErrorClass::None,
+                "Credentials required for remote operation",
+            )),
+        }
+    }
+
+    fn next_ssh(
+        &mut self,
+        url: &str,
+        username_from_url: Option<&str>,
+    ) -> Result<Cred, git2::Error> {
+        let next = match self.take_preferred(true) {
+            Some(preferred) => Some(preferred),
+            None => self
+                .ssh
+                .get_or_insert_with(|| {
+                    let stored = self.store.as_ref().map(|s| s.get(url)).unwrap_or_default();
+                    let mut candidates = VecDeque::new();
+                    candidates.push_back((CredentialSource::SshAgent, Candidate::Agent));
+                    let mut paths = vec![];
+                    for credential in stored {
+                        if let Credential::SshKey { private_key, .. } = &credential {
+                            paths.push(private_key.clone());
+                            candidates.push_back((
+                                CredentialSource::Store,
+                                Candidate::Credential(credential),
+                            ));
+                        }
+                    }
+                    for key in default_ssh_keys() {
+                        if !paths.contains(&key) {
+                            let credential = Credential::SshKey {
+                                username: None,
+                                private_key: key,
+                                public_key: None,
+                                passphrase: None,
+                            };
+                            candidates.push_back((
+                                CredentialSource::SshKeyFile,
+                                Candidate::Credential(credential),
+                            ));
+                        }
+                    }
+                    candidates
+                })
+                .pop_front(),
+        };
+        let (source, candidate) = next.ok_or_else(|| {
+            git2::Error::new(
+                git2::ErrorCode::Auth,
+                git2::ErrorClass::Ssh,
+                "No ssh key could authenticate",
+            )
+        })?;
+        let username = username_from_url.unwrap_or("git");
+        let cred = match &candidate {
+            Candidate::Credential(Credential::SshKey {
+                username: key_username,
+                private_key,
+                public_key,
+                passphrase,
+            }) => Cred::ssh_key(
+                key_username.as_deref().unwrap_or(username),
+                public_key.as_deref(),
+                private_key,
+                passphrase.as_deref(),
+            ),
+            _ => Cred::ssh_key_from_agent(username),
+        };
+        self.current = Some((source, candidate));
+        cred
+    }
+
+    /// 远程操作结束，将 helper 提供的凭证的认证结果反馈给 git credential helper，
+    /// 操作成功时记住使用的凭证
+    pub fn finish(&mut self, success: bool) {
+        let used_helper = self.last_source() == Some(CredentialSource::Helper);
+        if let Some(helper) = self.helper.take().filter(|_| used_helper) {
+            let action = if success { "approve" } else { "reject" };
+            credential_feedback(&self.workdir, action, &helper);
+        }
+        if let Some(current) = self.current.take().filter(|_| success) {
+            self.preferred = Some(current);
+        }
+    }
+}
+
+fn default_ssh_keys() -> Vec<PathBuf> {
+    let home = match std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")) {
+        Some(home) => PathBuf::from(home),
+        None => return vec![],
+    };
+    DEFAULT_SSH_KEYS
+        .iter()
+        .map(|name| home.join(".ssh").join(name))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// 执行 git credential 子命令，禁止git弹出终端或者askpass提示
+fn git_credential(workdir: &Path, action: &str, input: &str) -> Option<String> {
+    let mut cmd = Command::new("git");
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000);
+    cmd.args(["-c", "core.askPass=", "credential", action])
+        .current_dir(workdir)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_ASKPASS", "")
+        .env("SSH_ASKPASS", "")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = cmd.spawn().ok()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(input.as_bytes());
+    }
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        log::debug!(
+            "git credential {} failed: {}",
+            action,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// 通过 git credential fill 协议从 credential helper 获取用户名密码
+fn credential_fill(
+    workdir: &Path,
+    url: &str,
+    username: Option<&str>,
+) -> Option<(HelperCredential, String, String)> {
+    let mut input = format!("url={}\n", url);
+    if let Some(username) = username {
+        input.push_str(&format!("username={}\n", username));
+    }
+    input.push('\n');
+    let raw = git_credential(workdir, "fill", &input)?;
+    let mut username = None;
+    let mut password = None;
+    for line in raw.lines() {
+        match line.split_once('=') {
+            Some(("username", value)) => username = Some(value.to_string()),
+            Some(("password", value)) => password = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    Some((HelperCredential { raw }, username?, password?))
+}
+
+fn credential_feedback(workdir: &Path, action: &str, helper: &HelperCredential) {
+    let input = format!("{}\n", helper.raw.trim_end());
+    git_credential(workdir, action, &input);
 }
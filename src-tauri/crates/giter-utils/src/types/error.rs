@@ -5,6 +5,8 @@ use strum_macros::{EnumDiscriminants, EnumIter};
 
 use thiserror::Error;
 
+use super::hook::HookOutput;
+
 #[derive(Error, Debug, EnumDiscriminants)]
 #[strum_discriminants(derive(EnumIter))]
 pub enum GitUtilsErrorCode {
@@ -110,6 +112,114 @@ pub enum GitUtilsErrorCode {
     #[error("Cant pull")]
     CantPull, // 不能拉取
 
+    #[error("No commit to amend")]
+    NoCommitToAmend, // 没有可以修改的提交
+
+    #[error("Failed to sign commit: {0}")]
+    SignCommitError(String), // 提交签名失败
+
+    #[error("{} hook failed: {}", .0.hook, .0.message())]
+    HookFailed(HookOutput), // 钩子执行失败
+
+    #[error("Invalid conventional commit message: {0}")]
+    ConventionalCommitInvalid(String), // 提交信息不符合 Conventional Commits 规范
+
+    #[error("Failed to fetch: {0}")]
+    FetchError(String), // 拉取远程失败
+
+    #[error("Operation cancelled")]
+    OperationCancelled, // 操作已取消
+
+    #[error("Authentication failed: {0}")]
+    RemoteAuthFailed(String), // 远程认证失败
+
+    #[error("Unknown host key: {0}")]
+    HostKeyUnknown(String), // 未知的主机密钥
+
+    #[error("Host key mismatch: {0}")]
+    HostKeyMismatch(String), // 主机密钥与记录的不一致
+
+    #[error("Rejected non-fast-forward update: {0}")]
+    NonFastForward(String), // 非快进更新被拒绝
+
+    #[error("Remote reference is locked: {0}")]
+    RemoteRefLocked(String), // 远程引用被锁定
+
+    #[error("Protected branch: {0}")]
+    ProtectedBranch(String), // 受保护的分支
+
+    #[error("Remote rejected: {0}")]
+    PushRejected(String), // 远程拒绝了推送
+
+    #[error("Network unreachable: {0}")]
+    NetworkUnreachable(String), // 网络不可达
+
+    #[error("Remote operation timed out: {0}")]
+    RemoteTimeout(String), // 远程操作超时
+
+    #[error("Failed to push: {0}")]
+    PushError(String), // 推送失败
+
+    #[error("Remote reference has been updated since last fetch: {0}")]
+    StaleRemoteRef(String), // 远程分支在上次拉取后被更新(force-with-lease 检查失败)
+
+    #[error("Not possible to fast-forward: {0}")]
+    CannotFastForward(String), // 无法快进
+
+    #[error("Cannot rebase with uncommitted changes")]
+    RebaseUncommittedChanges, // 有未提交的修改时不能变基
+
+    #[error("Applying autostash resulted in conflicts, changes are kept in the stash")]
+    AutostashConflict, // 恢复自动暂存的修改时冲突
+
+    #[error("Invalid remote name: {0}")]
+    InvalidRemoteName(String), // 无效的远程名称
+
+    #[error("Remote already exists: {0}")]
+    RemoteAlreadyExists(String), // 远程已存在
+
+    #[error("Failed to clone: {0}")]
+    CloneError(String), // 克隆失败
+
+    #[error("Destination path already exists and is not an empty directory: {0}")]
+    DestinationNotEmpty(String), // 克隆的目标目录不是空目录
+
+    #[error("Repository already exists: {0}")]
+    RepoAlreadyExists(String), // 仓库已存在
+
+    #[error("Gitignore template not found: {0}")]
+    GitignoreTemplateNotFound(String), // .gitignore 模板不存在
+
+    #[error("Invalid branch name: {0}")]
+    InvalidBranchName(String), // 无效的分支名
+
+    #[error("Submodule not found: {0}")]
+    SubmoduleNotFound(String), // 子模块不存在
+
+    #[error("Submodule is not checked out: {0}")]
+    SubmoduleNotCheckedOut(String), // 子模块还没有检出
+
+    #[error("Worktree not found: {0}")]
+    WorktreeNotFound(String), // 工作树不存在
+
+    #[error("Worktree already exists: {0}")]
+    WorktreeAlreadyExists(String), // 工作树已存在
+
+    #[error("Branch is already checked out in worktree: {0}")]
+    BranchAlreadyCheckedOut(String), // 分支已经在其他工作树中检出
+
+    #[error("Directory not found: {0}")]
+    DirectoryNotFound(String), // 目录不存在
+
+    #[error("Invalid search pattern: {0}")]
+    InvalidPattern(String), // 搜索的正则表达式无效
+
+    #[error("Invalid page cursor: {0}")]
+    InvalidCursor(String), // 分页的 cursor 无效
+
+    #[error("Invalid TLS certificate: {0}")]
+    CertificateInvalid(String), // 远程的 TLS 证书无效或不受信任
+
     #[error("Other git error: {0}")]
     OtherError(String),
 
@@ -120,6 +230,91 @@ pub enum GitUtilsErrorCode {
     AnyhowError(#[from] anyhow::Error),
 }
 
+impl GitUtilsErrorCode {
+    /// 将远程操作(fetch/push等)的 git2 错误转换为具体的错误，
+    /// 无法识别的错误使用 fallback 构造
+    pub fn from_remote(e: &git2::Error, fallback: impl FnOnce(String) -> Self) -> Self {
+        let message = e.message().to_string();
+        let lower = message.to_lowercase();
+        let host_key = lower.contains("host key") || lower.contains("hostkey");
+        match e.code() {
+            git2::ErrorCode::User => return GitUtilsErrorCode::PushNeedNameAndPassword,
+            git2::ErrorCode::Auth => return GitUtilsErrorCode::RemoteAuthFailed(message),
+            // SSH 的主机密钥和 HTTPS 的证书检查失败都使用 Certificate
+            git2::ErrorCode::Certificate if e.class() == git2::ErrorClass::Ssh || host_key => {
+                return if lower.contains("mismatch") {
+                    GitUtilsErrorCode::HostKeyMismatch(message)
+                } else {
+                    GitUtilsErrorCode::HostKeyUnknown(message)
+                };
+            }
+            git2::ErrorCode::Certificate => return GitUtilsErrorCode::CertificateInvalid(message),
+            git2::ErrorCode::NotFastForward => {
+                return GitUtilsErrorCode::NonFastForward(message);
+            }
+            git2::ErrorCode::Locked => return GitUtilsErrorCode::RemoteRefLocked(message),
+            git2::ErrorCode::Timeout => return GitUtilsErrorCode::RemoteTimeout(message),
+            _ => {}
+        }
+        if lower.contains("timed out") || lower.contains("timeout") {
+            GitUtilsErrorCode::RemoteTimeout(message)
+        } else if host_key {
+            if lower.contains("mismatch") {
+                GitUtilsErrorCode::HostKeyMismatch(message)
+            } else {
+                GitUtilsErrorCode::HostKeyUnknown(message)
+            }
+        } else if e.class() == git2::ErrorClass::Ssh && lower.contains("authenticat") {
+            GitUtilsErrorCode::RemoteAuthFailed(message)
+        } else if matches!(e.class(), git2::ErrorClass::Ssl | git2::ErrorClass::Net)
+            && lower.contains("certificate")
+        {
+            GitUtilsErrorCode::CertificateInvalid(message)
+        } else if [
+            // Net 类的错误还包括 TLS 握手失败、HTTP 状态码错误等，只根据信息判断网络不可达
+            "failed to resolve address",
+            "could not resolve host",
+            "failed to connect",
+            "could not connect",
+            "connection refused",
+            "network is unreachable",
+            "no route to host",
+        ]
+        .iter()
+        .any(|s| lower.contains(s))
+        {
+            GitUtilsErrorCode::NetworkUnreachable(message)
+        } else {
+            fallback(message)
+        }
+    }
+
+    /// 根据服务端返回的引用更新状态(push_update_reference)转换为具体的错误，
+    /// remote_message 为服务端通过 sideband 输出的信息
+    pub fn from_push_status(refname: &str, status: &str, remote_message: &str) -> Self {
+        let mut message = format!("{}: {}", refname, status);
+        if !remote_message.trim().is_empty() {
+            message = format!("{}\n{}", message, remote_message.trim());
+        }
+        let lower = message.to_lowercase();
+        if lower.contains("protected branch") || lower.contains("protected ref") {
+            GitUtilsErrorCode::ProtectedBranch(message)
+        } else if lower.contains("non-fast-forward")
+            || lower.contains("fetch first")
+            || lower.contains("not fast-forward")
+        {
+            GitUtilsErrorCode::NonFastForward(message)
+        } else if lower
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|w| w == "lock" || w == "locked")
+        {
+            GitUtilsErrorCode::RemoteRefLocked(message)
+        } else {
+            GitUtilsErrorCode::PushRejected(message)
+        }
+    }
+}
+
 impl ExposeError for GitUtilsErrorCode {
     fn code(&self) -> u32 {
         GitUtilsErrorCodeDiscriminants::from(self) as u32
@@ -128,4 +323,11 @@ impl ExposeError for GitUtilsErrorCode {
     fn module(&self) -> &str {
         return "giter-utils";
     }
+
+    fn data(&self) -> Option<serde_json::Value> {
+        match self {
+            GitUtilsErrorCode::HookFailed(output) => serde_json::to_value(output).ok(),
+            _ => None,
+        }
+    }
 }
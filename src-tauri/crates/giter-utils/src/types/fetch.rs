@@ -0,0 +1,77 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use super::error::GitUtilsErrorCode;
+
+/// 拉取远程选项
+///
+/// - prune: 删除远程已经不存在的远程跟踪分支
+/// - tags: 是否拉取标签，为空时按照远程配置(默认只拉取指向已拉取提交的标签)
+/// - depth: 浅克隆深度，为空或者0时拉取完整历史
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FetchOptions {
+    pub prune: bool,
+    pub tags: Option<bool>,
+    pub depth: Option<i32>,
+}
+
+/// 传输进度
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferProgress {
+    pub remote: String,
+    pub total_objects: usize,
+    pub received_objects: usize,
+    pub indexed_objects: usize,
+    pub local_objects: usize,
+    pub total_deltas: usize,
+    pub indexed_deltas: usize,
+    pub received_bytes: usize,
+    pub done: bool,
+}
+
+impl TransferProgress {
+    pub fn new(remote: &str, progress: &git2::Progress) -> Self {
+        TransferProgress {
+            remote: remote.to_string(),
+            total_objects: progress.total_objects(),
+            received_objects: progress.received_objects(),
+            indexed_objects: progress.indexed_objects(),
+            local_objects: progress.local_objects(),
+            total_deltas: progress.total_deltas(),
+            indexed_deltas: progress.indexed_deltas(),
+            received_bytes: progress.received_bytes(),
+            done: false,
+        }
+    }
+}
+
+/// 取消令牌，可以在其他线程中取消正在进行的远程操作
+#[derive(Debug, Default, Clone)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// 已经取消时返回 OperationCancelled，在连接远程之前检查，
+    /// 不需要传输数据的操作不会调用进度回调
+    pub fn check(&self) -> Result<(), GitUtilsErrorCode> {
+        if self.is_cancelled() {
+            return Err(GitUtilsErrorCode::OperationCancelled);
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 use git2::FileMode;
 use serde::Serialize;
 
+use super::tree::LastCommit;
+
 #[derive(Serialize, Debug, PartialEq, Clone)]
 #[serde(transparent)] // 或者 `untagged` 也行，因为只有一个字段
 pub struct EntryMode(u32);
@@ -45,11 +47,14 @@ impl From<FileMode> for EntryMode {
     }
 }
 
+/// last_commit: 相对浏览的提交，最后一次修改这一项的提交，只有按提交浏览时才有
 #[derive(Debug, Serialize)]
 pub struct EntryMetadata {
     pub size: usize,
     pub mode: EntryMode,
     pub object_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_commit: Option<LastCommit>,
 }
 #[derive(Debug, Serialize)]
 pub struct File {
@@ -76,6 +81,22 @@ pub enum FsNode {
     Dir(Dir), // 展开目录内容
 }
 
+impl FsNode {
+    pub fn name(&self) -> &str {
+        match self {
+            FsNode::File(file) => &file.name,
+            FsNode::Dir(dir) => &dir.name,
+        }
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut EntryMetadata {
+        match self {
+            FsNode::File(file) => &mut file.metadata,
+            FsNode::Dir(dir) => &mut dir.metadata,
+        }
+    }
+}
+
 impl File {
     pub fn new(
         path: String,
@@ -96,6 +117,7 @@ impl File {
                 size,
                 object_id,
                 mode,
+                last_commit: None,
             },
         }
     }
@@ -118,6 +140,7 @@ impl Dir {
                 size: 0,
                 mode: EntryMode::TREE,
                 object_id,
+                last_commit: None,
             },
         }
     }
@@ -1,10 +1,10 @@
 use super::file::FileHistoryEntry;
 use super::{author::Author, branch::Branch, commit::Commit, status::WorkStatus};
 use crate::types::fs::{self, EntryMode};
-use crate::types::fs::Dir;
 use crate::util::build_commit;
 use crate::util::change_status_to_file_status;
 use crate::util::get_file_content;
+use crate::util::has_git;
 use crate::util::is_binary_file;
 use crate::util::object_is_binary;
 use crate::util::size_by_path;
@@ -14,19 +14,20 @@ use crate::util::time_to_ymd;
 use crate::util::write_file;
 use anyhow::Result;
 use anyhow::anyhow;
-use git2::Cred;
-use git2::CredentialType;
 use git2::FetchOptions;
 use git2::PushOptions;
 use git2::RemoteCallbacks;
+use git2::Signature;
 use git2::Tree;
 use git2::TreeWalkMode;
 use git2::TreeWalkResult;
 use git2::build::CheckoutBuilder;
-use git2::{BranchType, Oid, Repository, Revwalk, Status};
+use git2::{BranchType, Direction, Oid, Repository, Revwalk, Status};
 use serde_json::Value;
 use similar::DiffOp;
 use similar::TextDiff;
+use std::cell::RefCell;
+use std::collections::BinaryHeap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt::Pointer;
@@ -36,18 +37,40 @@ use std::os::windows::process::CommandExt;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
+use std::sync::Arc;
 use std::usize;
 use std::vec;
 
+use super::clone;
 use super::commit_filter::FilterConditions;
+use super::commit_options::CommitOptions;
 use super::contribution::CommitStatistic;
+use super::credential::CredentialSession;
 use super::diff::ContentDiff;
 use super::error::GitUtilsErrorCode;
+use super::fetch::{self, CancelToken, TransferProgress};
 use super::file::ChangedFile;
 use super::file::CommittedEntry;
 use super::file::UntrackedFile;
+use super::graph::{self, GraphBuilder, GraphCursor, GraphPage, GraphRow};
+use super::grep::{GrepOptions, GrepResult, GrepSource};
+use super::history::{HistoryWalk, cached_commit};
+use super::hook::{HookRunner, conventional_commits_enabled, validate_conventional_commit};
+use super::init;
+use super::pickaxe::{self, PickaxeMatch, PickaxeOptions, PickaxePage};
+use super::pull;
+use super::push;
+use super::remote::Remote;
+use super::search::{CommitQuery, CommitSearch};
+use super::sign::{SignConfig, SignatureStatus, commit_signature_status};
 use super::status::FileStatus;
 use super::status::status_to_changed_status;
+use super::submodule::Submodule;
+use super::summary::{InProgressOperation, RepoSummary};
+use super::tree::{
+    FuzzyMatch, LastCommit, LastCommits, PathIndex, TreeEntry, TreeEntryDetail, TreePage,
+};
+use super::worktree::{self, Worktree};
 
 pub struct GitDataProvider {
     pub repository: Repository,
@@ -307,10 +330,15 @@ impl GitDataProvider {
     pub fn branches(&self) -> Result<Vec<Branch>, GitUtilsErrorCode> {
         let repo = &self.repository;
         let branches = repo.branches(None).unwrap();
+        let mut checked_out = self.checked_out_branches().unwrap_or_default();
         let mut _branches: Vec<Branch> = Vec::new();
         for branch in branches {
-            let (branch, _) = branch.unwrap();
-            _branches.push(Branch::from(branch.into_reference()))
+            let (branch, branch_type) = branch.unwrap();
+            let mut branch = Branch::from(branch.into_reference());
+            if branch_type == BranchType::Local {
+                branch.worktree = checked_out.remove(&branch.name);
+            }
+            _branches.push(branch)
         }
         Ok(_branches)
     }
@@ -320,7 +348,7 @@ impl GitDataProvider {
         let current = self.current_branch()?;
         let remote = self
             .repository
-            .find_branch(&current.reference, BranchType::Local)?
+            .find_branch(&current.name, BranchType::Local)?
             .upstream();
         match remote {
             Ok(remote) => Ok(Branch::from(remote.into_reference())),
@@ -332,7 +360,7 @@ impl GitDataProvider {
         let current = self.current_branch()?;
         let remote = self
             .repository
-            .find_branch(&current.reference, BranchType::Local)?
+            .find_branch(&current.name, BranchType::Local)?
             .upstream();
         match remote {
             Ok(remote) => Ok(remote),
@@ -364,16 +392,361 @@ impl GitDataProvider {
         Ok(false)
     }
 
-    /// 获取远程仓库列表
-    pub fn remotes(&self) -> Vec<String> {
-        let remote_names: Vec<String> = self
-            .branches()
-            .unwrap_or(vec![])
-            .iter()
-            .filter(|b| b.is_remote)
-            .map(|b| b.name.clone())
-            .collect();
-        remote_names
+    /// 当前分支是否落后于跟踪的远程分支(远程有本地没有的提交)
+    ///
+    pub fn unpulled_commits(&self) -> Result<bool> {
+        let repo = &self.repository;
+        let remote_branch = match self.current_remote_branch_inner() {
+            Ok(remote_branch) => remote_branch,
+            Err(_) => return Ok(false),
+        };
+        let remote_commit_id = remote_branch.get().peel_to_commit()?.id();
+        let local_commit_id = repo.head()?.peel_to_commit()?.id();
+        let (_, behind) = repo.graph_ahead_behind(local_commit_id, remote_commit_id)?;
+        Ok(behind > 0)
+    }
+
+    /// 获取仓库配置的远程列表
+    pub fn remotes(&self) -> Result<Vec<Remote>, GitUtilsErrorCode> {
+        let repo = &self.repository;
+        let mut remotes = vec![];
+        for name in repo.remotes()?.iter().flatten() {
+            remotes.push(Remote::from(&repo.find_remote(name)?));
+        }
+        Ok(remotes)
+    }
+
+    fn check_remote_name(&self, name: &str) -> Result<(), GitUtilsErrorCode> {
+        if !git2::Remote::is_valid_name(name) {
+            return Err(GitUtilsErrorCode::InvalidRemoteName(name.to_string()));
+        }
+        if self.repository.find_remote(name).is_ok() {
+            return Err(GitUtilsErrorCode::RemoteAlreadyExists(name.to_string()));
+        }
+        Ok(())
+    }
+
+    fn find_remote(&self, name: &str) -> Result<git2::Remote<'_>, GitUtilsErrorCode> {
+        self.repository
+            .find_remote(name)
+            .map_err(|_| GitUtilsErrorCode::RemoteNotFound(name.to_string()))
+    }
+
+    /// 添加远程，使用默认的 fetch refspec
+    pub fn add_remote(&self, name: &str, url: &str) -> Result<Remote, GitUtilsErrorCode> {
+        self.check_remote_name(name)?;
+        let remote = self.repository.remote(name, url)?;
+        Ok(Remote::from(&remote))
+    }
+
+    /// 重命名远程，远程跟踪分支和分支的上游配置会一起更新，
+    /// 返回无法自动更新的非默认 fetch refspec
+    pub fn rename_remote(
+        &self,
+        name: &str,
+        new_name: &str,
+    ) -> Result<Vec<String>, GitUtilsErrorCode> {
+        self.find_remote(name)?;
+        self.check_remote_name(new_name)?;
+        let problems = self.repository.remote_rename(name, new_name)?;
+        Ok(problems.iter().flatten().map(String::from).collect())
+    }
+
+    /// 删除远程以及它的远程跟踪分支
+    pub fn remove_remote(&self, name: &str) -> Result<(), GitUtilsErrorCode> {
+        self.find_remote(name)?;
+        self.repository.remote_delete(name)?;
+        Ok(())
+    }
+
+    /// 修改远程的地址，push_url 为空时删除单独配置的推送地址
+    pub fn set_remote_url(
+        &self,
+        name: &str,
+        url: &str,
+        push_url: Option<&str>,
+    ) -> Result<Remote, GitUtilsErrorCode> {
+        self.find_remote(name)?;
+        let repo = &self.repository;
+        repo.remote_set_url(name, url)?;
+        repo.remote_set_pushurl(name, push_url.filter(|u| !u.is_empty()))?;
+        Ok(Remote::from(&self.find_remote(name)?))
+    }
+
+    /// 仓库的子模块列表
+    pub fn submodules(&self) -> Result<Vec<Submodule>, GitUtilsErrorCode> {
+        let repo = &self.repository;
+        // submodule_status 中的 IN_CONFIG 在只有 .gitmodules 时也会被设置，直接读取配置
+        let config = repo.config()?.snapshot()?;
+        let mut submodules = vec![];
+        for submodule in repo.submodules()? {
+            let name = submodule.name().unwrap_or_default();
+            let status = repo.submodule_status(name, git2::SubmoduleIgnore::None)?;
+            let initialized = config.get_entry(&format!("submodule.{}.url", name)).is_ok();
+            submodules.push(Submodule::new(&submodule, status, initialized));
+        }
+        Ok(submodules)
+    }
+
+    /// 根据子模块在仓库中的路径查找子模块
+    fn find_submodule(&self, path: &str) -> Result<git2::Submodule<'_>, GitUtilsErrorCode> {
+        let path = path.trim_end_matches(['/', '\\']);
+        self.repository
+            .submodules()?
+            .into_iter()
+            .find(|s| s.path() == Path::new(path))
+            .ok_or_else(|| GitUtilsErrorCode::SubmoduleNotFound(path.to_string()))
+    }
+
+    /// 把 .gitmodules 中子模块的配置写入 .git/config，
+    /// overwrite 为 true 时覆盖已经存在的配置
+    pub fn init_submodule(&self, path: &str, overwrite: bool) -> Result<(), GitUtilsErrorCode> {
+        self.find_submodule(path)?.init(overwrite)?;
+        Ok(())
+    }
+
+    /// 把 .gitmodules 中子模块的地址同步到 .git/config 和子模块的 origin，
+    /// path 为空时同步所有子模块
+    pub fn sync_submodule(&self, path: Option<&str>) -> Result<(), GitUtilsErrorCode> {
+        let submodules = match path {
+            Some(path) => vec![self.find_submodule(path)?],
+            None => self.repository.submodules()?,
+        };
+        for mut submodule in submodules {
+            submodule.sync()?;
+        }
+        Ok(())
+    }
+
+    /// 初始化并更新子模块，检出父仓库中记录的提交，path 为空时更新所有子模块，
+    /// recursive 为 true 时同时更新子模块中的子模块
+    ///
+    /// 传输进度通过 on_progress 回调(最多每100ms一次)，
+    /// cancel 被取消时中断传输并返回 OperationCancelled
+    pub fn update_submodule(
+        &self,
+        path: Option<&str>,
+        recursive: bool,
+        credentials: Option<(String, String)>,
+        cancel: &CancelToken,
+        on_progress: impl FnMut(TransferProgress),
+    ) -> Result<(), GitUtilsErrorCode> {
+        cancel.check()?;
+        let submodules = match path {
+            Some(path) => vec![self.find_submodule(path)?],
+            None => self.repository.submodules()?,
+        };
+        let session = self.credential_session(&credentials);
+        let last_emit = RefCell::new(std::time::Instant::now());
+        let on_progress = RefCell::new(on_progress);
+        let build_callbacks = || {
+            let mut cbs = RemoteCallbacks::new();
+            cbs.credentials(|url, username, allowed| {
+                session.borrow_mut().credential(url, username, allowed)
+            });
+            cbs.transfer_progress(|progress| {
+                if cancel.is_cancelled() {
+                    return false;
+                }
+                let mut last_emit = last_emit.borrow_mut();
+                if last_emit.elapsed() >= std::time::Duration::from_millis(100) {
+                    *last_emit = std::time::Instant::now();
+                    (on_progress.borrow_mut())(TransferProgress::new("origin", &progress));
+                }
+                true
+            });
+            cbs
+        };
+        let ret = update_submodules(submodules, &build_callbacks, recursive);
+        session.borrow_mut().finish(ret.is_ok());
+        ret.map_err(|e| {
+            if cancel.is_cancelled() {
+                return GitUtilsErrorCode::OperationCancelled;
+            }
+            log::error!("Update submodules error: {:?}", e);
+            GitUtilsErrorCode::from_remote(&e, GitUtilsErrorCode::FetchError)
+        })
+    }
+
+    /// 把已经检出的子模块作为单独的仓库打开
+    pub fn open_submodule(&self, path: &str) -> Result<GitDataProvider, GitUtilsErrorCode> {
+        let submodule = self.find_submodule(path)?;
+        let repository = submodule
+            .open()
+            .map_err(|_| GitUtilsErrorCode::SubmoduleNotCheckedOut(path.to_string()))?;
+        Ok(GitDataProvider { repository })
+    }
+
+    /// 主工作树所在的目录，当前仓库不是链接的工作树时返回 None
+    pub fn main_worktree(&self) -> Option<PathBuf> {
+        if !self.repository.is_worktree() {
+            return None;
+        }
+        let main = Repository::open(self.repository.commondir()).ok()?;
+        main.workdir().map(Path::to_path_buf)
+    }
+
+    fn find_worktree(&self, name: &str) -> Result<git2::Worktree, GitUtilsErrorCode> {
+        self.repository
+            .find_worktree(name)
+            .map_err(|_| GitUtilsErrorCode::WorktreeNotFound(name.to_string()))
+    }
+
+    /// 读取工作树检出的分支和提交
+    fn worktree_head(repo: &Repository) -> (Option<String>, Option<String>) {
+        let Ok(head) = repo.find_reference("HEAD") else {
+            return (None, None);
+        };
+        let branch = head
+            .symbolic_target()
+            .and_then(|t| t.strip_prefix("refs/heads/"))
+            .map(String::from);
+        let id = head.resolve().ok().and_then(|r| r.target());
+        (branch, id.map(|id| id.to_string()))
+    }
+
+    /// 仓库的工作树列表，主工作树在第一个，裸仓库没有主工作树
+    pub fn worktrees(&self) -> Result<Vec<Worktree>, GitUtilsErrorCode> {
+        let mut worktrees = vec![];
+        let main = Repository::open(self.repository.commondir())?;
+        if let Some(workdir) = main.workdir() {
+            let (branch, head_id) = Self::worktree_head(&main);
+            let path = workdir
+                .to_string_lossy()
+                .trim_end_matches(['/', '\\'])
+                .to_string();
+            worktrees.push(Worktree {
+                name: Path::new(&path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                path,
+                branch,
+                head_id,
+                is_main: true,
+                locked: false,
+                lock_reason: None,
+                prunable: false,
+            });
+        }
+        for name in self.repository.worktrees()?.iter().flatten() {
+            let worktree = self.find_worktree(name)?;
+            let (branch, head_id) = match worktree.validate() {
+                Ok(_) => match Repository::open_from_worktree(&worktree) {
+                    Ok(repo) => Self::worktree_head(&repo),
+                    Err(_) => (None, None),
+                },
+                Err(_) => (None, None),
+            };
+            let (locked, lock_reason) = match worktree.is_locked()? {
+                git2::WorktreeLockStatus::Unlocked => (false, None),
+                git2::WorktreeLockStatus::Locked(reason) => {
+                    (true, Some(reason.unwrap_or_default()))
+                }
+            };
+            worktrees.push(Worktree {
+                name: name.to_string(),
+                path: worktree
+                    .path()
+                    .to_string_lossy()
+                    .trim_end_matches(['/', '\\'])
+                    .to_string(),
+                branch,
+                head_id,
+                is_main: false,
+                locked,
+                lock_reason,
+                prunable: worktree.is_prunable(None).unwrap_or(false),
+            });
+        }
+        Ok(worktrees)
+    }
+
+    /// 本地分支名到检出该分支的工作树目录
+    fn checked_out_branches(&self) -> Result<HashMap<String, String>, GitUtilsErrorCode> {
+        Ok(self
+            .worktrees()?
+            .into_iter()
+            .filter_map(|w| w.branch.map(|b| (b, w.path)))
+            .collect())
+    }
+
+    /// 在 path 添加名为 name 的工作树
+    ///
+    /// 没有指定分支时以工作树名称从 HEAD 创建新分支，
+    /// 同一个分支不能同时在两个工作树中检出
+    pub fn add_worktree<P: AsRef<Path>>(
+        &self,
+        name: &str,
+        path: P,
+        options: &worktree::AddWorktreeOptions,
+    ) -> Result<Worktree, GitUtilsErrorCode> {
+        let repo = &self.repository;
+        if repo.find_worktree(name).is_ok() {
+            return Err(GitUtilsErrorCode::WorktreeAlreadyExists(name.to_string()));
+        }
+        let branch_name = options.branch.as_deref().filter(|b| !b.is_empty());
+        let new_branch = options.new_branch || branch_name.is_none();
+        let branch_name = branch_name.unwrap_or(name);
+        let branch = if new_branch {
+            if !git2::Branch::name_is_valid(branch_name)? {
+                return Err(GitUtilsErrorCode::InvalidBranchName(
+                    branch_name.to_string(),
+                ));
+            }
+            let head = repo.head()?.peel_to_commit()?;
+            repo.branch(branch_name, &head, false)?
+        } else {
+            let branch = repo
+                .find_branch(branch_name, BranchType::Local)
+                .map_err(|_| GitUtilsErrorCode::BranchNotFound(branch_name.to_string()))?;
+            if let Some(path) = self.checked_out_branches()?.remove(branch_name) {
+                return Err(GitUtilsErrorCode::BranchAlreadyCheckedOut(format!(
+                    "{} -> {}",
+                    branch_name, path
+                )));
+            }
+            branch
+        };
+        let reference = branch.into_reference();
+        let mut add_opt = git2::WorktreeAddOptions::new();
+        add_opt.lock(options.lock).reference(Some(&reference));
+        if let Err(e) = repo.worktree(name, path.as_ref(), Some(&add_opt)) {
+            // 新建的分支没有被使用，删除掉
+            if new_branch && let Ok(mut branch) = repo.find_branch(branch_name, BranchType::Local) {
+                let _ = branch.delete();
+            }
+            return Err(e.into());
+        }
+        self.worktrees()?
+            .into_iter()
+            .find(|w| !w.is_main && w.name == name)
+            .ok_or_else(|| GitUtilsErrorCode::WorktreeNotFound(name.to_string()))
+    }
+
+    /// 锁定工作树，锁定的工作树不会被清理
+    pub fn lock_worktree(&self, name: &str, reason: Option<&str>) -> Result<(), GitUtilsErrorCode> {
+        self.find_worktree(name)?
+            .lock(reason.filter(|r| !r.is_empty()))?;
+        Ok(())
+    }
+
+    pub fn unlock_worktree(&self, name: &str) -> Result<(), GitUtilsErrorCode> {
+        self.find_worktree(name)?.unlock()?;
+        Ok(())
+    }
+
+    /// 清理目录已经不存在并且没有锁定的工作树，返回被清理的工作树名称
+    pub fn prune_worktrees(&self) -> Result<Vec<String>, GitUtilsErrorCode> {
+        let mut pruned = vec![];
+        for name in self.repository.worktrees()?.iter().flatten() {
+            let worktree = self.find_worktree(name)?;
+            if worktree.is_prunable(None)? {
+                worktree.prune(None)?;
+                pruned.push(name.to_string());
+            }
+        }
+        Ok(pruned)
     }
 
     /// 获取仓库的文件状态
@@ -386,10 +759,20 @@ impl GitDataProvider {
             .map(|v| !v.is_empty())
             .map_err(|e| anyhow::anyhow!(e).context("Failed to get untracked files"))?;
 
-        let modified = self
+        let submodules = self
+            .repository
+            .submodules()
+            .map_err(|e| anyhow::anyhow!(e).context("Failed to get submodules"))?
+            .iter()
+            .map(|s| s.path().to_string_lossy().replace('\\', "/"))
+            .collect::<HashSet<_>>();
+        let (submodule_changed, modified): (Vec<_>, Vec<_>) = self
             .workspace_change()
-            .map(|v| !v.is_empty())
-            .map_err(|e| anyhow::anyhow!(e).context("Failed to get workspace changes"))?;
+            .map_err(|e| anyhow::anyhow!(e).context("Failed to get workspace changes"))?
+            .into_iter()
+            .partition(|path| submodules.contains(path.trim_end_matches('/')));
+        let modified = !modified.is_empty();
+        let submodule_changed = !submodule_changed.is_empty();
 
         let uncommitted = self
             .uncommitted()
@@ -398,12 +781,18 @@ impl GitDataProvider {
         let unpushed = self
             .unpushed_commits()
             .map_err(|e| anyhow::anyhow!(e).context("Failed to check unpushed commits"))?;
+
+        let behind = self
+            .unpulled_commits()
+            .map_err(|e| anyhow::anyhow!(e).context("Failed to check unpulled commits"))?;
         // 使用组合的 Result 处理
         let results = vec![
             (untracked, WorkStatus::Untracked),
             (modified, WorkStatus::Modified),
             (uncommitted, WorkStatus::Uncommitted),
             (unpushed, WorkStatus::Unpushed),
+            (behind, WorkStatus::Behind),
+            (submodule_changed, WorkStatus::Submodule),
         ];
         for (result, status) in results {
             match result {
@@ -419,6 +808,47 @@ impl GitDataProvider {
         Ok(statuses)
     }
 
+    /// 获取仓库概览，用于同时展示多个仓库的状态
+    pub fn summary(&self) -> Result<RepoSummary, GitUtilsErrorCode> {
+        let repo = &self.repository;
+        let status = self.work_status()?;
+        let head = repo.head().ok();
+        let branch = head
+            .as_ref()
+            .filter(|h| h.is_branch())
+            .and_then(|h| h.shorthand())
+            .map(String::from);
+        let last_commit = head
+            .as_ref()
+            .and_then(|h| h.peel_to_commit().ok())
+            .map(|c| build_commit(&c, repo));
+        let upstream = branch
+            .as_ref()
+            .and_then(|name| repo.find_branch(name, BranchType::Local).ok())
+            .and_then(|b| b.upstream().ok());
+        let (ahead, behind) = match (&upstream, &last_commit) {
+            (Some(upstream), Some(commit)) => {
+                let local = Oid::from_str(&commit.commit_id)?;
+                let remote = upstream.get().peel_to_commit()?.id();
+                repo.graph_ahead_behind(local, remote)?
+            }
+            _ => (0, 0),
+        };
+        let upstream = upstream.and_then(|u| u.name().ok().flatten().map(String::from));
+        // 每个 stash 是 refs/stash 的一条引用日志
+        let stash_count = repo.reflog("refs/stash").map(|r| r.len()).unwrap_or(0);
+        Ok(RepoSummary {
+            status,
+            branch,
+            upstream,
+            ahead,
+            behind,
+            stash_count,
+            last_commit,
+            operation: InProgressOperation::from_state(repo.state()),
+        })
+    }
+
     pub fn get_branch(&self, branch_name: &str) -> Result<Branch, GitUtilsErrorCode> {
         let branches = self.branches()?;
         for branch in branches {
@@ -432,32 +862,53 @@ impl GitDataProvider {
     pub fn build_commits(&self, revwalk: &mut Revwalk, count: i32) -> Result<Vec<Commit>> {
         let mut commits: Vec<Commit> = Vec::new();
         for (_, id) in revwalk.by_ref().take(count as usize).enumerate() {
-            let id = id?;
-            let commit = self.repository.find_commit(id)?;
-            let commit = build_commit(&commit, &self.repository);
-            commits.push(commit);
+            commits.push(cached_commit(&self.repository, id?)?);
         }
+        self.verify_signatures(&mut commits);
+        Ok(commits)
+    }
+
+    /// 按时间倒序获取 tip 之前(包含 tip)第 offset 个之后的 count 个提交
+    ///
+    /// 同一个 tip 的遍历会保存下来，下一页从上一次停下的位置继续，不需要重新遍历
+    pub fn history(
+        &self,
+        tip: Oid,
+        offset: usize,
+        count: usize,
+    ) -> Result<Vec<Commit>, GitUtilsErrorCode> {
+        let mut commits = HistoryWalk::page(&self.repository, tip, offset, count)?
+            .into_iter()
+            .map(|id| cached_commit(&self.repository, id))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.verify_signatures(&mut commits);
         Ok(commits)
     }
 
+    /// 验证一页提交中有签名的提交，只验证返回的这一页，结果会缓存
+    fn verify_signatures<'a>(&self, commits: impl IntoIterator<Item = &'a mut Commit>) {
+        for commit in commits {
+            if commit.signature_status != SignatureStatus::Unverified {
+                continue;
+            }
+            if let Ok(oid) = Oid::from_str(&commit.commit_id) {
+                commit.signature_status = commit_signature_status(&self.repository, oid);
+            }
+        }
+    }
+
     /// 从当前HEAD获取所有之前的提交
     ///
     pub fn commits(&self, count: i32) -> Result<Vec<Commit>> {
         let head_id = self.repository.head()?.target().unwrap();
-        let mut revwalk = self.repository.revwalk()?;
-        revwalk.push(head_id)?;
-        let commits = self.build_commits(&mut revwalk, count)?;
-        Ok(commits)
+        Ok(self.history(head_id, 0, count as usize)?)
     }
 
     /// 根据commit_id 获取之前的指定数量提交
     ///
     pub fn get_commits_before(&self, commit_id: impl Into<Oid>, count: i32) -> Result<Vec<Commit>> {
-        let commits = self.repository.find_commit(commit_id.into())?;
-        let mut revwalk = self.repository.revwalk()?;
-        revwalk.push(commits.id())?;
-        let commits = self.build_commits(&mut revwalk, count)?;
-        Ok(commits)
+        let commit = self.repository.find_commit(commit_id.into())?;
+        Ok(self.history(commit.id(), 0, count as usize)?)
     }
 
     /// 获取分支所有提交(分支的最后一次提交)，git2中的提交对象
@@ -488,17 +939,85 @@ impl GitDataProvider {
     ) -> Result<Vec<Commit>, GitUtilsErrorCode> {
         // 获取分支所在的提交
         let commit = self.branch_commit_inner(branch)?;
-        let mut revwalk = self.repository.revwalk()?;
-        revwalk.push(commit.id())?;
-        let commits = self.build_commits(&mut revwalk, count)?;
-        Ok(commits)
+        self.history(commit.id(), 0, count as usize)
+    }
+
+    /// 计算多个引用的提交图，按时间倒序返回 count 行
+    ///
+    /// refs 为空时使用 HEAD；cursor 为上一页返回的 cursor，为空时从第一页开始
+    pub fn commit_graph(
+        &self,
+        refs: &[String],
+        count: usize,
+        cursor: Option<&GraphCursor>,
+    ) -> Result<GraphPage, GitUtilsErrorCode> {
+        let repo = &self.repository;
+        // 之后的页沿用第一页的起点，保证引用移动后仍然翻的是同一份历史
+        let (mut builder, offset, tips) = match cursor {
+            Some(cursor) => {
+                let tips = cursor
+                    .tips
+                    .iter()
+                    .map(|id| Oid::from_str(id))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| GitUtilsErrorCode::InvalidCursor("graph cursor".to_string()))?;
+                (GraphBuilder::from_cursor(cursor)?, cursor.offset, tips)
+            }
+            None => {
+                let mut tips = Vec::with_capacity(refs.len().max(1));
+                if refs.is_empty() {
+                    tips.push(repo.head()?.peel_to_commit()?.id());
+                }
+                for name in refs {
+                    let commit = repo
+                        .revparse_single(name)
+                        .and_then(|o| o.peel_to_commit())
+                        .map_err(|_| GitUtilsErrorCode::BranchNotFound(name.clone()))?;
+                    tips.push(commit.id());
+                }
+                (GraphBuilder::default(), 0, tips)
+            }
+        };
+        let mut walk = graph::resume_walk(repo, &tips, offset)?;
+        let mut rows = vec![];
+        while rows.len() < count {
+            let Some(commit) = walk.next(repo)? else {
+                break;
+            };
+            // 提交时间错误时父提交可能先于子提交出现，已经出现的父提交不再连线
+            let parents = commit
+                .parent_ids()
+                .filter(|id| walk.is_pending(*id))
+                .collect::<Vec<_>>();
+            let (column, color, edges, width) = builder.next(commit.id(), &parents);
+            rows.push(GraphRow {
+                commit: build_commit(&commit, repo),
+                column,
+                color,
+                edges,
+                width,
+            });
+        }
+        self.verify_signatures(rows.iter_mut().map(|row| &mut row.commit));
+        let end = offset + rows.len();
+        let cursor = if walk.is_done() {
+            None
+        } else {
+            graph::save_walk(repo, &tips, end, walk);
+            Some(builder.cursor(end, &tips))
+        };
+        Ok(GraphPage { rows, cursor })
     }
 
-    /// 在某些情况下，使用git命令获取提交数量会更快，因为git命令是直接调用底层的git库，而不是通过rust的git2库
+    /// 使用 git rev-list --count 计算提交数量，存在 commit-graph 文件时 git 直接从中读取提交关系；
+    /// 没有安装git时使用 commits_count
     pub fn before_reference_commits_count(
         &self,
         reference: &str,
     ) -> Result<i32, GitUtilsErrorCode> {
+        if !has_git() {
+            return self.commits_count(reference).map(|count| count as i32);
+        }
         let output = Command::new("git")
             .args(&["rev-list", "--count", reference])
             .current_dir(self.workdir())
@@ -517,6 +1036,52 @@ impl GitDataProvider {
         }
     }
 
+    /// 使用 revwalk 计算引用之前的提交数量，只遍历提交id，不生成提交信息；
+    /// libgit2 不读取 commit-graph 文件，仍然需要解析每个提交对象
+    pub fn commits_count(&self, reference: &str) -> Result<usize, GitUtilsErrorCode> {
+        let commit = self
+            .repository
+            .revparse_single(reference)
+            .and_then(|o| o.peel_to_commit())
+            .map_err(|_| GitUtilsErrorCode::BranchNotFound(reference.to_string()))?;
+        let mut revwalk = self.repository.revwalk()?;
+        revwalk.push(commit.id())?;
+        let mut count = 0;
+        for id in revwalk {
+            id?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// 仓库是否有 commit-graph 文件(单个文件或者 commit-graphs 目录中的分层文件)
+    pub fn has_commit_graph(&self) -> bool {
+        // 链接的工作树中 path() 为 .git/worktrees/<name>，对象在主仓库的目录中
+        let info = self.repository.commondir().join("objects").join("info");
+        info.join("commit-graph").is_file()
+            || info
+                .join("commit-graphs")
+                .join("commit-graph-chain")
+                .is_file()
+    }
+
+    /// 调用 git commit-graph write 为所有可达的提交生成(或更新) commit-graph 文件，
+    /// 大仓库中 git rev-list 计算提交数量会快很多
+    pub fn write_commit_graph(&self) -> Result<(), GitUtilsErrorCode> {
+        let mut cmd = Command::new("git");
+        #[cfg(windows)]
+        cmd.creation_flags(0x08000000);
+        cmd.current_dir(self.workdir());
+        cmd.args(["commit-graph", "write", "--reachable"]);
+        let output = cmd.output()?;
+        if !output.status.success() {
+            return Err(GitUtilsErrorCode::OtherError(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     /// 获取一颗提交树的所有文件列表
     fn tree_walk(&self, tree: &git2::Tree) -> Vec<CommittedEntry> {
         let mut files: Vec<CommittedEntry> = Vec::new();
@@ -556,10 +1121,20 @@ impl GitDataProvider {
 
     pub fn get_commit(&self, commit_id: impl Into<Oid>) -> Result<Commit, GitUtilsErrorCode> {
         let commit = self.repository.find_commit(commit_id.into())?;
-        let commit = build_commit(&commit, &self.repository);
+        let mut commit = build_commit(&commit, &self.repository);
+        self.verify_signatures([&mut commit]);
         Ok(commit)
     }
 
+    /// 验证提交的签名，会调用 gpg 或者 ssh-keygen，结果会缓存
+    pub fn verify_commit(
+        &self,
+        commit_id: impl Into<Oid>,
+    ) -> Result<SignatureStatus, GitUtilsErrorCode> {
+        let commit = self.repository.find_commit(commit_id.into())?;
+        Ok(commit_signature_status(&self.repository, commit.id()))
+    }
+
     /// 获取一个提交的内容
     ///
     pub fn commit_content(
@@ -812,6 +1387,24 @@ impl GitDataProvider {
         filter
             .entry("count".to_string())
             .or_insert(Value::from(size.unwrap_or(i32::MAX)));
+        let conditions = FilterConditions::build_from_sv_map(&filter);
+        let unfiltered = conditions.author.is_none()
+            && conditions.message.is_none()
+            && conditions.start_time.is_none()
+            && conditions.end_time.is_none();
+        // 没有筛选条件时沿用保存的遍历，翻页不需要每次从头跳过 offset 个提交
+        if unfiltered
+            && let Ok(tip) = self
+                .repository
+                .revparse_single(reference)
+                .and_then(|o| o.peel_to_commit())
+        {
+            return self.history(
+                tip.id(),
+                conditions.offset.unwrap_or(0),
+                conditions.count.unwrap_or(usize::MAX),
+            );
+        }
         let mut cmd = self.build_cinnut_filter(reference, &filter)?;
         let output = cmd.output()?;
         let commit_ids = String::from_utf8_lossy(&output.stdout)
@@ -824,163 +1417,743 @@ impl GitDataProvider {
         }
         let mut commits = Vec::<Commit>::new();
         for commit_id in commit_ids {
-            let commit = Commit::from_oid(str_to_oid(&commit_id)?, &self.repository)?;
-            commits.push(commit);
+            commits.push(cached_commit(&self.repository, str_to_oid(&commit_id)?)?);
+        }
+        self.verify_signatures(&mut commits);
+        Ok(commits)
+    }
+
+    /// 按条件搜索提交，按时间倒序返回最多 limit 个提交
+    ///
+    /// 翻页时 limit 增大，已经遍历过的历史不会重新遍历
+    pub fn search_commits(
+        &self,
+        query: &CommitQuery,
+        limit: usize,
+    ) -> Result<Vec<Commit>, GitUtilsErrorCode> {
+        let repo = &self.repository;
+        let mut tips = vec![];
+        if query.branches.is_empty() {
+            for branch in repo.branches(Some(git2::BranchType::Local))? {
+                if let Ok(commit) = branch?.0.get().peel_to_commit() {
+                    tips.push(commit.id());
+                }
+            }
+        } else {
+            for name in &query.branches {
+                let target = repo
+                    .resolve_reference_from_short_name(name)
+                    .ok()
+                    .and_then(|r| r.peel_to_commit().ok());
+                if let Some(commit) = target {
+                    tips.push(commit.id());
+                }
+            }
         }
+        let path = query
+            .path
+            .as_deref()
+            .map(|p| p.trim_matches('/'))
+            .filter(|p| !p.is_empty());
+        let ids = CommitSearch::search(repo, tips, query, limit, |commit| {
+            if !query.matches(commit) {
+                return Ok(false);
+            }
+            if let Some(path) = path
+                && !self.commit_touches_path(commit, path)?
+            {
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+        let mut commits = ids
+            .into_iter()
+            .map(|id| cached_commit(repo, id))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.verify_signatures(&mut commits);
         Ok(commits)
     }
 
+    /// 提交相对第一个父提交是否修改了 path 下的文件
+    fn commit_touches_path(
+        &self,
+        commit: &git2::Commit,
+        path: &str,
+    ) -> Result<bool, GitUtilsErrorCode> {
+        let tree = commit.tree()?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let mut options = git2::DiffOptions::new();
+        options.pathspec(path);
+        let diff = self.repository.diff_tree_to_tree(
+            parent_tree.as_ref(),
+            Some(&tree),
+            Some(&mut options),
+        )?;
+        Ok(diff.deltas().len() > 0)
+    }
+
+    /// 在提交历史中搜索新增或删除了匹配内容的提交，按时间倒序返回一页结果
+    ///
+    /// 按提交时间遍历，不需要先读取全部历史，找到第一个匹配的提交就可以返回；
+    /// 下一页从上一页的 cursor 继续，已经检查过的提交不会重新比较差异。
+    /// 每找到一个这一页中的提交回调一次 on_match，
+    /// cancel 被取消时停止搜索并返回 OperationCancelled
+    pub fn pickaxe(
+        &self,
+        options: &PickaxeOptions,
+        cancel: &CancelToken,
+        mut on_match: impl FnMut(&PickaxeMatch),
+    ) -> Result<PickaxePage, GitUtilsErrorCode> {
+        let repo = &self.repository;
+        let matcher = options.matcher()?;
+        let tip = match &options.reference {
+            Some(reference) => repo.revparse_single(reference)?.peel_to_commit()?,
+            None => repo.head()?.peel_to_commit()?,
+        }
+        .id();
+        let cursor = match options.cursor.as_deref() {
+            Some(cursor) => Some(
+                Oid::from_str(cursor)
+                    .map_err(|_| GitUtilsErrorCode::InvalidCursor(cursor.to_string()))?,
+            ),
+            None => None,
+        };
+        let path = options
+            .path
+            .as_deref()
+            .map(|p| p.trim_matches('/'))
+            .filter(|p| !p.is_empty());
+        let mut walk = pickaxe::resume_walk(repo, tip, cursor)?;
+        let mut last = cursor;
+        let mut matches = vec![];
+        while matches.len() < options.count {
+            if cancel.is_cancelled() {
+                return Err(GitUtilsErrorCode::OperationCancelled);
+            }
+            let Some(commit) = walk.next(repo)? else {
+                break;
+            };
+            last = Some(commit.id());
+            let Some(diff) = pickaxe::commit_diff(repo, &commit, path)? else {
+                continue;
+            };
+            let hunks = pickaxe::match_hunks(repo, &diff, &matcher, options.mode)?;
+            if hunks.is_empty() {
+                continue;
+            }
+            let mut found = PickaxeMatch {
+                commit: build_commit(&commit, repo),
+                hunks,
+            };
+            self.verify_signatures([&mut found.commit]);
+            on_match(&found);
+            matches.push(found);
+        }
+        let has_more = !walk.is_done();
+        if has_more && let Some(last) = last {
+            pickaxe::save_walk(repo, tip, last, walk);
+        }
+        Ok(PickaxePage {
+            matches,
+            has_more,
+            cursor: last.filter(|_| has_more).map(|id| id.to_string()),
+        })
+    }
+
     pub fn commit(
         &self,
         message: &str,
         update_ref: Option<&str>,
+    ) -> Result<Oid, GitUtilsErrorCode> {
+        self.commit_with_options(message, update_ref, &CommitOptions::default())
+    }
+
+    /// 根据提交选项创建提交，支持修改最后一次提交、指定作者、空提交、只提交部分路径，
+    /// 空仓库(HEAD未出生)时创建没有父提交的第一次提交
+    /// 提交前会依次执行 pre-commit、prepare-commit-msg、commit-msg 钩子
+    pub fn commit_with_options(
+        &self,
+        message: &str,
+        update_ref: Option<&str>,
+        options: &CommitOptions,
     ) -> Result<Oid, GitUtilsErrorCode> {
         // 如果没有指定更新的分支，默认更新当前分支
-        let update_ref = if update_ref.is_none() {
-            Some("HEAD")
-        } else {
-            update_ref
-        };
+        let update_ref = update_ref.unwrap_or("HEAD");
         let repo = &self.repository;
         if repo.is_bare() {
             return Err(GitUtilsErrorCode::RepoIsBare(
                 repo.path().display().to_string(),
             ));
         }
+        let hooks = HookRunner::new(repo)?;
+        if !options.no_verify {
+            hooks.run("pre-commit", &[], None)?;
+        }
         let mut index = repo.index()?;
+        // pre-commit 钩子可能修改了暂存区，重新读取
+        index.read(false)?;
         let conflicts = index.conflicts()?;
         if conflicts.into_iter().count() > 0 {
             return Err(GitUtilsErrorCode::RepoHasConflicts(
                 repo.path().display().to_string(),
             ));
         }
-        let author = repo.signature();
-        if author.is_err() {
-            return Err(GitUtilsErrorCode::RepoAuthorNoConfig);
+        let committer = repo
+            .signature()
+            .map_err(|_| GitUtilsErrorCode::RepoAuthorNoConfig)?;
+        let author = match &options.author {
+            Some(author) => Some(Signature::now(&author.name, &author.email)?),
+            None => None,
+        };
+        let head_commit = self.head_commit_inner()?;
+        if options.amend && head_commit.is_none() {
+            return Err(GitUtilsErrorCode::NoCommitToAmend);
         }
-        if self.staged_files()?.len() == 0 {
+        let tree = match &options.only_paths {
+            Some(paths) => self.only_paths_tree(&index, head_commit.as_ref(), paths)?,
+            None => repo.find_tree(index.write_tree()?)?,
+        };
+        // 树和父提交的树一致，说明没有需要提交的修改
+        let unchanged = match &head_commit {
+            Some(head_commit) => head_commit.tree_id() == tree.id(),
+            None => tree.is_empty(),
+        };
+        if !options.amend && unchanged && !options.allow_empty {
             return Err(GitUtilsErrorCode::NoStagedFile);
         }
-        let author = author.unwrap();
-        let tree_oid = index.write_tree()?;
-        let tree = repo.find_tree(tree_oid)?;
-        let parents = &[&repo.head()?.peel_to_commit()?];
+        // 修改提交时没有新的提交信息，沿用原提交信息
+        let message = match &head_commit {
+            Some(head_commit) if options.amend && message.trim().is_empty() => {
+                String::from_utf8_lossy(head_commit.message_raw_bytes()).to_string()
+            }
+            _ => message.to_string(),
+        };
+        let message = hooks.run_commit_msg_hooks(&message, options.amend, !options.no_verify)?;
+        if !options.no_verify && conventional_commits_enabled(repo) {
+            validate_conventional_commit(repo, &message)?;
+        }
+        let sign_config = SignConfig::from_repo(repo)?;
+        let sign = options.sign.unwrap_or(sign_config.enabled);
+        let title = message.lines().next().unwrap_or("");
+        if let Some(head_commit) = head_commit.as_ref().filter(|_| options.amend) {
+            if !sign {
+                let oid = head_commit.amend(
+                    Some(update_ref),
+                    author.as_ref(),
+                    Some(&committer),
+                    None,
+                    Some(&message),
+                    Some(&tree),
+                )?;
+                return Ok(oid);
+            }
+            let author = author.unwrap_or_else(|| head_commit.author().to_owned());
+            let parents = head_commit.parents().collect::<Vec<_>>();
+            let parents = parents.iter().collect::<Vec<_>>();
+            let buffer =
+                repo.commit_create_buffer(&author, &committer, &message, &tree, &parents)?;
+            let reflog = format!("commit (amend): {}", title);
+            return self.write_signed_commit(&sign_config, update_ref, &buffer, &reflog);
+        }
+        let author = author.unwrap_or_else(|| committer.clone());
+        let parents = head_commit.iter().collect::<Vec<_>>();
+        if sign {
+            let buffer =
+                repo.commit_create_buffer(&author, &committer, &message, &tree, &parents)?;
+            let reflog = if parents.is_empty() {
+                format!("commit (initial): {}", title)
+            } else {
+                format!("commit: {}", title)
+            };
+            return self.write_signed_commit(&sign_config, update_ref, &buffer, &reflog);
+        }
         // 创建提交
-        let oid = repo.commit(update_ref, &author, &author, message, &tree, parents)?;
-        return Ok(oid);
+        let oid = repo.commit(
+            Some(update_ref),
+            &author,
+            &committer,
+            &message,
+            &tree,
+            &parents,
+        )?;
+        Ok(oid)
     }
 
-    pub fn has_tracking(&self, branch: &git2::Branch) -> bool {
-        let tracking = branch.upstream();
-        match tracking {
-            Ok(_) => true,
-            Err(_) => false,
+    /// 对提交内容签名并写入仓库，然后更新引用
+    /// update_ref 为符号引用(如HEAD)时，更新它指向的分支
+    fn write_signed_commit(
+        &self,
+        sign_config: &SignConfig,
+        update_ref: &str,
+        buffer: &[u8],
+        reflog: &str,
+    ) -> Result<Oid, GitUtilsErrorCode> {
+        let repo = &self.repository;
+        let signature = sign_config.sign(buffer)?;
+        let content = String::from_utf8_lossy(buffer);
+        let oid = repo.commit_signed(&content, &signature, None)?;
+        let target = repo
+            .find_reference(update_ref)
+            .ok()
+            .and_then(|r| r.symbolic_target().map(|t| t.to_string()))
+            .unwrap_or(update_ref.to_string());
+        repo.reference(&target, oid, true, reflog)?;
+        Ok(oid)
+    }
+
+    /// 获取HEAD指向的提交，HEAD未出生(空仓库还没有提交)时返回None
+    fn head_commit_inner(&self) -> Result<Option<git2::Commit<'_>>, GitUtilsErrorCode> {
+        match self.repository.head() {
+            Ok(head) => Ok(Some(head.peel_to_commit()?)),
+            Err(e)
+                if e.code() == git2::ErrorCode::UnbornBranch
+                    || e.code() == git2::ErrorCode::NotFound =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(e.into()),
         }
     }
 
-    fn build_remote_credentials_cb(
+    /// 以HEAD的树为基础，只写入指定路径(文件或文件夹)在暂存区中的内容，
+    /// 暂存区中已经不存在的路径视为删除
+    fn only_paths_tree(
         &self,
-        credentials: &Option<(String, String)>,
-    ) -> impl Fn(&str, Option<&str>, CredentialType) -> Result<Cred, git2::Error> + '_ {
-        let (username, password) = credentials
-            .as_ref()
-            .map(|(u, p)| (u.clone(), p.clone()))
-            .unwrap_or_default();
-        move |_: &str, username_from_url: Option<&str>, allowed_types: CredentialType| {
-            if let CredentialType::USER_PASS_PLAINTEXT = allowed_types {
-                if username.is_empty() || password.is_empty() {
-                    return Err(git2::Error::new(
-                        git2::ErrorCode::User,
-                        git2::ErrorClass::None,
-                        "Credentials required for remote operation",
-                    ));
+        index: &git2::Index,
+        head_commit: Option<&git2::Commit>,
+        paths: &[String],
+    ) -> Result<Tree<'_>, GitUtilsErrorCode> {
+        let repo = &self.repository;
+        let mut partial = git2::Index::new()?;
+        if let Some(head_commit) = head_commit {
+            partial.read_tree(&head_commit.tree()?)?;
+        }
+        for path in paths {
+            let path = path.replace("\\", "/");
+            let path = path.trim_end_matches('/');
+            let prefix = format!("{}/", path);
+            let _ = partial.remove_path(Path::new(path));
+            let _ = partial.remove_dir(Path::new(path), 0);
+            for entry in index.iter() {
+                let entry_path = String::from_utf8_lossy(&entry.path).to_string();
+                if entry_path == path || entry_path.starts_with(&prefix) {
+                    partial.add(&entry)?;
                 }
-                return Cred::userpass_plaintext(&username, &password);
             }
+        }
+        let tree_id = partial.write_tree_to(repo)?;
+        Ok(repo.find_tree(tree_id)?)
+    }
+
+    pub fn has_tracking(&self, branch: &git2::Branch) -> bool {
+        let tracking = branch.upstream();
+        match tracking {
+            Ok(_) => true,
+            Err(_) => false,
+        }
+    }
+
+    /// 创建远程操作的凭证会话，credentials 为调用方直接传入的用户名密码
+    fn credential_session(
+        &self,
+        credentials: &Option<(String, String)>,
+    ) -> RefCell<CredentialSession> {
+        let workdir = self.repository.workdir().unwrap_or(self.repository.path());
+        RefCell::new(CredentialSession::new(workdir, credentials))
+    }
 
-            match allowed_types {
-                CredentialType::SSH_KEY => {
-                    Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"))
+    /// 在 path 中初始化仓库，path 不存在时会被创建
+    pub fn init_repo<P: AsRef<Path>>(
+        path: P,
+        options: &init::InitOptions,
+    ) -> Result<Self, GitUtilsErrorCode> {
+        let path = path.as_ref();
+        if Repository::open(path).is_ok() {
+            return Err(GitUtilsErrorCode::RepoAlreadyExists(
+                path.display().to_string(),
+            ));
+        }
+        let gitignore =
+            match options.gitignore.as_deref().filter(|g| !g.is_empty()) {
+                Some(name) => Some(init::gitignore_template(name).ok_or_else(|| {
+                    GitUtilsErrorCode::GitignoreTemplateNotFound(name.to_string())
+                })?),
+                None => None,
+            };
+        let mut init_opt = git2::RepositoryInitOptions::new();
+        init_opt.mkpath(true);
+        if let Some(branch) = options.initial_branch.as_deref().filter(|b| !b.is_empty()) {
+            if !git2::Branch::name_is_valid(branch)? {
+                return Err(GitUtilsErrorCode::InvalidBranchName(branch.to_string()));
+            }
+            init_opt.initial_head(branch);
+        }
+        let repo = Repository::init_opts(path, &init_opt)?;
+        let provider = GitDataProvider { repository: repo };
+
+        let gitignore_path = path.join(".gitignore");
+        if let Some(content) = gitignore
+            && !gitignore_path.exists()
+        {
+            std::fs::write(&gitignore_path, content)?;
+        }
+        if let Some(message) = options.initial_commit.as_deref().filter(|m| !m.is_empty()) {
+            let mut index = provider.repository.index()?;
+            index.add_all(["*"], git2::IndexAddOption::DEFAULT, None)?;
+            index.write()?;
+            let commit_opt = CommitOptions {
+                allow_empty: true,
+                ..Default::default()
+            };
+            provider.commit_with_options(message, None, &commit_opt)?;
+        }
+        Ok(provider)
+    }
+
+    /// 克隆远程仓库到 path，path 必须不存在或者是空目录
+    ///
+    /// 传输进度通过 on_progress 回调(最多每100ms一次，结束时再回调一次)，
+    /// cancel 被取消时中断克隆并删除已经克隆的内容
+    pub fn clone_repo<P: AsRef<Path>>(
+        url: &str,
+        path: P,
+        options: &clone::CloneOptions,
+        credentials: Option<(String, String)>,
+        cancel: &CancelToken,
+        mut on_progress: impl FnMut(TransferProgress),
+    ) -> Result<Self, GitUtilsErrorCode> {
+        cancel.check()?;
+        let path = path.as_ref();
+        let existed = path.exists();
+        if existed && (!path.is_dir() || path.read_dir()?.next().is_some()) {
+            return Err(GitUtilsErrorCode::DestinationNotEmpty(
+                path.display().to_string(),
+            ));
+        }
+        // 目标目录还不存在，git credential 在它的上级目录中执行
+        let workdir = path
+            .ancestors()
+            .find(|p| p.is_dir())
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let session = RefCell::new(CredentialSession::new(workdir, &credentials));
+        let build_callbacks = || {
+            let mut cbs = RemoteCallbacks::new();
+            cbs.credentials(|url, username, allowed| {
+                session.borrow_mut().credential(url, username, allowed)
+            });
+            cbs
+        };
+
+        let mut last_emit = std::time::Instant::now();
+        let mut last_progress = TransferProgress::default();
+        let mut cbs = build_callbacks();
+        cbs.transfer_progress(|progress| {
+            if cancel.is_cancelled() {
+                return false;
+            }
+            last_progress = TransferProgress::new("origin", &progress);
+            if last_emit.elapsed() >= std::time::Duration::from_millis(100) {
+                last_emit = std::time::Instant::now();
+                on_progress(last_progress.clone());
+            }
+            true
+        });
+        let mut fetch_opt = FetchOptions::new();
+        fetch_opt.remote_callbacks(cbs);
+        if let Some(depth) = options.depth.filter(|d| *d > 0) {
+            fetch_opt.depth(depth);
+        }
+        let mut builder = git2::build::RepoBuilder::new();
+        builder.bare(options.bare).fetch_options(fetch_opt);
+        if let Some(branch) = options.branch.as_deref().filter(|b| !b.is_empty()) {
+            builder.branch(branch);
+        }
+        let ret = builder.clone(url, path);
+        session.borrow_mut().finish(ret.is_ok());
+        // 释放回调中对 on_progress 的借用
+        drop(builder);
+        let cleanup = || {
+            if existed {
+                for entry in path.read_dir().into_iter().flatten().flatten() {
+                    let _ = std::fs::remove_dir_all(entry.path())
+                        .or_else(|_| std::fs::remove_file(entry.path()));
+                }
+            } else {
+                let _ = std::fs::remove_dir_all(path);
+            }
+        };
+        let repo = ret.map_err(|e| {
+            cleanup();
+            if cancel.is_cancelled() {
+                return GitUtilsErrorCode::OperationCancelled;
+            }
+            log::error!("Clone error: {:?}", e);
+            GitUtilsErrorCode::from_remote(&e, GitUtilsErrorCode::CloneError)
+        })?;
+        if options.recurse_submodules && !options.bare {
+            update_submodules(repo.submodules()?, &build_callbacks, true).map_err(|e| {
+                log::error!("Update submodules error: {:?}", e);
+                GitUtilsErrorCode::from_remote(&e, GitUtilsErrorCode::CloneError)
+            })?;
+        }
+        last_progress.remote = "origin".to_string();
+        last_progress.done = true;
+        on_progress(last_progress);
+        Ok(GitDataProvider { repository: repo })
+    }
+
+    /// 拉取远程更新，remote为空时拉取所有远程，使用远程配置的refspec
+    /// 传输进度通过 on_progress 回调(最多每100ms一次，结束时再回调一次)，
+    /// cancel 被取消时中断传输并返回 OperationCancelled
+    pub fn fetch(
+        &self,
+        remote: Option<&str>,
+        options: &fetch::FetchOptions,
+        credentials: Option<(String, String)>,
+        cancel: &CancelToken,
+        mut on_progress: impl FnMut(TransferProgress),
+    ) -> Result<(), GitUtilsErrorCode> {
+        let repo = &self.repository;
+        let remotes = match remote {
+            Some(remote) => vec![remote.to_string()],
+            None => repo.remotes()?.iter().flatten().map(String::from).collect(),
+        };
+        let session = self.credential_session(&credentials);
+        for name in remotes.iter() {
+            cancel.check()?;
+            let mut remote = repo
+                .find_remote(name)
+                .map_err(|_| GitUtilsErrorCode::RemoteNotFound(name.to_string()))?;
+            let mut last_emit = std::time::Instant::now();
+            let mut cbs = RemoteCallbacks::new();
+            cbs.credentials(|url, username, allowed| {
+                session.borrow_mut().credential(url, username, allowed)
+            });
+            cbs.transfer_progress(|progress| {
+                if cancel.is_cancelled() {
+                    return false;
+                }
+                if last_emit.elapsed() >= std::time::Duration::from_millis(100) {
+                    last_emit = std::time::Instant::now();
+                    on_progress(TransferProgress::new(name, &progress));
                 }
-                _ => Cred::default(),
+                true
+            });
+            let mut fetch_opt = FetchOptions::new();
+            fetch_opt.remote_callbacks(cbs);
+            if options.prune {
+                fetch_opt.prune(git2::FetchPrune::On);
+            }
+            match options.tags {
+                Some(true) => fetch_opt.download_tags(git2::AutotagOption::All),
+                Some(false) => fetch_opt.download_tags(git2::AutotagOption::None),
+                None => fetch_opt.download_tags(git2::AutotagOption::Auto),
+            };
+            if let Some(depth) = options.depth.filter(|d| *d > 0) {
+                fetch_opt.depth(depth);
             }
+            let ret = remote.fetch::<&str>(&[], Some(&mut fetch_opt), None);
+            session.borrow_mut().finish(ret.is_ok());
+            ret.map_err(|e| {
+                if cancel.is_cancelled() {
+                    return GitUtilsErrorCode::OperationCancelled;
+                }
+                log::error!("Fetch error: {:?}", e);
+                GitUtilsErrorCode::from_remote(&e, GitUtilsErrorCode::FetchError)
+            })?;
+            // 释放回调中对 on_progress 的借用
+            drop(fetch_opt);
+            let mut progress = TransferProgress::new(name, &remote.stats());
+            progress.done = true;
+            on_progress(progress);
         }
+        Ok(())
     }
 
+    /// 推送本地分支到远程
+    ///
+    /// 先读取远程分支当前指向的提交：普通推送要求本地分支包含该提交，Force 不做检查；
+    /// ForceWithLease 在推送的连接上要求远程分支与本地的远程跟踪分支一致，
+    /// 读取之后远程分支被其他人更新时也不会覆盖
     pub fn push(
         &self,
         remote: &str,
         branch_name: &str,
         credentials: Option<(String, String)>,
+        options: &push::PushOptions,
     ) -> Result<(), GitUtilsErrorCode> {
         let repo = &self.repository;
 
         // 提取远程获取和分支验证逻辑
         let mut remote = repo
             .find_remote(remote)
-            .map_err(|_| GitUtilsErrorCode::RemoteNotFound(branch_name.to_string()))?;
+            .map_err(|_| GitUtilsErrorCode::RemoteNotFound(remote.to_string()))?;
+        let remote_name = remote.name().unwrap_or_default().to_string();
 
         let branch = repo
             .find_branch(branch_name, BranchType::Local)
             .map_err(|_| GitUtilsErrorCode::BranchNotFound(branch_name.to_string()))?;
+        let branch_ref = branch.into_reference();
+        let branch_ref_name = branch_ref.name().ok_or(anyhow!(""))?.to_string();
+        let local_commit = branch_ref.peel_to_commit()?;
 
-        // 验证分支跟踪状态
-        if !self.has_tracking(&branch) {
+        // 远程分支名：指定的分支名 > 同一个远程上的上游分支 > 本地分支名
+        let upstream_branch = self.upstream_branch_name(&branch_ref_name, &remote_name);
+        if options.remote_branch.is_none() && upstream_branch.is_none() && !options.set_upstream {
             return Err(GitUtilsErrorCode::BranchNotTrackAny(
                 branch_name.to_string(),
             ));
         }
+        let remote_branch = options
+            .remote_branch
+            .clone()
+            .or(upstream_branch)
+            .unwrap_or_else(|| branch_name.to_string());
+        let remote_ref_name = format!("refs/heads/{}", remote_branch);
+        let tracking_ref_name = format!("refs/remotes/{}/{}", remote_name, remote_branch);
 
         // 提取公共回调配置
+        let session = self.credential_session(&credentials);
         let build_callbacks = || {
             let mut cbs = RemoteCallbacks::new();
-            cbs.credentials(self.build_remote_credentials_cb(&credentials));
+            cbs.credentials(|url, username, allowed| {
+                session.borrow_mut().credential(url, username, allowed)
+            });
             cbs
         };
-
-        // 统一错误处理函数
-        let handle_error = |e: git2::Error| {
-            log::error!("Git operation error: {:?}", e);
-            match e.code() {
-                git2::ErrorCode::User => GitUtilsErrorCode::PushNeedNameAndPassword,
-                _ => return GitUtilsErrorCode::PushOtherError.into(),
-            }
+        let remote_error = |e: git2::Error| {
+            log::error!("Push error: {:?}", e);
+            GitUtilsErrorCode::from_remote(&e, GitUtilsErrorCode::PushError)
         };
 
-        // 获取必要分支信息
-        let remote_branch = branch.upstream()?;
-        let branch_ref = branch.into_reference();
-        let branch_ref_name = branch_ref.name().ok_or(anyhow!(""))?;
-
-        // 执行fetch操作
-        let mut fetch_opt = FetchOptions::new();
-        fetch_opt.remote_callbacks(build_callbacks());
-        remote
-            .fetch(&[branch_ref_name], Some(&mut fetch_opt), None)
-            .map_err(handle_error)?;
+        // 读取远程分支当前指向的提交
+        let ret = remote
+            .connect_auth(Direction::Push, Some(build_callbacks()), None)
+            .and_then(|connection| {
+                Ok(connection
+                    .list()?
+                    .iter()
+                    .find(|head| head.name() == remote_ref_name)
+                    .map(|head| head.oid()))
+            });
+        session.borrow_mut().finish(ret.is_ok());
+        let remote_head = ret.map_err(remote_error)?;
 
         // 验证祖先关系
-        let remote_commit = remote_branch.into_reference().peel_to_commit()?;
-        let remote_head = remote_commit.id();
-        let local_commit = branch_ref.peel_to_commit()?;
-        if !local_commit.parent_ids().any(|id| id == remote_head) {
-            return Err(GitUtilsErrorCode::RemoteHeadHasNotInLocal.into());
+        match (options.mode, remote_head) {
+            (push::PushMode::Normal, Some(remote_head)) if remote_head != local_commit.id() => {
+                if repo.find_commit(remote_head).is_err() {
+                    return Err(GitUtilsErrorCode::RemoteHeadHasNotInLocal);
+                }
+                if !repo.graph_descendant_of(local_commit.id(), remote_head)? {
+                    return Err(GitUtilsErrorCode::NonFastForward(remote_ref_name));
+                }
+            }
+            _ => {}
         }
 
-        // 执行push操作
+        // 执行 pre-push 钩子，标准输入为 "<本地引用> <本地sha> <远程引用> <远程sha>"
+        if !options.no_verify {
+            let remote_url = remote.url().unwrap_or_default().to_string();
+            let stdin = format!(
+                "{} {} {} {}\n",
+                branch_ref_name,
+                local_commit.id(),
+                remote_ref_name,
+                remote_head.unwrap_or_else(Oid::zero)
+            );
+            HookRunner::new(repo)?.run(
+                "pre-push",
+                &[&remote_name, &remote_url],
+                Some(stdin.as_bytes()),
+            )?;
+        }
+
+        // 执行push操作，服务端拒绝的引用通过 push_update_reference 返回
+        let force = if options.mode == push::PushMode::Normal {
+            ""
+        } else {
+            "+"
+        };
+        let refspec = format!("{}{}:{}", force, branch_ref_name, remote_ref_name);
+        let remote_message = RefCell::new(String::new());
+        let mut rejected = None;
+        let lease = (options.mode == push::PushMode::ForceWithLease)
+            .then(|| repo.refname_to_id(&tracking_ref_name).ok());
+        let mut stale = false;
+        let mut cbs = build_callbacks();
+        // 在推送的连接上比较远程分支，避免检查之后、推送之前远程分支被其他人更新
+        cbs.push_negotiation(|updates| {
+            let Some(expected) = lease else {
+                return Ok(());
+            };
+            for update in updates {
+                if update.dst_refname() != Some(remote_ref_name.as_str()) {
+                    continue;
+                }
+                let current = Some(update.src()).filter(|id| !id.is_zero());
+                if current != expected {
+                    stale = true;
+                    return Err(git2::Error::from_str("stale remote ref"));
+                }
+            }
+            Ok(())
+        });
+        cbs.sideband_progress(|data| {
+            remote_message
+                .borrow_mut()
+                .push_str(&String::from_utf8_lossy(data));
+            true
+        });
+        cbs.push_update_reference(|refname, status| {
+            if let Some(status) = status {
+                rejected = Some((refname.to_string(), status.to_string()));
+            }
+            Ok(())
+        });
         let mut push_opt = PushOptions::new();
-        push_opt.remote_callbacks(build_callbacks());
-        remote
-            .push(&[branch_ref_name], Some(&mut push_opt))
-            .map_err(handle_error)?;
+        push_opt.remote_callbacks(cbs);
+        let ret = remote.push(&[&refspec], Some(&mut push_opt));
+        session.borrow_mut().finish(ret.is_ok());
+        drop(push_opt);
+        if stale {
+            return Err(GitUtilsErrorCode::StaleRemoteRef(remote_ref_name));
+        }
+        ret.map_err(remote_error)?;
+        if let Some((refname, status)) = rejected {
+            log::error!("Push rejected: {} {}", refname, status);
+            return Err(GitUtilsErrorCode::from_push_status(
+                &refname,
+                &status,
+                &remote_message.borrow(),
+            ));
+        }
+
+        if options.set_upstream {
+            // 远程没有配置对应的 fetch refspec 时不会自动更新远程跟踪分支
+            if repo.find_reference(&tracking_ref_name).is_err() {
+                repo.reference(&tracking_ref_name, local_commit.id(), true, "push")?;
+            }
+            let mut branch = repo.find_branch(branch_name, BranchType::Local)?;
+            branch.set_upstream(Some(&format!("{}/{}", remote_name, remote_branch)))?;
+        }
         Ok(())
     }
 
+    /// 拉取远程分支并整合到本地分支
+    ///
+    /// 整合方式由 options.strategy 或 pull.rebase、pull.ff 配置决定，
+    /// 检出时不会覆盖工作区中未提交的修改，非当前分支只能快进
     pub fn pull(
         &self,
         remote: &str,
         branch: &str,
         credentials: Option<(String, String)>,
+        options: &pull::PullOptions,
     ) -> Result<(), GitUtilsErrorCode> {
         let repo = &self.repository;
 
@@ -989,83 +2162,253 @@ impl GitDataProvider {
             log::error!("Find remote error: {:?}", e);
             GitUtilsErrorCode::RemoteNotFound(remote.to_string())
         })?;
+        let remote_name = remote.name().unwrap_or_default().to_string();
+        let remote_url = remote.url().unwrap_or_default().to_string();
+        // 获取本地分支
+        let local_branch = repo
+            .find_branch(branch, BranchType::Local)
+            .map_err(|_| GitUtilsErrorCode::BranchNotFound(branch.to_string()))?;
+        let is_head = local_branch.is_head();
+        let mut local_branch_ref = local_branch.into_reference();
+        let local_ref_name = local_branch_ref.name().ok_or(anyhow!(""))?.to_string();
+        let remote_branch = self
+            .upstream_branch_name(&local_ref_name, &remote_name)
+            .unwrap_or_else(|| branch.to_string());
+
         // 2. 配置回调（复用已有的凭证处理逻辑）
-        let callbacks = self.build_remote_credentials_cb(&credentials);
+        let session = self.credential_session(&credentials);
         // 3. 执行 fetch 操作
         let mut fetch_opts = FetchOptions::new();
         fetch_opts.remote_callbacks({
             let mut cb = RemoteCallbacks::new();
-            cb.credentials(callbacks);
+            cb.credentials(|url, username, allowed| {
+                session.borrow_mut().credential(url, username, allowed)
+            });
             cb
         });
-        // 获取本地分支
-        let local_branch = repo
-            .find_branch(branch, BranchType::Local)
-            .map_err(|_| GitUtilsErrorCode::BranchNotFound(branch.to_string()))?;
-        let local_branch_ref = local_branch.into_reference();
 
         // 4. 获取FETCH_HEAD 提交
-        remote
-            .fetch(
-                &[local_branch_ref.name().unwrap()],
-                Some(&mut fetch_opts),
-                None,
-            )
-            .map_err(|e| {
-                log::error!("Fetch error: {:?}", e);
-                GitUtilsErrorCode::PushOtherError
-            })?;
+        let ret = remote.fetch(
+            &[format!("refs/heads/{}", remote_branch)],
+            Some(&mut fetch_opts),
+            None,
+        );
+        session.borrow_mut().finish(ret.is_ok());
+        ret.map_err(|e| {
+            log::error!("Fetch error: {:?}", e);
+            GitUtilsErrorCode::from_remote(&e, GitUtilsErrorCode::FetchError)
+        })?;
         let fetch_head = repo.find_reference("FETCH_HEAD")?;
         let fetch_commit = repo.reference_to_annotated_commit(&fetch_head)?;
-        // 5. 执行合并操作
-        let analysis = repo.merge_analysis(&[&fetch_commit])?;
-        if analysis.0.is_up_to_date() {
+
+        // 5. 根据拉取方式整合远程提交
+        let config = repo.config()?;
+        let strategy = options
+            .strategy
+            .unwrap_or_else(|| pull::PullStrategy::from_config(&config));
+        let (analysis, _) = repo.merge_analysis_for_ref(&local_branch_ref, &[&fetch_commit])?;
+        if analysis.is_up_to_date() {
             return Ok(());
-        } else if analysis.0.is_fast_forward() {
-            let mut reference = local_branch_ref;
-            /*
-             * set_target 核心功能：
-             * 原子性更新引用：将本地分支引用指向 fetch_commit.id() 对应的提交
-             * 实现快进合并：当远程分支是本地分支的直接祖先时，直接移动分支指针
-             * 写入引用日志：第二个参数 "Fast-Forward" 会写入 .git/logs 中的引用日志
-             */
-            let _ = reference
-                .set_target(fetch_commit.id(), "Fast-Forward")
-                .map_err(|e| {
-                    log::error!("Set target error: {:?}", e);
-                    GitUtilsErrorCode::TargetReferenceNotDirect
-                })?;
-            repo.checkout_head(Some(CheckoutBuilder::default().force()))?;
+        }
+        if !analysis.is_fast_forward() && !analysis.is_normal() {
+            return Err(GitUtilsErrorCode::CantPull);
+        }
+        if !analysis.is_fast_forward() && strategy == pull::PullStrategy::FastForwardOnly {
+            return Err(GitUtilsErrorCode::CannotFastForward(branch.to_string()));
+        }
+        if !is_head {
+            // 非当前分支不需要检出，只移动分支指针
+            if !analysis.is_fast_forward() {
+                return Err(GitUtilsErrorCode::CannotFastForward(branch.to_string()));
+            }
+            local_branch_ref.set_target(fetch_commit.id(), "pull: Fast-forward")?;
             return Ok(());
-        } else if analysis.0.is_normal() {
-            // 创建提交的合并
-            let head_commit = repo.head()?.peel_to_commit()?;
-            repo.merge(&[&fetch_commit], None, None).map_err(|e| {
+        }
+        let no_ff =
+            strategy == pull::PullStrategy::Merge && config.get_bool("pull.ff").ok() == Some(false);
+        let autostash = options.autostash.unwrap_or_else(|| {
+            let key = match strategy {
+                pull::PullStrategy::Rebase => "rebase.autoStash",
+                _ => "merge.autoStash",
+            };
+            config.get_bool(key).unwrap_or(false)
+        });
+        let stashed = autostash && self.has_tracked_changes()?;
+        if stashed {
+            self.autostash_save()?;
+        }
+
+        let ret = if analysis.is_fast_forward() && !no_ff {
+            self.fast_forward(&local_ref_name, &fetch_commit)
+        } else if strategy == pull::PullStrategy::Rebase {
+            let local_commit = repo.reference_to_annotated_commit(&local_branch_ref)?;
+            self.rebase_onto(&local_commit, &fetch_commit)
+        } else {
+            // 与 git 的默认合并信息一致，合并到 master/main 以外的分支时注明目标分支
+            let mut message = format!("Merge branch '{}' of {}", remote_branch, remote_url);
+            if branch != "master" && branch != "main" {
+                message = format!("{} into {}", message, branch);
+            }
+            self.merge_commit(&fetch_commit, &message)
+        };
+        if stashed {
+            // 拉取失败时也恢复暂存的修改，恢复失败时修改保留在 stash 中
+            let popped = self.autostash_pop();
+            return ret.and(popped);
+        }
+        ret
+    }
+
+    /// 本地分支在 remote 上的上游分支名，上游分支不在 remote 上时返回None
+    fn upstream_branch_name(&self, branch_ref_name: &str, remote_name: &str) -> Option<String> {
+        let repo = &self.repository;
+        let upstream_remote = repo.branch_upstream_remote(branch_ref_name).ok()?;
+        if upstream_remote.as_str() != Some(remote_name) {
+            return None;
+        }
+        let merge = repo.branch_upstream_merge(branch_ref_name).ok()?;
+        merge
+            .as_str()
+            .and_then(|m| m.strip_prefix("refs/heads/"))
+            .map(String::from)
+    }
+
+    /// 工作区或暂存区中是否有已跟踪文件的修改
+    fn has_tracked_changes(&self) -> Result<bool, GitUtilsErrorCode> {
+        let mut opts = git2::StatusOptions::new();
+        opts.include_untracked(false).include_ignored(false);
+        Ok(!self.repository.statuses(Some(&mut opts))?.is_empty())
+    }
+
+    /// 暂存工作区的修改，stash 需要可变的 Repository，所以重新打开一个
+    fn autostash_save(&self) -> Result<(), GitUtilsErrorCode> {
+        let mut repo = Repository::open(self.repository.path())?;
+        let signature = repo
+            .signature()
+            .map_err(|_| GitUtilsErrorCode::UserUnConfigured)?;
+        repo.stash_save(&signature, "autostash", None)?;
+        self.repository.index()?.read(false)?;
+        Ok(())
+    }
+
+    /// 恢复 autostash_save 暂存的修改，有冲突时保留 stash
+    fn autostash_pop(&self) -> Result<(), GitUtilsErrorCode> {
+        let mut repo = Repository::open(self.repository.path())?;
+        let mut opts = git2::StashApplyOptions::new();
+        opts.reinstantiate_index();
+        repo.stash_apply(0, Some(&mut opts)).map_err(|e| {
+            log::error!("Apply autostash error: {:?}", e);
+            match e.code() {
+                git2::ErrorCode::Conflict | git2::ErrorCode::MergeConflict => {
+                    GitUtilsErrorCode::AutostashConflict
+                }
+                _ => e.into(),
+            }
+        })?;
+        self.repository.index()?.read(false)?;
+        if repo.index()?.has_conflicts() {
+            return Err(GitUtilsErrorCode::AutostashConflict);
+        }
+        repo.stash_drop(0)?;
+        Ok(())
+    }
+
+    /// 快进当前分支，检出时如果会覆盖未提交的修改则失败
+    fn fast_forward(
+        &self,
+        ref_name: &str,
+        target: &git2::AnnotatedCommit,
+    ) -> Result<(), GitUtilsErrorCode> {
+        let repo = &self.repository;
+        let commit = repo.find_commit(target.id())?;
+        repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))
+            .map_err(checkout_error)?;
+        repo.find_reference(ref_name)?
+            .set_target(commit.id(), "pull: Fast-forward")
+            .map_err(|e| {
+                log::error!("Set target error: {:?}", e);
+                GitUtilsErrorCode::TargetReferenceNotDirect
+            })?;
+        Ok(())
+    }
+
+    /// 合并到当前分支并创建合并提交，有冲突时保留合并状态
+    fn merge_commit(
+        &self,
+        target: &git2::AnnotatedCommit,
+        message: &str,
+    ) -> Result<(), GitUtilsErrorCode> {
+        let repo = &self.repository;
+        let head_commit = repo.head()?.peel_to_commit()?;
+        repo.merge(&[target], None, Some(CheckoutBuilder::new().safe()))
+            .map_err(|e| {
                 log::error!("Merge error: {:?}", e);
-                GitUtilsErrorCode::CommitBeforePullWouldBeOverwrittenByMerge
+                let _ = repo.cleanup_state();
+                checkout_error(e)
             })?;
-            // 创建合并提交
-            let signature = repo
-                .signature()
-                .map_err(|_| GitUtilsErrorCode::UserUnConfigured)?;
-            let tree_id = repo.index()?.write_tree()?;
-            let tree = repo.find_tree(tree_id)?;
-            let _ = repo
-                .commit(
-                    Some("HEAD"),
-                    &signature,
-                    &signature,
-                    "Merge commit",
-                    &tree,
-                    &[&head_commit, &fetch_head.peel_to_commit()?],
-                )
-                .map_err(|e| {
-                    log::error!("Commit error: {:?}", e);
-                    GitUtilsErrorCode::BuildMergeCommitError
-                })?;
-        } else if analysis.0.is_none() {
-            return Err(GitUtilsErrorCode::CantPull.into());
+        let mut index = repo.index()?;
+        if index.has_conflicts() {
+            // 解决冲突后提交时使用的合并信息
+            std::fs::write(repo.path().join("MERGE_MSG"), format!("{}\n", message))?;
+            return Err(GitUtilsErrorCode::RepoHasConflicts(conflict_paths(&index)?));
         }
+        // 创建合并提交
+        let signature = repo
+            .signature()
+            .map_err(|_| GitUtilsErrorCode::UserUnConfigured)?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &[&head_commit, &repo.find_commit(target.id())?],
+        )
+        .map_err(|e| {
+            log::error!("Commit error: {:?}", e);
+            GitUtilsErrorCode::BuildMergeCommitError
+        })?;
+        repo.cleanup_state()?;
+        Ok(())
+    }
+
+    /// 把当前分支变基到 upstream 之上，有冲突时放弃变基
+    fn rebase_onto(
+        &self,
+        local: &git2::AnnotatedCommit,
+        upstream: &git2::AnnotatedCommit,
+    ) -> Result<(), GitUtilsErrorCode> {
+        let repo = &self.repository;
+        if self.has_tracked_changes()? {
+            return Err(GitUtilsErrorCode::RebaseUncommittedChanges);
+        }
+        let signature = repo
+            .signature()
+            .map_err(|_| GitUtilsErrorCode::UserUnConfigured)?;
+        let mut rebase = repo.rebase(Some(local), Some(upstream), None, None)?;
+        while let Some(operation) = rebase.next() {
+            if let Err(e) = operation {
+                let _ = rebase.abort();
+                return Err(e.into());
+            }
+            let index = repo.index()?;
+            if index.has_conflicts() {
+                let paths = conflict_paths(&index)?;
+                rebase.abort()?;
+                return Err(GitUtilsErrorCode::RepoHasConflicts(paths));
+            }
+            match rebase.commit(None, &signature, None) {
+                // 提交的修改已经在上游中，跳过
+                Err(e) if e.code() == git2::ErrorCode::Applied => {}
+                Err(e) => {
+                    let _ = rebase.abort();
+                    return Err(e.into());
+                }
+                Ok(_) => {}
+            }
+        }
+        rebase.finish(Some(&signature))?;
         Ok(())
     }
 
@@ -1279,29 +2622,6 @@ impl GitDataProvider {
                场景：子模块是 Git 中的一个特性，允许一个仓库嵌套另一个仓库。子模块的模式为 0o160000，表示它指向一个提交对象，而不是一个普通的文件或目录。
         */
         let (tree, tree_path) = self.resolve_tree(object_id, tree_path)?;
-        // let commit = self.repository.find_commit(commit_id)?;
-        // let tree = commit.tree()?;
-
-        fn find_dir<'a>(path: &str, dir_stack: &'a mut Vec<*mut fs::Dir>) -> Option<&'a mut Dir> {
-            unsafe {
-                if path.is_empty() {
-                    dir_stack.truncate(1);
-                    return dir_stack[0].as_mut();
-                } else {
-                    for i in (0..dir_stack.len()).rev() {
-                        let dir_path = (*dir_stack[i]).abs_path();
-                        // eprintln!("dir_path: {} path: {}", dir_path, path);
-                        if dir_path == path {
-                            return dir_stack[i].as_mut();
-                        }
-                        if i != 0 {
-                            dir_stack.pop();
-                        }
-                    }
-                }
-                None
-            }
-        }
         let _path = PathBuf::from(&tree_path);
         let basename = _path
             .file_name()
@@ -1313,68 +2633,398 @@ impl GitDataProvider {
             .and_then(|n| n.to_str())
             .unwrap_or_default()
             .to_string();
-        let mut root = Box::new(fs::Dir::new(dirname, basename, "0".into()));
-        let mut dir_stack: Vec<*mut fs::Dir> = vec![root.as_mut() as *mut fs::Dir];
-        let _ = tree.walk(TreeWalkMode::PreOrder, |path, entry| {
-            /*
-            拼接path，如果传入的tree_path不为None，就要拼接
-            例如：tree_path为：arch/alpha/boot，path为tools/，则path为arch/alpha/boot/tools/
-            如果tree_path为：arch/alpha/boot，path为""，则path为arch/alpha/boot/
-            如果tree_path为""，path也为""，则path为""
-            如果tree_path为""，path为"arch/"，则path为arch/
-             */
-            let path = if tree_path.is_empty() {
-                path
-            } else if path.is_empty() {
-                &format!("{}/", tree_path)
-            } else {
-                &format!("{}/{}", tree_path, path)
-            };
-            let file_mode: fs::EntryMode = entry.filemode().into();
+        let mut root = fs::Dir::new(dirname, basename, "0".into());
+        self.fill_dir(&tree, &mut root);
+        Ok(root)
+    }
+
+    /// 递归地把树中的项添加到目录中，文件大小只读取对象头，不读取文件内容
+    fn fill_dir(&self, tree: &Tree, dir: &mut fs::Dir) {
+        let odb = self.repository.odb().ok();
+        for entry in tree.iter() {
             let name = match entry.name() {
                 Some(name) => name.to_string(),
-                None => return TreeWalkResult::Skip,
+                None => continue,
             };
-            let obj = match entry.to_object(&self.repository) {
-                Ok(obj) => obj,
-                Err(_) => return TreeWalkResult::Skip,
+            let file_mode: fs::EntryMode = entry.filemode().into();
+            let object_id = entry.id().to_string();
+            if file_mode == fs::EntryMode::TREE {
+                let subtree = match self.repository.find_tree(entry.id()) {
+                    Ok(subtree) => subtree,
+                    Err(_) => continue,
+                };
+                let mut child = fs::Dir::new(dir.abs_path(), name, object_id);
+                self.fill_dir(&subtree, &mut child);
+                dir.add(fs::FsNode::Dir(child));
+            } else {
+                // 子模块的提交不在当前仓库中，会被跳过
+                let size = match odb.as_ref().map(|odb| odb.read_header(entry.id())) {
+                    Some(Ok((size, git2::ObjectType::Blob))) => size,
+                    _ => continue,
+                };
+                let file = fs::File::new(dir.abs_path(), name, object_id, size, file_mode);
+                dir.add(fs::FsNode::File(file));
+            }
+        }
+    }
+
+    /// 解析 rev 所指的树中的目录，path 为空时为根目录
+    fn tree_dir(&self, rev: &str, path: &str) -> Result<Tree<'_>, GitUtilsErrorCode> {
+        let tree = self.repository.revparse_single(rev)?.peel_to_tree()?;
+        let path = path.trim_matches('/');
+        if path.is_empty() {
+            return Ok(tree);
+        }
+        tree.get_path(Path::new(path))
+            .and_then(|entry| entry.to_object(&self.repository))
+            .and_then(|obj| obj.peel_to_tree())
+            .map_err(|_| GitUtilsErrorCode::TreeNotFound(path.to_string()))
+    }
+
+    /// 分页列出目录中的项，顺序与 git 树中的顺序一致
+    ///
+    /// cursor 为上一页返回的 cursor，为空时从第一项开始，目录中没有 cursor 时返回 InvalidCursor
+    pub fn list_tree(
+        &self,
+        rev: &str,
+        path: &str,
+        cursor: Option<&str>,
+        count: usize,
+    ) -> Result<TreePage, GitUtilsErrorCode> {
+        let tree = self.tree_dir(rev, path)?;
+        let path = path.trim_matches('/');
+        // 树中的项按名称排序，从 cursor 之后开始
+        let start = match cursor {
+            Some(cursor) => tree
+                .iter()
+                .position(|e| e.name_bytes() == cursor.as_bytes())
+                .map(|idx| idx + 1)
+                .ok_or_else(|| GitUtilsErrorCode::InvalidCursor(cursor.to_string()))?,
+            None => 0,
+        };
+        let mut entries = Vec::with_capacity(count.min(tree.len()));
+        for entry in tree.iter().skip(start).take(count) {
+            let name = String::from_utf8_lossy(entry.name_bytes()).to_string();
+            entries.push(TreeEntry {
+                path: match path {
+                    "" => name.clone(),
+                    _ => format!("{}/{}", path, name),
+                },
+                name,
+                kind: entry.filemode().into(),
+                mode: entry.filemode().into(),
+                object_id: entry.id().to_string(),
+            });
+        }
+        let cursor = (start + entries.len() < tree.len())
+            .then(|| entries.last().map(|e| e.name.clone()))
+            .flatten();
+        Ok(TreePage { entries, cursor })
+    }
+
+    /// 获取 rev 所指的提交中多个路径的大小和最后一次修改的提交，不存在的路径会被忽略
+    pub fn tree_entry_details(
+        &self,
+        rev: &str,
+        paths: &[String],
+    ) -> Result<Vec<TreeEntryDetail>, GitUtilsErrorCode> {
+        let repo = &self.repository;
+        let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+        let tree = commit.tree()?;
+        let odb = repo.odb()?;
+        let mut details = vec![];
+        for path in paths {
+            let path = path.trim_matches('/');
+            let Ok(entry) = tree.get_path(Path::new(path)) else {
+                continue;
             };
-            let object_id = obj.id().to_string();
-            // eprintln!("=======================================");
-            // eprintln!("path: {:?}, name: {:?}, file mode: {:?}", path, name, file_mode);
-            if let Some(parent_dir) = find_dir(path, &mut dir_stack) {
-                if file_mode == fs::EntryMode::TREE {
-                    let dir = Box::new(fs::Dir::new(path.into(), name, object_id));
-                    parent_dir.add(fs::FsNode::Dir(*dir));
-                    // 从parent_dir中获取新的指针，避免重复使用Box
-                    if let Some(fs::FsNode::Dir(added_dir)) = parent_dir.children.last_mut() {
-                        let new_dir_ptr = added_dir as *mut fs::Dir;
-                        dir_stack.push(new_dir_ptr);
-                    } else {
-                        eprintln!("Failed to get new directory reference for: {}", path);
-                        return TreeWalkResult::Skip;
-                    }
+            let size = match entry.kind() {
+                Some(git2::ObjectType::Blob) => Some(odb.read_header(entry.id())?.0),
+                _ => None,
+            };
+            let (dir, name) = match path.rsplit_once('/') {
+                Some((dir, name)) => (dir, name),
+                None => ("", path),
+            };
+            let last_commit = match self.tree_last_commits(commit.id(), dir)?.get(name) {
+                Some(last) => Some(build_commit(
+                    &repo.find_commit(str_to_oid(&last.commit_id)?)?,
+                    repo,
+                )),
+                None => None,
+            };
+            details.push(TreeEntryDetail {
+                path: path.to_string(),
+                size,
+                last_commit,
+            });
+        }
+        Ok(details)
+    }
+
+    /// 提交中 dir 目录下每一项最后一次修改的提交，key 为项的名称；同一个提交的同一个目录的结果会被缓存
+    ///
+    /// dir 为空时为根目录，目录不存在时返回空的结果
+    pub fn tree_last_commits(
+        &self,
+        commit_id: Oid,
+        dir: &str,
+    ) -> Result<Arc<LastCommits>, GitUtilsErrorCode> {
+        let dir = dir.trim_matches('/');
+        let key = (
+            self.repository.path().to_path_buf(),
+            commit_id,
+            dir.to_string(),
+        );
+        if let Some(commits) = LastCommit::cached(&key) {
+            return Ok(commits);
+        }
+        let commit = self.repository.find_commit(commit_id)?;
+        let commits = self
+            .last_commits_in_dir(&commit, Path::new(dir))?
+            .into_iter()
+            .map(|(name, id)| Ok((name, LastCommit::from(&self.repository.find_commit(id)?))))
+            .collect::<Result<LastCommits, GitUtilsErrorCode>>()?;
+        Ok(LastCommit::cache(key, commits))
+    }
+
+    /// 在一次历史遍历中查找 dir 目录下每一项最后一次修改的提交
+    ///
+    /// 与 git log 的默认历史简化一致：合并提交中一项与某个父提交相同时，只沿着这个父提交继续查找。
+    /// 按提交时间从新到旧只访问还有未确定的项的提交，目录没有变化的父提交不需要比较每一项
+    fn last_commits_in_dir(
+        &self,
+        commit: &git2::Commit,
+        dir: &Path,
+    ) -> Result<HashMap<String, Oid>, GitUtilsErrorCode> {
+        let repo = &self.repository;
+        fn dir_tree<'r>(
+            repo: &'r Repository,
+            commit: &git2::Commit,
+            dir: &Path,
+        ) -> Result<Option<Tree<'r>>, GitUtilsErrorCode> {
+            if dir.as_os_str().is_empty() {
+                return Ok(Some(repo.find_tree(commit.tree_id())?));
+            }
+            Ok(match commit.tree()?.get_path(dir) {
+                Ok(entry) if entry.kind() == Some(git2::ObjectType::Tree) => {
+                    Some(repo.find_tree(entry.id())?)
+                }
+                _ => None,
+            })
+        }
+        let mut result = HashMap::new();
+        let Some(tree) = dir_tree(repo, commit, dir)? else {
+            return Ok(result);
+        };
+        // 每个提交上还没有确定的项：(名称, 对象id)
+        let mut pending: HashMap<Oid, Vec<(String, Oid)>> = HashMap::new();
+        let entries = tree
+            .iter()
+            .map(|e| (String::from_utf8_lossy(e.name_bytes()).to_string(), e.id()))
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return Ok(result);
+        }
+        pending.insert(commit.id(), entries);
+        let mut queue = BinaryHeap::from([(commit.time().seconds(), commit.id())]);
+        while let Some((_, id)) = queue.pop() {
+            let Some(mut entries) = pending.remove(&id) else {
+                continue;
+            };
+            let current = repo.find_commit(id)?;
+            let tree_id = dir_tree(repo, &current, dir)?.map(|t| t.id());
+            for parent in current.parents() {
+                if entries.is_empty() {
+                    break;
+                }
+                let Some(parent_tree) = dir_tree(repo, &parent, dir)? else {
+                    continue;
+                };
+                let same = if Some(parent_tree.id()) == tree_id {
+                    std::mem::take(&mut entries)
                 } else {
-                    let blob = match obj.into_blob() {
+                    let (same, changed) = entries.into_iter().partition(|(name, id)| {
+                        parent_tree.get_name(name).map(|e| e.id()) == Some(*id)
+                    });
+                    entries = changed;
+                    same
+                };
+                if same.is_empty() {
+                    continue;
+                }
+                let waiting = pending.entry(parent.id()).or_default();
+                if waiting.is_empty() {
+                    queue.push((parent.time().seconds(), parent.id()));
+                }
+                waiting.extend(same);
+            }
+            for (name, _) in entries {
+                result.insert(name, id);
+            }
+        }
+        Ok(result)
+    }
+
+    /// 为目录中的每一项添加相对 commit_id 最后一次修改的提交
+    pub fn annotate_last_commits(
+        &self,
+        commit_id: Oid,
+        dir: &mut fs::Dir,
+    ) -> Result<(), GitUtilsErrorCode> {
+        let commits = self.tree_last_commits(commit_id, &dir.abs_path())?;
+        for child in dir.get_children_mut() {
+            let last_commit = commits.get(child.name()).cloned();
+            child.metadata_mut().last_commit = last_commit;
+        }
+        Ok(())
+    }
+
+    /// 树中所有文件的路径，用于模糊查找文件；同一棵树的结果会被缓存
+    pub fn path_index(&self, rev: &str) -> Result<Arc<PathIndex>, GitUtilsErrorCode> {
+        let tree = self.repository.revparse_single(rev)?.peel_to_tree()?;
+        let key = self.repository.path().to_path_buf();
+        if let Some(index) = PathIndex::cached(key.clone(), tree.id()) {
+            return Ok(index);
+        }
+        let mut index = PathIndex::default();
+        tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() == Some(git2::ObjectType::Blob) {
+                let name = String::from_utf8_lossy(entry.name_bytes());
+                index.paths.push(format!("{}{}", dir, name));
+            }
+            TreeWalkResult::Ok
+        })?;
+        Ok(PathIndex::cache(key, tree.id(), index))
+    }
+
+    /// 在树中模糊查找文件，返回得分最高的 limit 个路径
+    pub fn find_files(
+        &self,
+        rev: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<FuzzyMatch>, GitUtilsErrorCode> {
+        Ok(self.path_index(rev)?.fuzzy_find(query, limit))
+    }
+
+    /// 在工作区、暂存区或者提交的文件中搜索内容，按路径顺序返回匹配的行
+    pub fn grep(
+        &self,
+        source: &GrepSource,
+        options: &GrepOptions,
+    ) -> Result<GrepResult, GitUtilsErrorCode> {
+        let repo = &self.repository;
+        let matcher = options.matcher()?;
+        let mut result = GrepResult::default();
+        match source {
+            GrepSource::Commit(rev) => {
+                let tree = repo.revparse_single(rev)?.peel_to_tree()?;
+                let mut ret = Ok(());
+                tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+                    if entry.kind() != Some(git2::ObjectType::Blob) {
+                        return TreeWalkResult::Ok;
+                    }
+                    let path = format!("{}{}", dir, entry.name().unwrap_or_default());
+                    if !options.accepts(&path) {
+                        return TreeWalkResult::Ok;
+                    }
+                    let blob = match repo.find_blob(entry.id()) {
                         Ok(blob) => blob,
-                        Err(_) => return TreeWalkResult::Skip,
+                        Err(e) => {
+                            ret = Err(e);
+                            return TreeWalkResult::Abort;
+                        }
                     };
-                    let size = blob.size();
-                    let file =
-                        Box::new(fs::File::new(path.into(), name, object_id, size, file_mode));
-                    parent_dir.add(fs::FsNode::File(*file));
+                    match result.search_file(&path, blob.content(), &matcher, options) {
+                        true => TreeWalkResult::Ok,
+                        false => TreeWalkResult::Abort,
+                    }
+                })
+                .or_else(|e| match e.code() {
+                    git2::ErrorCode::User => Ok(()),
+                    _ => Err(e),
+                })?;
+                ret?;
+            }
+            GrepSource::Index => {
+                let index = repo.index()?;
+                for entry in index.iter() {
+                    // 跳过子模块和冲突时的 ours/theirs 等版本，只搜索 stage 0
+                    if entry.mode == 0o160000 || (entry.flags >> 12) & 0x3 != 0 {
+                        continue;
+                    }
+                    let path = String::from_utf8_lossy(&entry.path).to_string();
+                    if !options.accepts(&path) {
+                        continue;
+                    }
+                    let blob = repo.find_blob(entry.id)?;
+                    if !result.search_file(&path, blob.content(), &matcher, options) {
+                        break;
+                    }
                 }
-            } else {
-                // eprintln!("Failed to get parent directory for: {}", path);
-                return TreeWalkResult::Skip;
             }
-            TreeWalkResult::Ok
-        });
-        Ok(*root)
+            GrepSource::Worktree => {
+                let workdir = repo
+                    .workdir()
+                    .ok_or_else(|| {
+                        GitUtilsErrorCode::RepoIsBare(repo.path().display().to_string())
+                    })?
+                    .to_path_buf();
+                let mut stack = vec![workdir.clone()];
+                while let Some(dir) = stack.pop() {
+                    let mut entries = std::fs::read_dir(&dir)?
+                        .flatten()
+                        .map(|e| e.path())
+                        .collect::<Vec<_>>();
+                    // 倒序入栈，按路径顺序搜索
+                    entries.sort_by(|a, b| b.cmp(a));
+                    let mut files = vec![];
+                    for path in entries {
+                        let rel = path
+                            .strip_prefix(&workdir)
+                            .unwrap_or(&path)
+                            .to_string_lossy()
+                            .replace('\\', "/");
+                        if rel == ".git" || repo.is_path_ignored(&rel).unwrap_or(false) {
+                            continue;
+                        }
+                        let file_type = match std::fs::symlink_metadata(&path) {
+                            Ok(meta) => meta.file_type(),
+                            Err(_) => continue,
+                        };
+                        if file_type.is_dir() {
+                            // 子模块和嵌套的仓库不搜索
+                            if !path.join(".git").exists() {
+                                stack.push(path);
+                            }
+                        } else if file_type.is_file() && options.accepts(&rel) {
+                            files.push((rel, path));
+                        }
+                    }
+                    // 当前目录的文件先于子目录搜索
+                    for (rel, path) in files.into_iter().rev() {
+                        let content = match std::fs::read(&path) {
+                            Ok(content) => content,
+                            Err(e) => {
+                                log::warn!("grep: read {} error: {}", path.display(), e);
+                                continue;
+                            }
+                        };
+                        if !result.search_file(&rel, &content, &matcher, options) {
+                            return Ok(result);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(result)
     }
 
     /// 根据提交获取根节点树(只获取一级)，object_id所指的对象应该为tree或comit，
     /// 如果是tree，则tree_name, tree_path必填，如果是commit，则会自动变为空值
+    ///
+    /// 不会计算每一项最后一次修改的提交，需要时调用 annotate_last_commits 或者 tree_last_commits
     pub fn get_tree(
         &self,
         object_id: Oid,
@@ -1434,3 +3084,44 @@ impl GitDataProvider {
         Ok(())
     }
 }
+
+/// 检出失败时，会覆盖未提交修改的冲突转换为对应的错误
+fn checkout_error(e: git2::Error) -> GitUtilsErrorCode {
+    log::error!("Checkout error: {:?}", e);
+    match e.code() {
+        git2::ErrorCode::Conflict => GitUtilsErrorCode::CommitBeforePullWouldBeOverwrittenByMerge,
+        _ => e.into(),
+    }
+}
+
+/// 索引中有冲突的文件路径，每行一个
+fn conflict_paths(index: &git2::Index) -> Result<String, GitUtilsErrorCode> {
+    let mut paths = vec![];
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+            paths.push(String::from_utf8_lossy(&entry.path).to_string());
+        }
+    }
+    Ok(paths.join("\n"))
+}
+
+/// 初始化并更新子模块，recursive 为 true 时同时更新子模块中的子模块
+fn update_submodules<'a>(
+    submodules: Vec<git2::Submodule>,
+    build_callbacks: &impl Fn() -> RemoteCallbacks<'a>,
+    recursive: bool,
+) -> Result<(), git2::Error> {
+    for mut submodule in submodules {
+        let mut fetch_opt = FetchOptions::new();
+        fetch_opt.remote_callbacks(build_callbacks());
+        let mut update_opt = git2::SubmoduleUpdateOptions::new();
+        update_opt.fetch(fetch_opt);
+        submodule.update(true, Some(&mut update_opt))?;
+        if recursive {
+            let repo = submodule.open()?;
+            update_submodules(repo.submodules()?, build_callbacks, recursive)?;
+        }
+    }
+    Ok(())
+}
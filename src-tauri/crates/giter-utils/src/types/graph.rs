@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use git2::{Oid, Repository};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use super::commit::Commit;
+use super::error::GitUtilsErrorCode;
+use super::history::HistoryWalk;
+
+/// 最多保存的提交图遍历数量
+const GRAPH_WALK_CACHE_SIZE: usize = 16;
+
+/// (仓库路径, 起点提交, 已经输出的行数)
+type WalkKey = (PathBuf, Vec<Oid>, usize);
+
+/// 停在某一页末尾的遍历，下一页从这里继续，不需要重新遍历前面的历史
+static GRAPH_WALKS: LazyLock<Mutex<HashMap<WalkKey, HistoryWalk>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 从 tips 开始按时间倒序遍历，跳过前 offset 个提交
+///
+/// 只需要遍历到当前页的最后一个提交，不需要先读取全部历史；
+/// 缓存已经被清除时重新遍历前 offset 个提交，历史不足 offset 个时 cursor 无效
+pub(crate) fn resume_walk(
+    repo: &Repository,
+    tips: &[Oid],
+    offset: usize,
+) -> Result<HistoryWalk, GitUtilsErrorCode> {
+    let key = (repo.path().to_path_buf(), tips.to_vec(), offset);
+    if let Some(walk) = GRAPH_WALKS.lock().remove(&key) {
+        return Ok(walk);
+    }
+    let mut walk = HistoryWalk::new(repo, tips)?;
+    for _ in 0..offset {
+        if walk.next(repo)?.is_none() {
+            return Err(GitUtilsErrorCode::InvalidCursor("graph cursor".to_string()));
+        }
+    }
+    Ok(walk)
+}
+
+/// 保存输出了 offset 行的遍历，下一页从这里继续
+pub(crate) fn save_walk(repo: &Repository, tips: &[Oid], offset: usize, walk: HistoryWalk) {
+    let mut walks = GRAPH_WALKS.lock();
+    if walks.len() >= GRAPH_WALK_CACHE_SIZE {
+        walks.clear();
+    }
+    walks.insert((repo.path().to_path_buf(), tips.to_vec(), offset), walk);
+}
+
+/// 上一行到这一行的线段，from 是上一行的列，to 是这一行的列
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphEdge {
+    pub from: usize,
+    pub to: usize,
+    pub color: usize,
+}
+
+/// 提交图中的一行
+///
+/// - column: 提交所在的列
+/// - color: 提交所在分支线的颜色编号，同一条线的颜色不变
+/// - edges: 从上一行连接到这一行的线段，第一行的线段来自上一页的最后一行
+/// - width: 这一行和上一行的线段占用的列数
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphRow {
+    pub commit: Commit,
+    pub column: usize,
+    pub color: usize,
+    pub edges: Vec<GraphEdge>,
+    pub width: usize,
+}
+
+/// 继续计算下一页需要的状态，前端不需要关心其中的内容，原样传回即可
+///
+/// tips 为第一页时引用所在的提交，之后的页沿用第一页的起点，引用移动后需要重新从第一页开始计算
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphCursor {
+    pub(crate) offset: usize,
+    pub(crate) tips: Vec<String>,
+    lanes: Vec<Option<String>>,
+    sources: Vec<Vec<(usize, usize)>>,
+    colors: Vec<usize>,
+    next_color: usize,
+}
+
+/// 提交图的一页，cursor 为空时没有更多的提交
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphPage {
+    pub rows: Vec<GraphRow>,
+    pub cursor: Option<GraphCursor>,
+}
+
+/// 按时间倒序逐个输入提交，计算每个提交所在的列和线段
+///
+/// 每一列(lane)保存等待出现的提交，提交出现时所有等待它的列汇合到提交所在的列，
+/// 第一个父提交沿用提交所在的列，其他父提交(合并、章鱼合并)占用新的列；
+/// 父提交已经在其他列中等待时，线段汇合到那一列
+#[derive(Debug, Default)]
+pub(crate) struct GraphBuilder {
+    lanes: Vec<Option<Oid>>,
+    /// 每一列在上一行的起点和线段的颜色
+    sources: Vec<Vec<(usize, usize)>>,
+    colors: Vec<usize>,
+    next_color: usize,
+}
+
+impl GraphBuilder {
+    /// 从前端传回的 cursor 恢复状态，cursor 不完整时返回错误
+    pub(crate) fn from_cursor(cursor: &GraphCursor) -> Result<Self, GitUtilsErrorCode> {
+        let invalid = || GitUtilsErrorCode::InvalidCursor("graph cursor".to_string());
+        let width = cursor.lanes.len();
+        if cursor.sources.len() != width || cursor.colors.len() != width {
+            return Err(invalid());
+        }
+        let colors = cursor
+            .colors
+            .iter()
+            .chain(cursor.sources.iter().flatten().map(|(_, color)| color));
+        if colors.into_iter().any(|color| *color >= cursor.next_color) {
+            return Err(invalid());
+        }
+        let lanes = cursor
+            .lanes
+            .iter()
+            .map(|l| match l {
+                Some(id) => Oid::from_str(id).map(Some).map_err(|_| invalid()),
+                None => Ok(None),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(GraphBuilder {
+            lanes,
+            sources: cursor.sources.clone(),
+            colors: cursor.colors.clone(),
+            next_color: cursor.next_color,
+        })
+    }
+
+    pub(crate) fn cursor(&self, offset: usize, tips: &[Oid]) -> GraphCursor {
+        GraphCursor {
+            offset,
+            tips: tips.iter().map(|id| id.to_string()).collect(),
+            lanes: self
+                .lanes
+                .iter()
+                .map(|l| l.map(|id| id.to_string()))
+                .collect(),
+            sources: self.sources.clone(),
+            colors: self.colors.clone(),
+            next_color: self.next_color,
+        }
+    }
+
+    fn new_color(&mut self) -> usize {
+        self.next_color += 1;
+        self.next_color - 1
+    }
+
+    /// 第一个空闲的列，没有时在最后添加一列
+    fn free_lane(&mut self) -> usize {
+        match self.lanes.iter().position(|l| l.is_none()) {
+            Some(idx) => idx,
+            None => {
+                self.lanes.push(None);
+                self.sources.push(vec![]);
+                self.colors.push(0);
+                self.lanes.len() - 1
+            }
+        }
+    }
+
+    /// 输入下一个提交，返回提交所在的列、颜色、连接到这一行的线段和宽度
+    pub(crate) fn next(
+        &mut self,
+        id: Oid,
+        parents: &[Oid],
+    ) -> (usize, usize, Vec<GraphEdge>, usize) {
+        let found = self.lanes.iter().position(|l| *l == Some(id));
+        let mut edges = vec![];
+        for (idx, lane) in self.lanes.iter().enumerate() {
+            let Some(lane) = lane else {
+                continue;
+            };
+            let to = if *lane == id {
+                found.unwrap_or(idx)
+            } else {
+                idx
+            };
+            for (from, color) in &self.sources[idx] {
+                edges.push(GraphEdge {
+                    from: *from,
+                    to,
+                    color: *color,
+                });
+            }
+        }
+        let column = match found {
+            Some(column) => column,
+            None => {
+                let column = self.free_lane();
+                self.colors[column] = self.new_color();
+                column
+            }
+        };
+        let color = self.colors[column];
+        for (idx, lane) in self.lanes.iter_mut().enumerate() {
+            if *lane == Some(id) {
+                *lane = None;
+            }
+            self.sources[idx] = match lane {
+                Some(_) => vec![(idx, self.colors[idx])],
+                None => vec![],
+            };
+        }
+        for (n, parent) in parents.iter().enumerate() {
+            let waiting = self.lanes.iter().position(|l| *l == Some(*parent));
+            match waiting {
+                // 第一个父提交在右侧的列中等待时，把那一列移到提交所在的列，保持主线在左侧
+                Some(idx) if n == 0 && idx > column => {
+                    let mut sources = vec![(column, color)];
+                    sources.append(&mut self.sources[idx]);
+                    self.lanes[idx] = None;
+                    self.lanes[column] = Some(*parent);
+                    self.sources[column] = sources;
+                }
+                Some(idx) => self.sources[idx].push((column, color)),
+                None => {
+                    let idx = if n == 0 {
+                        column
+                    } else {
+                        let idx = self.free_lane();
+                        self.colors[idx] = self.new_color();
+                        idx
+                    };
+                    self.lanes[idx] = Some(*parent);
+                    self.sources[idx] = vec![(column, self.colors[idx])];
+                }
+            }
+        }
+        while self.lanes.last().is_some_and(|l| l.is_none()) {
+            self.lanes.pop();
+            self.sources.pop();
+            self.colors.pop();
+        }
+        let width = edges
+            .iter()
+            .map(|e| e.from.max(e.to) + 1)
+            .chain([column + 1])
+            .max()
+            .unwrap_or(1);
+        (column, color, edges, width)
+    }
+}
@@ -0,0 +1,156 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::error::GitUtilsErrorCode;
+use crate::util::{build_regex, is_binary_file_content, wildcard_match};
+
+/// 搜索的文件来源
+///
+/// - Worktree: 工作区中的文件，忽略的文件不会被搜索
+/// - Index: 暂存区中的文件
+/// - Commit: 提交(或者任意可以解析为树的引用)中的文件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GrepSource {
+    Worktree,
+    Index,
+    Commit(String),
+}
+
+/// 文件内容搜索选项
+///
+/// - word: 只匹配完整的单词
+/// - include: 只搜索匹配的路径，为空时搜索所有文件；
+///   不包含 `/` 的通配符匹配文件名，否则匹配相对仓库根目录的路径
+/// - exclude: 不搜索的路径，规则与 include 相同
+/// - context: 匹配行前后返回的行数
+/// - max_results: 最多返回的匹配行数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GrepOptions {
+    pub pattern: String,
+    pub regex: bool,
+    pub ignore_case: bool,
+    pub word: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub context: usize,
+    pub max_results: usize,
+}
+
+impl Default for GrepOptions {
+    fn default() -> Self {
+        GrepOptions {
+            pattern: String::new(),
+            regex: false,
+            ignore_case: false,
+            word: false,
+            include: vec![],
+            exclude: vec![],
+            context: 2,
+            max_results: 1000,
+        }
+    }
+}
+
+impl GrepOptions {
+    pub(crate) fn matcher(&self) -> Result<Regex, GitUtilsErrorCode> {
+        build_regex(&self.pattern, self.regex, self.ignore_case, self.word)
+    }
+
+    /// 路径是否需要搜索
+    pub(crate) fn accepts(&self, path: &str) -> bool {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let matches = |pattern: &String| {
+            if pattern.contains('/') {
+                wildcard_match(pattern.trim_start_matches('/'), path)
+            } else {
+                wildcard_match(pattern, name)
+            }
+        };
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+}
+
+/// 文件中的一行，line_number 从1开始
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepLine {
+    pub line_number: usize,
+    pub content: String,
+}
+
+/// 匹配的行
+///
+/// - ranges: 匹配的内容在行中的字节范围
+/// - before/after: 匹配行前后的上下文
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepMatch {
+    pub path: String,
+    pub line_number: usize,
+    pub content: String,
+    pub ranges: Vec<(usize, usize)>,
+    pub before: Vec<GrepLine>,
+    pub after: Vec<GrepLine>,
+}
+
+/// 搜索结果，truncated 表示匹配的行数达到了 max_results，还有没有返回的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepResult {
+    pub matches: Vec<GrepMatch>,
+    pub files_searched: usize,
+    pub truncated: bool,
+}
+
+impl GrepResult {
+    /// 搜索一个文件，二进制文件会被跳过；达到 max_results 时返回 false
+    pub(crate) fn search_file(
+        &mut self,
+        path: &str,
+        content: &[u8],
+        matcher: &Regex,
+        options: &GrepOptions,
+    ) -> bool {
+        if self.truncated {
+            return false;
+        }
+        if is_binary_file_content(content) {
+            return true;
+        }
+        self.files_searched += 1;
+        let text = String::from_utf8_lossy(content);
+        let lines = text.lines().collect::<Vec<_>>();
+        let line_at = |idx: usize| GrepLine {
+            line_number: idx + 1,
+            content: lines[idx].to_string(),
+        };
+        for (idx, line) in lines.iter().enumerate() {
+            let ranges = matcher
+                .find_iter(line)
+                .map(|m| (m.start(), m.end()))
+                .collect::<Vec<_>>();
+            if ranges.is_empty() {
+                continue;
+            }
+            if self.matches.len() >= options.max_results {
+                self.truncated = true;
+                return false;
+            }
+            let end = (idx + 1 + options.context).min(lines.len());
+            self.matches.push(GrepMatch {
+                path: path.to_string(),
+                line_number: idx + 1,
+                content: line.to_string(),
+                ranges,
+                before: (idx.saturating_sub(options.context)..idx)
+                    .map(line_at)
+                    .collect(),
+                after: (idx + 1..end).map(line_at).collect(),
+            });
+        }
+        true
+    }
+}
@@ -0,0 +1,143 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use git2::{Oid, Repository};
+use parking_lot::Mutex;
+
+use super::commit::Commit;
+use super::error::GitUtilsErrorCode;
+use crate::util::build_commit;
+
+/// 最多保存的遍历数量，每个遍历保存已经遍历的提交id，百万个提交的仓库有几十MB
+const HISTORY_WALK_CACHE_SIZE: usize = 4;
+
+/// 最多缓存的提交数量
+const COMMIT_CACHE_SIZE: usize = 4096;
+
+/// (仓库路径, 提交id)
+type CommitKey = (PathBuf, Oid);
+
+/// 已经遍历的历史，key 为遍历的起点，提交的历史不会改变，缓存不需要失效
+static HISTORY_WALKS: LazyLock<Mutex<HashMap<CommitKey, HistoryWalk>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 已经生成的提交信息，翻页和不同的列表中出现的同一个提交不需要重新读取
+static COMMITS: LazyLock<Mutex<HashMap<CommitKey, Commit>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 从一个或多个提交开始按提交时间倒序遍历历史，和 git rev-list 的默认顺序相同
+///
+/// 保存已经遍历的提交和待遍历的父提交，同一个起点的下一页从上一次停下的位置继续，
+/// 不需要重新遍历前面的历史
+#[derive(Debug)]
+pub(crate) struct HistoryWalk {
+    ids: Vec<Oid>,
+    /// 待遍历的提交，时间相同时先加入的先遍历
+    queue: BinaryHeap<(i64, Reverse<usize>, Oid)>,
+    seen: HashSet<Oid>,
+    pushed: usize,
+}
+
+impl HistoryWalk {
+    pub(crate) fn new(repo: &Repository, tips: &[Oid]) -> Result<Self, GitUtilsErrorCode> {
+        let mut walk = HistoryWalk {
+            ids: vec![],
+            queue: BinaryHeap::new(),
+            seen: HashSet::new(),
+            pushed: 0,
+        };
+        for tip in tips {
+            let time = repo.find_commit(*tip)?.time().seconds();
+            walk.push(*tip, time);
+        }
+        Ok(walk)
+    }
+
+    fn push(&mut self, id: Oid, time: i64) {
+        if self.seen.insert(id) {
+            self.queue.push((time, Reverse(self.pushed), id));
+            self.pushed += 1;
+        }
+    }
+
+    /// 下一个提交，历史结束时返回 None，返回的提交不会保存在 ids 中
+    pub(crate) fn next<'r>(
+        &mut self,
+        repo: &'r Repository,
+    ) -> Result<Option<git2::Commit<'r>>, GitUtilsErrorCode> {
+        let Some((_, _, id)) = self.queue.pop() else {
+            return Ok(None);
+        };
+        let commit = repo.find_commit(id)?;
+        for idx in 0..commit.parent_count() {
+            // 浅克隆中缺失的父提交不再继续遍历
+            if let Ok(parent) = commit.parent(idx) {
+                self.push(parent.id(), parent.time().seconds());
+            }
+        }
+        Ok(Some(commit))
+    }
+
+    /// 提交是否在等待遍历，已经遍历过或者还没有遇到的提交返回 false
+    pub(crate) fn is_pending(&self, id: Oid) -> bool {
+        self.queue.iter().any(|(_, _, queued)| *queued == id)
+    }
+
+    /// 历史是否已经遍历完
+    pub(crate) fn is_done(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// 继续遍历，直到遍历了 len 个提交或者历史结束
+    fn fill(&mut self, repo: &Repository, len: usize) -> Result<(), GitUtilsErrorCode> {
+        while self.ids.len() < len {
+            let Some(commit) = self.next(repo)? else {
+                break;
+            };
+            self.ids.push(commit.id());
+        }
+        Ok(())
+    }
+
+    /// 从 tip 开始的历史中第 offset 个之后的最多 count 个提交id
+    pub(crate) fn page(
+        repo: &Repository,
+        tip: Oid,
+        offset: usize,
+        count: usize,
+    ) -> Result<Vec<Oid>, GitUtilsErrorCode> {
+        let key = (repo.path().to_path_buf(), tip);
+        // 遍历时不持有锁，同时翻页的其他仓库不需要等待
+        let walk = HISTORY_WALKS.lock().remove(&key);
+        let mut walk = match walk {
+            Some(walk) => walk,
+            None => HistoryWalk::new(repo, &[tip])?,
+        };
+        let end = offset.saturating_add(count);
+        walk.fill(repo, end)?;
+        let ids = walk.ids[offset.min(walk.ids.len())..end.min(walk.ids.len())].to_vec();
+        let mut walks = HISTORY_WALKS.lock();
+        if walks.len() >= HISTORY_WALK_CACHE_SIZE {
+            walks.clear();
+        }
+        walks.insert(key, walk);
+        Ok(ids)
+    }
+}
+
+/// 读取提交信息，已经生成过的提交直接从缓存中返回
+pub(crate) fn cached_commit(repo: &Repository, id: Oid) -> Result<Commit, GitUtilsErrorCode> {
+    let key = (repo.path().to_path_buf(), id);
+    if let Some(commit) = COMMITS.lock().get(&key) {
+        return Ok(commit.clone());
+    }
+    let commit = build_commit(&repo.find_commit(id)?, repo);
+    let mut commits = COMMITS.lock();
+    if commits.len() >= COMMIT_CACHE_SIZE {
+        commits.clear();
+    }
+    commits.insert(key, commit.clone());
+    Ok(commit)
+}
@@ -0,0 +1,202 @@
+use std::io::Write;
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use git2::Repository;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::error::GitUtilsErrorCode;
+
+/// Conventional Commits 默认允许的类型
+const CONVENTIONAL_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// 钩子执行失败时的输出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookOutput {
+    pub hook: String,
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl HookOutput {
+    /// 钩子的输出信息，优先使用stderr
+    pub fn message(&self) -> &str {
+        if self.stderr.trim().is_empty() {
+            self.stdout.trim()
+        } else {
+            self.stderr.trim()
+        }
+    }
+}
+
+/// 仓库钩子执行器，钩子目录读取自 core.hooksPath，默认为 .git/hooks
+pub struct HookRunner<'a> {
+    repo: &'a Repository,
+    hooks_dir: PathBuf,
+}
+
+impl<'a> HookRunner<'a> {
+    pub fn new(repo: &'a Repository) -> Result<Self, GitUtilsErrorCode> {
+        let config = repo.config()?;
+        let hooks_dir = match config.get_path("core.hooksPath") {
+            Ok(path) if path.is_absolute() => path,
+            // 相对路径相对于工作区根目录
+            Ok(path) => repo.workdir().unwrap_or(repo.path()).join(path),
+            Err(_) => repo.commondir().join("hooks"),
+        };
+        Ok(HookRunner { repo, hooks_dir })
+    }
+
+    fn hook_path(&self, name: &str) -> Option<PathBuf> {
+        let path = self.hooks_dir.join(name);
+        if !path.is_file() {
+            return None;
+        }
+        // 与git一致，忽略没有执行权限的钩子
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = path.metadata().ok()?.permissions().mode();
+            if mode & 0o111 == 0 {
+                log::warn!("hook {} was ignored because it's not executable", name);
+                return None;
+            }
+        }
+        Some(path)
+    }
+
+    /// 执行钩子，钩子不存在时返回 Ok(false)，钩子返回非0时返回 HookFailed
+    pub fn run(
+        &self,
+        name: &str,
+        args: &[&str],
+        stdin: Option<&[u8]>,
+    ) -> Result<bool, GitUtilsErrorCode> {
+        let path = match self.hook_path(name) {
+            Some(path) => path,
+            None => return Ok(false),
+        };
+        // windows下的钩子是shell脚本，需要通过sh执行
+        let mut cmd = if cfg!(windows) {
+            let mut cmd = Command::new("sh");
+            cmd.arg(&path);
+            cmd
+        } else {
+            Command::new(&path)
+        };
+        #[cfg(windows)]
+        cmd.creation_flags(0x08000000);
+        cmd.args(args)
+            .current_dir(self.repo.workdir().unwrap_or(self.repo.path()))
+            .env("GIT_INDEX_FILE", self.repo.path().join("index"))
+            .env("GIT_EDITOR", ":")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut child = cmd.spawn()?;
+        if let Some(mut pipe) = child.stdin.take() {
+            // 钩子可能不读取标准输入就退出，写入失败时忽略
+            let _ = pipe.write_all(stdin.unwrap_or_default());
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(GitUtilsErrorCode::HookFailed(HookOutput {
+                hook: name.to_string(),
+                code: output.status.code(),
+                stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            }));
+        }
+        Ok(true)
+    }
+
+    /// 依次执行 prepare-commit-msg 和 commit-msg，钩子可以修改提交信息，返回最终的提交信息
+    /// verify 为 false 时跳过 commit-msg(与 git commit --no-verify 一致)
+    pub fn run_commit_msg_hooks(
+        &self,
+        message: &str,
+        amend: bool,
+        verify: bool,
+    ) -> Result<String, GitUtilsErrorCode> {
+        let msg_file = self.repo.path().join("COMMIT_EDITMSG");
+        std::fs::write(&msg_file, message)?;
+        let msg_path = msg_file.to_string_lossy().to_string();
+        let source: &[&str] = if amend {
+            &["commit", "HEAD"]
+        } else {
+            &["message"]
+        };
+        let mut args = vec![msg_path.as_str()];
+        args.extend_from_slice(source);
+        let mut changed = self.run("prepare-commit-msg", &args, None)?;
+        if verify {
+            changed |= self.run("commit-msg", &[&msg_path], None)?;
+        }
+        if !changed {
+            return Ok(message.to_string());
+        }
+        let message = std::fs::read_to_string(&msg_file)?;
+        Ok(git2::message_prettify(message, Some(b'#'))?)
+    }
+}
+
+/// 是否启用了 Conventional Commits 检查，读取仓库配置 giter.conventionalCommits
+pub fn conventional_commits_enabled(repo: &Repository) -> bool {
+    repo.config()
+        .and_then(|c| c.get_bool("giter.conventionalCommits"))
+        .unwrap_or(false)
+}
+
+/// 按照 Conventional Commits 规范检查提交信息
+/// 允许的类型可以通过 giter.conventionalCommitTypes(逗号分隔) 配置
+pub fn validate_conventional_commit(
+    repo: &Repository,
+    message: &str,
+) -> Result<(), GitUtilsErrorCode> {
+    let types = repo
+        .config()
+        .and_then(|c| c.get_string("giter.conventionalCommitTypes"))
+        .map(|t| {
+            t.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_else(|_| CONVENTIONAL_TYPES.iter().map(|t| t.to_string()).collect());
+    check_conventional_commit(message, &types).map_err(GitUtilsErrorCode::ConventionalCommitInvalid)
+}
+
+fn check_conventional_commit(message: &str, types: &[String]) -> Result<(), String> {
+    let mut lines = message.lines();
+    let header = lines.next().unwrap_or_default();
+    // git 自动生成的提交信息不做检查
+    let generated = ["Merge ", "Revert \"", "fixup! ", "squash! ", "amend! "];
+    if generated.iter().any(|p| header.starts_with(p)) {
+        return Ok(());
+    }
+    let re =
+        Regex::new(r"^(?P<type>[A-Za-z]+)(\((?P<scope>[^()\r\n]+)\))?!?: (?P<desc>\S.*)$").unwrap();
+    let caps = re.captures(header).ok_or(format!(
+        "header must match \"<type>(<scope>)!: <description>\": {}",
+        header
+    ))?;
+    let commit_type = &caps["type"];
+    if !types.iter().any(|t| t == commit_type) {
+        return Err(format!(
+            "type \"{}\" is not allowed, expected one of: {}",
+            commit_type,
+            types.join(", ")
+        ));
+    }
+    if lines.next().is_some_and(|line| !line.trim().is_empty()) {
+        return Err("body must be separated from the header by a blank line".into());
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+/// 初始化仓库选项
+///
+/// - initial_branch: 初始分支名，为空时使用 init.defaultBranch 配置
+/// - gitignore: 写入 .gitignore 的模板名称，见 GITIGNORE_TEMPLATES，
+///   目录中已经有 .gitignore 时不会覆盖
+/// - initial_commit: 初始提交的信息，不为空时把目录中的文件提交为初始提交
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InitOptions {
+    pub initial_branch: Option<String>,
+    pub gitignore: Option<String>,
+    pub initial_commit: Option<String>,
+}
+
+/// 内置的 .gitignore 模板，(名称, 内容)
+pub const GITIGNORE_TEMPLATES: &[(&str, &str)] = &[
+    ("Rust", "/target/\n**/*.rs.bk\n*.pdb\n"),
+    (
+        "Node",
+        "node_modules/\ndist/\n.npm/\n.env\n.env.*\nnpm-debug.log*\nyarn-debug.log*\nyarn-error.log*\npnpm-debug.log*\n",
+    ),
+    (
+        "Python",
+        "__pycache__/\n*.py[cod]\n*.egg-info/\n.eggs/\nbuild/\ndist/\n.venv/\nvenv/\n.env\n.pytest_cache/\n",
+    ),
+    (
+        "Java",
+        "*.class\n*.jar\n*.war\n*.ear\n*.log\ntarget/\nbuild/\n.gradle/\nhs_err_pid*\n",
+    ),
+    (
+        "Go",
+        "*.exe\n*.exe~\n*.dll\n*.so\n*.dylib\n*.test\n*.out\nvendor/\n",
+    ),
+    (
+        "C++",
+        "*.o\n*.obj\n*.so\n*.dylib\n*.dll\n*.a\n*.lib\n*.exe\nbuild/\n",
+    ),
+    ("macOS", ".DS_Store\n.AppleDouble\n.LSOverride\n._*\n"),
+    (
+        "Windows",
+        "Thumbs.db\nehthumbs.db\nDesktop.ini\n$RECYCLE.BIN/\n*.lnk\n",
+    ),
+    ("JetBrains", ".idea/\n*.iml\nout/\n"),
+    (
+        "VisualStudioCode",
+        ".vscode/*\n!.vscode/settings.json\n!.vscode/extensions.json\n",
+    ),
+];
+
+/// 根据名称(不区分大小写)获取 .gitignore 模板内容
+pub fn gitignore_template(name: &str) -> Option<&'static str> {
+    GITIGNORE_TEMPLATES
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, content)| *content)
+}
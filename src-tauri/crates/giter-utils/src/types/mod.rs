@@ -1,13 +1,32 @@
 pub mod author;
 pub mod branch;
 pub mod cache;
+pub mod clone;
 pub mod commit;
 pub mod commit_filter;
+pub mod commit_options;
 pub mod contribution;
 pub mod credential;
 pub mod diff;
 pub mod error;
+pub mod fetch;
 pub mod file;
 pub mod fs;
 pub mod git_data_provider;
+pub mod graph;
+pub mod grep;
+pub mod history;
+pub mod hook;
+pub mod init;
+pub mod pickaxe;
+pub mod pull;
+pub mod push;
+pub mod remote;
+pub mod scan;
+pub mod search;
+pub mod sign;
 pub mod status;
+pub mod submodule;
+pub mod summary;
+pub mod tree;
+pub mod worktree;
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use git2::{Diff, DiffOptions, Oid, Patch, Repository};
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::commit::Commit;
+use super::error::GitUtilsErrorCode;
+use super::history::HistoryWalk;
+use crate::util::build_regex;
+
+/// 最多保存的未完成的搜索数量
+const PICKAXE_WALK_CACHE_SIZE: usize = 16;
+
+/// (仓库路径, 起点提交, 上一页最后检查的提交)
+type WalkKey = (PathBuf, Oid, Oid);
+
+/// 上一页停下时的遍历状态，下一页从这里继续，不需要重新比较前面提交的差异
+static PICKAXE_WALKS: LazyLock<Mutex<HashMap<WalkKey, HistoryWalk>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 匹配方式
+///
+/// - Occurrence: 与 `git log -S` 相同，文件修改前后匹配的次数不同
+/// - Diff: 与 `git log -G` 相同，新增或删除的行中有匹配
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PickaxeMode {
+    #[default]
+    Occurrence,
+    Diff,
+}
+
+/// 内容搜索选项
+///
+/// - pattern: 搜索的文本，regex 为 true 时是正则表达式
+/// - reference: 从哪个引用开始搜索，为空时从 HEAD 开始
+/// - path: 只搜索该文件或目录下的修改，相对仓库根目录
+/// - cursor: 上一页返回的 cursor，为空时从第一个提交开始
+/// - count: 最多返回的匹配提交数量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PickaxeOptions {
+    pub pattern: String,
+    pub regex: bool,
+    pub ignore_case: bool,
+    pub mode: PickaxeMode,
+    pub reference: Option<String>,
+    pub path: Option<String>,
+    pub cursor: Option<String>,
+    pub count: usize,
+}
+
+impl Default for PickaxeOptions {
+    fn default() -> Self {
+        PickaxeOptions {
+            pattern: String::new(),
+            regex: false,
+            ignore_case: false,
+            mode: PickaxeMode::default(),
+            reference: None,
+            path: None,
+            cursor: None,
+            count: 50,
+        }
+    }
+}
+
+impl PickaxeOptions {
+    pub(crate) fn matcher(&self) -> Result<Regex, GitUtilsErrorCode> {
+        build_regex(&self.pattern, self.regex, self.ignore_case, false)
+    }
+}
+
+/// 修改的行，origin 为 `+` 新增、`-` 删除或 ` ` 上下文
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PickaxeLine {
+    pub origin: char,
+    pub content: String,
+    pub old_lineno: Option<u32>,
+    pub new_lineno: Option<u32>,
+    pub matched: bool,
+}
+
+/// 包含匹配行的差异块
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickaxeHunk {
+    pub path: String,
+    pub header: String,
+    pub lines: Vec<PickaxeLine>,
+}
+
+/// 匹配的提交和其中包含匹配的差异块
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickaxeMatch {
+    pub commit: Commit,
+    pub hunks: Vec<PickaxeHunk>,
+}
+
+/// 搜索结果的一页
+///
+/// - has_more: 历史还没有遍历完，下一页不一定有匹配的提交
+/// - cursor: 这一页最后检查的提交，传给下一页的 PickaxeOptions
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickaxePage {
+    pub matches: Vec<PickaxeMatch>,
+    pub has_more: bool,
+    pub cursor: Option<String>,
+}
+
+/// 从 cursor 之后继续遍历 tip 的历史，cursor 为空时从 tip 开始
+pub(crate) fn resume_walk(
+    repo: &Repository,
+    tip: Oid,
+    cursor: Option<Oid>,
+) -> Result<HistoryWalk, GitUtilsErrorCode> {
+    let Some(cursor) = cursor else {
+        return HistoryWalk::new(repo, &[tip]);
+    };
+    let key = (repo.path().to_path_buf(), tip, cursor);
+    if let Some(walk) = PICKAXE_WALKS.lock().remove(&key) {
+        return Ok(walk);
+    }
+    // 缓存已经被清除时重新遍历到 cursor，只需要读取提交，不需要比较差异
+    let mut walk = HistoryWalk::new(repo, &[tip])?;
+    while let Some(commit) = walk.next(repo)? {
+        if commit.id() == cursor {
+            return Ok(walk);
+        }
+    }
+    Err(GitUtilsErrorCode::InvalidCursor(cursor.to_string()))
+}
+
+/// 保存停在 cursor 的遍历，下一页从这里继续
+pub(crate) fn save_walk(repo: &Repository, tip: Oid, cursor: Oid, walk: HistoryWalk) {
+    let mut walks = PICKAXE_WALKS.lock();
+    if walks.len() >= PICKAXE_WALK_CACHE_SIZE {
+        walks.clear();
+    }
+    walks.insert((repo.path().to_path_buf(), tip, cursor), walk);
+}
+
+fn count_matches(repo: &Repository, id: Oid, matcher: &Regex) -> usize {
+    if id.is_zero() {
+        return 0;
+    }
+    match repo.find_blob(id) {
+        Ok(blob) if !blob.is_binary() => matcher
+            .find_iter(&String::from_utf8_lossy(blob.content()))
+            .count(),
+        _ => 0,
+    }
+}
+
+/// 提交相对第一个父提交的差异，合并提交与 git log 一样不参与搜索
+pub(crate) fn commit_diff<'a>(
+    repo: &'a Repository,
+    commit: &git2::Commit,
+    path: Option<&str>,
+) -> Result<Option<Diff<'a>>, GitUtilsErrorCode> {
+    if commit.parent_count() > 1 {
+        return Ok(None);
+    }
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let mut options = DiffOptions::new();
+    if let Some(path) = path {
+        options.pathspec(path);
+    }
+    let diff = repo.diff_tree_to_tree(
+        parent_tree.as_ref(),
+        Some(&commit.tree()?),
+        Some(&mut options),
+    )?;
+    Ok(Some(diff))
+}
+
+/// 在差异中查找匹配的差异块，没有匹配时返回空
+pub(crate) fn match_hunks(
+    repo: &Repository,
+    diff: &Diff,
+    matcher: &Regex,
+    mode: PickaxeMode,
+) -> Result<Vec<PickaxeHunk>, GitUtilsErrorCode> {
+    let mut hunks = vec![];
+    for (idx, delta) in diff.deltas().enumerate() {
+        if mode == PickaxeMode::Occurrence
+            && count_matches(repo, delta.old_file().id(), matcher)
+                == count_matches(repo, delta.new_file().id(), matcher)
+        {
+            continue;
+        }
+        let Some(patch) = Patch::from_diff(diff, idx)? else {
+            continue;
+        };
+        let path = delta
+            .new_file()
+            .path()
+            .or(delta.old_file().path())
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+        for hunk_idx in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(hunk_idx)?;
+            let mut lines = Vec::with_capacity(line_count);
+            let mut matched = false;
+            for line_idx in 0..line_count {
+                let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+                let origin = line.origin();
+                let content = String::from_utf8_lossy(line.content())
+                    .trim_end_matches(['\r', '\n'])
+                    .to_string();
+                let is_match = matches!(origin, '+' | '-') && matcher.is_match(&content);
+                matched |= is_match;
+                lines.push(PickaxeLine {
+                    origin,
+                    content,
+                    old_lineno: line.old_lineno(),
+                    new_lineno: line.new_lineno(),
+                    matched: is_match,
+                });
+            }
+            if matched {
+                hunks.push(PickaxeHunk {
+                    path: path.clone(),
+                    header: String::from_utf8_lossy(hunk.header())
+                        .trim_end()
+                        .to_string(),
+                    lines,
+                });
+            }
+        }
+    }
+    Ok(hunks)
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// 拉取后整合远程提交的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PullStrategy {
+    /// 合并，可以快进时直接快进(pull.ff=false 时总是创建合并提交)
+    Merge,
+    /// 把本地提交变基到远程分支之上
+    Rebase,
+    /// 只允许快进
+    FastForwardOnly,
+}
+
+impl PullStrategy {
+    /// 根据 pull.rebase、pull.ff 配置决定拉取方式
+    pub fn from_config(config: &git2::Config) -> Self {
+        let rebase = config.get_string("pull.rebase").unwrap_or_default();
+        if matches!(
+            rebase.to_lowercase().as_str(),
+            "true" | "yes" | "on" | "1" | "merges" | "m" | "interactive" | "i"
+        ) {
+            return PullStrategy::Rebase;
+        }
+        match config.get_string("pull.ff").as_deref() {
+            Ok("only") => PullStrategy::FastForwardOnly,
+            _ => PullStrategy::Merge,
+        }
+    }
+}
+
+/// 拉取选项
+///
+/// - strategy: 拉取方式，为空时根据 pull.rebase、pull.ff 配置决定
+/// - autostash: 拉取前暂存工作区的修改，完成后再恢复，
+///   为空时根据 rebase.autoStash 或 merge.autoStash 配置决定
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PullOptions {
+    pub strategy: Option<PullStrategy>,
+    pub autostash: Option<bool>,
+}
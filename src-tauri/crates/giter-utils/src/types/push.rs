@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// 推送模式
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PushMode {
+    /// 只允许快进更新
+    #[default]
+    Normal,
+    /// 远程分支仍然指向本地记录的远程跟踪分支时才强制推送
+    ForceWithLease,
+    /// 强制推送
+    Force,
+}
+
+/// 推送选项
+///
+/// - mode: 推送模式
+/// - remote_branch: 推送到的远程分支名，为空时使用上游分支，没有上游分支时使用本地分支名
+/// - set_upstream: 推送成功后把远程分支设置为本地分支的上游分支
+/// - no_verify: 跳过 pre-push 钩子
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PushOptions {
+    pub mode: PushMode,
+    pub remote_branch: Option<String>,
+    pub set_upstream: bool,
+    pub no_verify: bool,
+}
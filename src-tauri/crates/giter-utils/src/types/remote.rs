@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// 仓库配置的远程
+///
+/// - push_url: 单独配置的推送地址(remote.<name>.pushurl)，为空时推送使用 url
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Remote {
+    pub name: String,
+    pub url: String,
+    pub push_url: Option<String>,
+    pub fetch_refspecs: Vec<String>,
+    pub push_refspecs: Vec<String>,
+}
+
+impl<'a> From<&git2::Remote<'a>> for Remote {
+    fn from(remote: &git2::Remote<'a>) -> Self {
+        let refspecs = |list: Result<git2::string_array::StringArray, git2::Error>| {
+            list.map(|l| l.iter().flatten().map(String::from).collect())
+                .unwrap_or_default()
+        };
+        Remote {
+            name: remote.name().unwrap_or_default().to_string(),
+            url: remote.url().unwrap_or_default().to_string(),
+            push_url: remote.pushurl().map(String::from),
+            fetch_refspecs: refspecs(remote.fetch_refspecs()),
+            push_refspecs: refspecs(remote.push_refspecs()),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+use super::error::GitUtilsErrorCode;
+use super::fetch::CancelToken;
+use crate::util::wildcard_match;
+
+/// 扫描仓库选项
+///
+/// - max_depth: 相对扫描目录的最大层级，扫描目录本身为0
+/// - exclude: 跳过的目录名，支持 `*` 和 `?` 通配符
+/// - include_hidden: 是否进入以 `.` 开头的隐藏目录
+/// - include_bare: 是否包含裸仓库
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScanOptions {
+    pub max_depth: usize,
+    pub exclude: Vec<String>,
+    pub include_hidden: bool,
+    pub include_bare: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            max_depth: 5,
+            exclude: [
+                "node_modules",
+                "target",
+                "build",
+                "dist",
+                "vendor",
+                "__pycache__",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            include_hidden: false,
+            include_bare: true,
+        }
+    }
+}
+
+/// 扫描到的仓库
+///
+/// - branch: 当前分支，分离头指针时为空
+/// - remote_url: origin 的地址，没有 origin 时为第一个远程的地址
+/// - last_commit_time: HEAD 指向提交的时间戳(秒)，空仓库为空
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScannedRepo {
+    pub path: String,
+    pub bare: bool,
+    pub branch: Option<String>,
+    pub remote_url: Option<String>,
+    pub last_commit_time: Option<i64>,
+}
+
+impl ScannedRepo {
+    fn new(path: &Path, repo: &Repository) -> Self {
+        let head = repo.find_reference("HEAD").ok();
+        let branch = head
+            .as_ref()
+            .and_then(|h| h.symbolic_target())
+            .and_then(|t| t.strip_prefix("refs/heads/"))
+            .map(String::from);
+        let last_commit_time = head
+            .and_then(|h| h.peel_to_commit().ok())
+            .map(|c| c.time().seconds());
+        let remote_url = repo
+            .find_remote("origin")
+            .ok()
+            .or_else(|| {
+                let remotes = repo.remotes().ok()?;
+                let name = remotes.iter().flatten().next()?;
+                repo.find_remote(name).ok()
+            })
+            .and_then(|r| r.url().map(String::from));
+        ScannedRepo {
+            path: path.to_string_lossy().to_string(),
+            bare: repo.is_bare(),
+            branch,
+            remote_url,
+            last_commit_time,
+        }
+    }
+}
+
+/// 目录是否是仓库，是的话打开仓库
+///
+/// 包含 .git 的目录是普通仓库，包含 HEAD、objects 和 refs 的目录是裸仓库
+fn open_repo(dir: &Path) -> Option<Repository> {
+    if dir.join(".git").exists() {
+        return Repository::open(dir).ok();
+    }
+    if dir.join("HEAD").is_file() && dir.join("objects").is_dir() && dir.join("refs").is_dir() {
+        return Repository::open_bare(dir).ok();
+    }
+    None
+}
+
+/// 递归扫描 root 下的仓库，每发现一个仓库回调一次 on_found
+///
+/// 发现仓库后不再进入仓库目录，子模块和仓库中嵌套的仓库不会被扫描；
+/// 符号链接指向已经扫描过的目录时跳过，避免循环；
+/// cancel 被取消时停止扫描并返回 OperationCancelled
+pub fn scan_repos(
+    root: &Path,
+    options: &ScanOptions,
+    cancel: &CancelToken,
+    mut on_found: impl FnMut(&ScannedRepo),
+) -> Result<Vec<ScannedRepo>, GitUtilsErrorCode> {
+    if !root.is_dir() {
+        return Err(GitUtilsErrorCode::DirectoryNotFound(
+            root.display().to_string(),
+        ));
+    }
+    let mut repos = vec![];
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    let mut stack = vec![(root.to_path_buf(), 0)];
+    while let Some((dir, depth)) = stack.pop() {
+        if cancel.is_cancelled() {
+            return Err(GitUtilsErrorCode::OperationCancelled);
+        }
+        let Ok(real) = dir.canonicalize() else {
+            continue;
+        };
+        if !visited.insert(real) {
+            continue;
+        }
+        if let Some(repo) = open_repo(&dir) {
+            if options.include_bare || !repo.is_bare() {
+                let scanned = ScannedRepo::new(&dir, &repo);
+                on_found(&scanned);
+                repos.push(scanned);
+            }
+            continue;
+        }
+        if depth >= options.max_depth {
+            continue;
+        }
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("scan repos: read dir {} error: {}", dir.display(), e);
+                continue;
+            }
+        };
+        let mut children = vec![];
+        for entry in entries.flatten() {
+            let path = entry.path();
+            // is_dir 会跟随符号链接
+            if !path.is_dir() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !options.include_hidden && name.starts_with('.') {
+                continue;
+            }
+            if options.exclude.iter().any(|p| wildcard_match(p, &name)) {
+                continue;
+            }
+            children.push(path);
+        }
+        // 按名称倒序入栈，出栈时按名称顺序扫描
+        children.sort_by(|a, b| b.cmp(a));
+        stack.extend(children.into_iter().map(|p| (p, depth + 1)));
+    }
+    Ok(repos)
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use git2::{Oid, Repository};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use super::commit::Commit;
+use super::error::GitUtilsErrorCode;
+use super::history::HistoryWalk;
+
+/// 最多保存的搜索数量，组中的每个仓库各占一个
+const COMMIT_SEARCH_CACHE_SIZE: usize = 64;
+
+/// 遍历到比 start_time 早这么多(毫秒)的提交后停止，提交时间不一定比父提交晚，
+/// 留出时钟误差的余量
+const START_TIME_SLACK: i64 = 24 * 60 * 60 * 1000;
+
+/// (仓库路径, 搜索的起点提交, 搜索条件)
+type SearchKey = (PathBuf, Vec<Oid>, CommitQuery);
+
+/// 进行中的搜索，起点不变时历史不会改变，下一页从上一次停下的位置继续
+static COMMIT_SEARCHES: LazyLock<Mutex<HashMap<SearchKey, CommitSearch>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 提交搜索条件，所有条件同时满足的提交才会被返回
+///
+/// - message: 提交信息包含的文本，不区分大小写
+/// - author: 作者名称或邮箱包含的文本，不区分大小写
+/// - start_time/end_time: 提交时间范围(毫秒)，包含边界
+/// - path: 提交修改了的文件或目录，相对仓库根目录
+/// - branches: 搜索的分支，为空时搜索所有本地分支；仓库中不存在的分支会被跳过
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CommitQuery {
+    pub message: Option<String>,
+    pub author: Option<String>,
+    pub start_time: Option<i64>,
+    pub end_time: Option<i64>,
+    pub path: Option<String>,
+    pub branches: Vec<String>,
+}
+
+impl CommitQuery {
+    /// 提交信息、作者和时间是否满足条件，路径条件需要比较提交的树，单独判断
+    pub(crate) fn matches(&self, commit: &git2::Commit) -> bool {
+        let time = commit.time().seconds() * 1000;
+        if self.start_time.is_some_and(|start| time < start)
+            || self.end_time.is_some_and(|end| time > end)
+        {
+            return false;
+        }
+        if let Some(message) = self.message.as_deref().filter(|m| !m.is_empty()) {
+            let text = String::from_utf8_lossy(commit.message_bytes()).to_lowercase();
+            if !text.contains(&message.to_lowercase()) {
+                return false;
+            }
+        }
+        if let Some(author) = self.author.as_deref().filter(|a| !a.is_empty()) {
+            let author = author.to_lowercase();
+            let signature = commit.author();
+            let name = String::from_utf8_lossy(signature.name_bytes()).to_lowercase();
+            let email = String::from_utf8_lossy(signature.email_bytes()).to_lowercase();
+            if !name.contains(&author) && !email.contains(&author) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// 一个仓库中的搜索，保存已经匹配的提交和待遍历的历史
+#[derive(Debug)]
+pub(crate) struct CommitSearch {
+    walk: HistoryWalk,
+    matched: Vec<Oid>,
+    done: bool,
+}
+
+impl CommitSearch {
+    /// 从 tips 开始按时间倒序搜索，返回最多 limit 个满足 matches 的提交id
+    ///
+    /// 同样的起点和条件已经搜索过时，只需要遍历上一次停下之后的历史
+    pub(crate) fn search(
+        repo: &Repository,
+        tips: Vec<Oid>,
+        query: &CommitQuery,
+        limit: usize,
+        mut matches: impl FnMut(&git2::Commit) -> Result<bool, GitUtilsErrorCode>,
+    ) -> Result<Vec<Oid>, GitUtilsErrorCode> {
+        let key = (repo.path().to_path_buf(), tips, query.clone());
+        // 遍历时不持有锁，同时搜索的其他仓库不需要等待
+        let search = COMMIT_SEARCHES.lock().remove(&key);
+        let mut search = match search {
+            Some(search) => search,
+            None => CommitSearch {
+                walk: HistoryWalk::new(repo, &key.1)?,
+                matched: vec![],
+                done: false,
+            },
+        };
+        while !search.done && search.matched.len() < limit {
+            let Some(commit) = search.walk.next(repo)? else {
+                search.done = true;
+                break;
+            };
+            let time = commit.time().seconds() * 1000;
+            if query
+                .start_time
+                .is_some_and(|start| time < start.saturating_sub(START_TIME_SLACK))
+            {
+                search.done = true;
+                break;
+            }
+            if matches(&commit)? {
+                search.matched.push(commit.id());
+            }
+        }
+        let ids = search.matched[..limit.min(search.matched.len())].to_vec();
+        let mut searches = COMMIT_SEARCHES.lock();
+        if searches.len() >= COMMIT_SEARCH_CACHE_SIZE {
+            searches.clear();
+        }
+        searches.insert(key, search);
+        Ok(ids)
+    }
+}
+
+/// 搜索结果的一页
+///
+/// - has_more: 是否还有下一页
+/// - errors: 搜索失败的仓库和错误信息，不影响其他仓库的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitSearchPage {
+    pub commits: Vec<Commit>,
+    pub has_more: bool,
+    pub errors: Vec<(String, String)>,
+}
+
+/// 把多个仓库的搜索结果按时间倒序合并后分页
+///
+/// 每个仓库需要提供按时间倒序的至少 offset + count + 1 个结果，
+/// 才能保证合并后的分页是准确的
+pub fn merge_search_results(
+    results: Vec<Vec<Commit>>,
+    offset: usize,
+    count: usize,
+    errors: Vec<(String, String)>,
+) -> CommitSearchPage {
+    let mut commits = results.into_iter().flatten().collect::<Vec<_>>();
+    // 时间相同时按仓库和提交id排序，保证分页稳定
+    commits.sort_by(|a, b| {
+        b.datetime
+            .cmp(&a.datetime)
+            .then_with(|| a.repo.cmp(&b.repo))
+            .then_with(|| a.commit_id.cmp(&b.commit_id))
+    });
+    let has_more = commits.len() > offset + count;
+    let commits = commits.into_iter().skip(offset).take(count).collect();
+    CommitSearchPage {
+        commits,
+        has_more,
+        errors,
+    }
+}
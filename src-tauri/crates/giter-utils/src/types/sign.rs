@@ -0,0 +1,421 @@
+use std::collections::HashMap;
+use std::io::Write;
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::LazyLock;
+
+use git2::{Commit, Oid, Repository};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+
+use super::error::GitUtilsErrorCode;
+
+const PGP_SIGNATURE_HEADER: &str = "-----BEGIN PGP SIGNATURE-----";
+const X509_SIGNATURE_HEADER: &str = "-----BEGIN SIGNED MESSAGE-----";
+const SSH_SIGNATURE_HEADER: &str = "-----BEGIN SSH SIGNATURE-----";
+const SSH_NAMESPACE: &str = "git";
+
+/// 最多缓存的签名验证结果数量
+const VERIFIED_CACHE_SIZE: usize = 4096;
+
+/// 签名验证缓存，key为(仓库路径, 提交id)，value为(验证时的配置, 状态)
+type VerifiedCache = HashMap<(PathBuf, Oid), (String, SignatureStatus)>;
+
+/// 已验证过的提交签名状态
+///
+/// 签名验证需要调用外部程序，结果缓存起来避免每次获取提交列表都重新验证；
+/// 只缓存 Good 和 Bad，其他状态在导入密钥后可能改变，签名配置改变时缓存失效
+static VERIFIED: LazyLock<Mutex<VerifiedCache>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 签名格式，对应 git 配置 gpg.format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignFormat {
+    OpenPgp,
+    X509,
+    Ssh,
+}
+
+impl SignFormat {
+    pub fn from_config(value: &str) -> Option<Self> {
+        match value {
+            "openpgp" => Some(SignFormat::OpenPgp),
+            "x509" => Some(SignFormat::X509),
+            "ssh" => Some(SignFormat::Ssh),
+            _ => None,
+        }
+    }
+
+    /// 根据签名内容判断签名格式
+    pub fn from_signature(signature: &str) -> Option<Self> {
+        let signature = signature.trim_start();
+        if signature.starts_with(PGP_SIGNATURE_HEADER) {
+            Some(SignFormat::OpenPgp)
+        } else if signature.starts_with(X509_SIGNATURE_HEADER) {
+            Some(SignFormat::X509)
+        } else if signature.starts_with(SSH_SIGNATURE_HEADER) {
+            Some(SignFormat::Ssh)
+        } else {
+            None
+        }
+    }
+
+    fn default_program(&self) -> &'static str {
+        match self {
+            SignFormat::OpenPgp => "gpg",
+            SignFormat::X509 => "gpgsm",
+            SignFormat::Ssh => "ssh-keygen",
+        }
+    }
+
+    fn config_name(&self) -> &'static str {
+        match self {
+            SignFormat::OpenPgp => "openpgp",
+            SignFormat::X509 => "x509",
+            SignFormat::Ssh => "ssh",
+        }
+    }
+}
+
+/// 提交签名的验证状态，与 git log 中 %G? 的含义一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignatureStatus {
+    /// 没有签名 (N)
+    None,
+    /// 有签名，还没有验证，调用 verify_commit 获取验证结果
+    Unverified,
+    /// 有效签名 (G)
+    Good,
+    /// 无效签名 (B)
+    Bad,
+    /// 签名有效，但签名者的可信度未知 (U)
+    UnknownValidity,
+    /// 签名已过期 (X)
+    Expired,
+    /// 签名的密钥已过期 (Y)
+    ExpiredKey,
+    /// 签名的密钥已被吊销 (R)
+    RevokedKey,
+    /// 无法验证，例如缺少公钥 (E)
+    CannotCheck,
+}
+
+/// 签名配置，读取自 commit.gpgsign、gpg.format、user.signingkey、gpg.<format>.program 等配置
+#[derive(Debug, Clone)]
+pub struct SignConfig {
+    pub enabled: bool,
+    pub format: SignFormat,
+    pub signing_key: Option<String>,
+    pub committer: Option<String>,
+    pub allowed_signers_file: Option<String>,
+    programs: HashMap<&'static str, String>,
+}
+
+impl SignConfig {
+    pub fn from_repo(repo: &Repository) -> Result<Self, GitUtilsErrorCode> {
+        let config = repo.config()?.snapshot()?;
+        let enabled = config.get_bool("commit.gpgsign").unwrap_or(false);
+        let format = config
+            .get_string("gpg.format")
+            .ok()
+            .and_then(|f| SignFormat::from_config(&f))
+            .unwrap_or(SignFormat::OpenPgp);
+        let signing_key = config
+            .get_string("user.signingkey")
+            .ok()
+            .filter(|k| !k.trim().is_empty());
+        let committer = repo.signature().ok().map(|s| s.to_string());
+        let allowed_signers_file = config
+            .get_path("gpg.ssh.allowedSignersFile")
+            .ok()
+            .map(|p| p.to_string_lossy().to_string());
+        let mut programs = HashMap::new();
+        for format in [SignFormat::OpenPgp, SignFormat::X509, SignFormat::Ssh] {
+            let mut program = config
+                .get_string(&format!("gpg.{}.program", format.config_name()))
+                .ok();
+            // gpg.program 是 gpg.openpgp.program 的旧写法
+            if program.is_none() && format == SignFormat::OpenPgp {
+                program = config.get_string("gpg.program").ok();
+            }
+            if let Some(program) = program {
+                programs.insert(format.config_name(), program);
+            }
+        }
+        Ok(SignConfig {
+            enabled,
+            format,
+            signing_key,
+            committer,
+            allowed_signers_file,
+            programs,
+        })
+    }
+
+    /// 影响验证结果的配置，包括 allowedSignersFile 的修改时间
+    fn verify_fingerprint(&self) -> String {
+        let mut programs = self.programs.iter().collect::<Vec<_>>();
+        programs.sort();
+        let modified = self
+            .allowed_signers_file
+            .as_ref()
+            .and_then(|f| std::fs::metadata(expand_home(f)).ok())
+            .and_then(|m| m.modified().ok());
+        format!(
+            "{:?} {:?} {:?}",
+            programs, self.allowed_signers_file, modified
+        )
+    }
+
+    fn program(&self, format: SignFormat) -> &str {
+        self.programs
+            .get(format.config_name())
+            .map(|p| p.as_str())
+            .unwrap_or(format.default_program())
+    }
+
+    /// 对提交内容进行签名，返回签名文本
+    pub fn sign(&self, buffer: &[u8]) -> Result<String, GitUtilsErrorCode> {
+        match self.format {
+            SignFormat::OpenPgp | SignFormat::X509 => self.sign_gpg(buffer),
+            SignFormat::Ssh => self.sign_ssh(buffer),
+        }
+    }
+
+    /// gpg 签名：gpg --status-fd=2 -bsau <key>
+    /// 未配置 user.signingkey 时，与 git 一致使用提交者身份作为密钥
+    fn sign_gpg(&self, buffer: &[u8]) -> Result<String, GitUtilsErrorCode> {
+        let key = self
+            .signing_key
+            .as_ref()
+            .or(self.committer.as_ref())
+            .ok_or(GitUtilsErrorCode::RepoAuthorNoConfig)?;
+        let mut cmd = Command::new(self.program(self.format));
+        cmd.args(["--status-fd=2", "-bsau", key]);
+        let (success, stdout, stderr) = run_with_stdin(cmd, buffer)?;
+        // gpg 成功签名时会在状态输出中写入 SIG_CREATED
+        let created = stderr
+            .lines()
+            .any(|l| l.starts_with("[GNUPG:] SIG_CREATED "));
+        if !success || !created {
+            return Err(GitUtilsErrorCode::SignCommitError(
+                stderr.trim().to_string(),
+            ));
+        }
+        Ok(stdout)
+    }
+
+    /// ssh 签名：ssh-keygen -Y sign -n git -f <key>
+    /// user.signingkey 可以是密钥文件路径，也可以是以 key:: 或 ssh- 开头的公钥文本(此时私钥由ssh-agent提供)
+    fn sign_ssh(&self, buffer: &[u8]) -> Result<String, GitUtilsErrorCode> {
+        let key = self
+            .signing_key
+            .as_ref()
+            .ok_or(GitUtilsErrorCode::SignCommitError(
+                "user.signingkey needs to be set for ssh signing".into(),
+            ))?;
+        let mut cmd = Command::new(self.program(SignFormat::Ssh));
+        cmd.args(["-Y", "sign", "-n", SSH_NAMESPACE]);
+        // 公钥文本需要先写入临时文件
+        let literal_key = key
+            .strip_prefix("key::")
+            .or_else(|| key.starts_with("ssh-").then_some(key.as_str()));
+        let _key_file = match literal_key {
+            Some(literal_key) => {
+                let key_file = temp_file(literal_key.as_bytes())?;
+                cmd.arg("-f").arg(key_file.path()).arg("-U");
+                Some(key_file)
+            }
+            None => {
+                cmd.arg("-f").arg(expand_home(key));
+                None
+            }
+        };
+        let (success, stdout, stderr) = run_with_stdin(cmd, buffer)?;
+        if !success || !stdout.contains(SSH_SIGNATURE_HEADER) {
+            return Err(GitUtilsErrorCode::SignCommitError(
+                stderr.trim().to_string(),
+            ));
+        }
+        Ok(stdout)
+    }
+
+    /// 验证提交签名
+    pub fn verify(&self, signature: &str, data: &[u8]) -> SignatureStatus {
+        let ret = match SignFormat::from_signature(signature) {
+            Some(SignFormat::Ssh) => self.verify_ssh(signature, data),
+            Some(format) => self.verify_gpg(format, signature, data),
+            None => Ok(SignatureStatus::CannotCheck),
+        };
+        ret.unwrap_or_else(|e| {
+            log::error!("verify signature error: {:?}", e);
+            SignatureStatus::CannotCheck
+        })
+    }
+
+    /// gpg --status-fd=1 --verify <sigfile> -，并解析状态输出
+    fn verify_gpg(
+        &self,
+        format: SignFormat,
+        signature: &str,
+        data: &[u8],
+    ) -> Result<SignatureStatus, GitUtilsErrorCode> {
+        let sig_file = temp_file(signature.as_bytes())?;
+        let mut cmd = Command::new(self.program(format));
+        cmd.args(["--keyid-format=long", "--status-fd=1", "--verify"])
+            .arg(sig_file.path())
+            .arg("-");
+        let (_, stdout, _) = run_with_stdin(cmd, data)?;
+        Ok(parse_gpg_status(&stdout))
+    }
+
+    /// 配置了 gpg.ssh.allowedSignersFile 时，根据签名找到签名者再验证；
+    /// 否则只验证签名本身，签名者的可信度未知
+    fn verify_ssh(
+        &self,
+        signature: &str,
+        data: &[u8],
+    ) -> Result<SignatureStatus, GitUtilsErrorCode> {
+        let sig_file = temp_file(signature.as_bytes())?;
+        let program = self.program(SignFormat::Ssh);
+        if let Some(allowed) = &self.allowed_signers_file {
+            let allowed = expand_home(allowed);
+            let mut cmd = Command::new(program);
+            cmd.args(["-Y", "find-principals", "-f"])
+                .arg(&allowed)
+                .arg("-s")
+                .arg(sig_file.path());
+            let (success, stdout, _) = run_with_stdin(cmd, &[])?;
+            let principal = stdout.lines().next().map(|l| l.trim().to_string());
+            if let (true, Some(principal)) = (success, principal.filter(|p| !p.is_empty())) {
+                let mut cmd = Command::new(program);
+                cmd.args(["-Y", "verify", "-n", SSH_NAMESPACE, "-f"])
+                    .arg(&allowed)
+                    .arg("-I")
+                    .arg(&principal)
+                    .arg("-s")
+                    .arg(sig_file.path());
+                let (success, _, _) = run_with_stdin(cmd, data)?;
+                return Ok(if success {
+                    SignatureStatus::Good
+                } else {
+                    SignatureStatus::Bad
+                });
+            }
+        }
+        let mut cmd = Command::new(program);
+        cmd.args(["-Y", "check-novalidate", "-n", SSH_NAMESPACE, "-s"])
+            .arg(sig_file.path());
+        let (success, _, _) = run_with_stdin(cmd, data)?;
+        Ok(if success {
+            SignatureStatus::UnknownValidity
+        } else {
+            SignatureStatus::Bad
+        })
+    }
+}
+
+/// 获取提交的签名验证状态，没有签名时返回 SignatureStatus::None
+pub fn commit_signature_status(repo: &Repository, oid: Oid) -> SignatureStatus {
+    let (signature, data) = match repo.extract_signature(&oid, None) {
+        Ok(ret) => ret,
+        Err(_) => return SignatureStatus::None,
+    };
+    let (Some(signature), Ok(config)) = (signature.as_str(), SignConfig::from_repo(repo)) else {
+        return SignatureStatus::CannotCheck;
+    };
+    let key = (repo.path().to_path_buf(), oid);
+    let fingerprint = config.verify_fingerprint();
+    if let Some((verified_with, status)) = VERIFIED.lock().get(&key)
+        && *verified_with == fingerprint
+    {
+        return *status;
+    }
+    let status = config.verify(signature, &data);
+    if matches!(status, SignatureStatus::Good | SignatureStatus::Bad) {
+        let mut verified = VERIFIED.lock();
+        if verified.len() >= VERIFIED_CACHE_SIZE {
+            verified.clear();
+        }
+        verified.insert(key, (fingerprint, status));
+    }
+    status
+}
+
+/// 提交头中是否有签名，不需要重新读取提交对象
+pub(crate) fn has_signature(commit: &Commit) -> bool {
+    commit.header_field_bytes("gpgsig").is_ok()
+        || commit.header_field_bytes("gpgsig-sha256").is_ok()
+}
+
+/// 解析 gpg --status-fd 的输出，规则与 git 的 gpg-interface 一致
+fn parse_gpg_status(output: &str) -> SignatureStatus {
+    let mut status = SignatureStatus::CannotCheck;
+    let mut trusted = false;
+    for line in output.lines() {
+        let line = match line.strip_prefix("[GNUPG:] ") {
+            Some(line) => line,
+            None => continue,
+        };
+        let keyword = line.split_whitespace().next().unwrap_or_default();
+        match keyword {
+            "GOODSIG" => status = SignatureStatus::Good,
+            "BADSIG" => status = SignatureStatus::Bad,
+            "EXPSIG" => status = SignatureStatus::Expired,
+            "EXPKEYSIG" => status = SignatureStatus::ExpiredKey,
+            "REVKEYSIG" => status = SignatureStatus::RevokedKey,
+            "ERRSIG" => status = SignatureStatus::CannotCheck,
+            "TRUST_MARGINAL" | "TRUST_FULLY" | "TRUST_ULTIMATE" => trusted = true,
+            _ => {}
+        }
+    }
+    if status == SignatureStatus::Good && !trusted {
+        return SignatureStatus::UnknownValidity;
+    }
+    status
+}
+
+/// 运行外部程序，将data写入标准输入，返回(是否成功, stdout, stderr)
+fn run_with_stdin(
+    mut cmd: Command,
+    data: &[u8],
+) -> Result<(bool, String, String), GitUtilsErrorCode> {
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000);
+    cmd.stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = cmd.spawn().map_err(|e| {
+        GitUtilsErrorCode::SignCommitError(format!("failed to run {:?}: {}", cmd.get_program(), e))
+    })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // 程序可能不读取标准输入就退出，写入失败时忽略
+        let _ = stdin.write_all(data);
+    }
+    let output = child.wait_with_output()?;
+    Ok((
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+    ))
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
+        let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"));
+        if let Some(home) = home {
+            return PathBuf::from(home).join(rest);
+        }
+    }
+    PathBuf::from(path)
+}
+
+/// 写入临时文件，文件以独占方式创建，离开作用域时删除
+fn temp_file(content: &[u8]) -> Result<NamedTempFile, GitUtilsErrorCode> {
+    let mut file = tempfile::Builder::new().prefix("giter-sign-").tempfile()?;
+    file.write_all(content)?;
+    file.flush()?;
+    Ok(file)
+}
@@ -3,8 +3,9 @@ use git2::Status;
 use serde::{Deserialize, Serialize};
 
 bitflags! {
-  /// 工作状态，是否修改，是否未提交，是否未推送，是否正常
+  /// 工作状态，是否修改，是否未提交，是否未推送，是否落后于远程，是否正常
   /// Modified状态中包含工作空间的新建、修改和删除
+  /// Submodule状态表示子模块检出的提交与记录的不同或者子模块中有修改，这时不会标记为Modified
   #[derive(Debug, Clone)]
   pub struct WorkStatus: u32 {
     const None = 0;
@@ -14,6 +15,8 @@ bitflags! {
     const Untracked = 1 << 3;
     const Uncommitted = 1 << 4;
     const Unpushed =  1 << 5;
+    const Behind = 1 << 6;
+    const Submodule = 1 << 7;
   }
 }
 
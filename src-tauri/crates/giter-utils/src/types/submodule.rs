@@ -0,0 +1,51 @@
+use git2::SubmoduleStatus;
+use serde::{Deserialize, Serialize};
+
+/// 仓库中的子模块
+///
+/// - recorded_id: 父仓库 HEAD 中记录的提交
+/// - index_id: 父仓库索引中记录的提交
+/// - checked_out_id: 子模块工作目录中检出的提交，未检出时为空
+/// - initialized: 子模块是否已经写入 .git/config(git submodule init)
+/// - checked_out: 子模块是否已经检出到工作目录
+/// - dirty: 子模块工作目录中有修改或者未跟踪的文件
+/// - out_of_date: 子模块检出的提交与父仓库索引中记录的提交不同
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Submodule {
+    pub name: String,
+    pub path: String,
+    pub url: Option<String>,
+    pub branch: Option<String>,
+    pub recorded_id: Option<String>,
+    pub index_id: Option<String>,
+    pub checked_out_id: Option<String>,
+    pub initialized: bool,
+    pub checked_out: bool,
+    pub dirty: bool,
+    pub out_of_date: bool,
+}
+
+impl Submodule {
+    pub fn new(submodule: &git2::Submodule, status: SubmoduleStatus, initialized: bool) -> Self {
+        let id = |id: Option<git2::Oid>| id.map(|id| id.to_string());
+        let dirty = SubmoduleStatus::WD_INDEX_MODIFIED
+            | SubmoduleStatus::WD_WD_MODIFIED
+            | SubmoduleStatus::WD_UNTRACKED;
+        let checked_out = status.contains(SubmoduleStatus::IN_WD)
+            && !status.contains(SubmoduleStatus::WD_UNINITIALIZED);
+        Submodule {
+            name: submodule.name().unwrap_or_default().to_string(),
+            path: submodule.path().to_string_lossy().replace('\\', "/"),
+            url: submodule.url().map(String::from),
+            branch: submodule.branch().map(String::from),
+            recorded_id: id(submodule.head_id()),
+            index_id: id(submodule.index_id()),
+            checked_out_id: id(submodule.workdir_id()),
+            initialized,
+            checked_out,
+            dirty: status.intersects(dirty),
+            out_of_date: checked_out && status.contains(SubmoduleStatus::WD_MODIFIED),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use git2::RepositoryState;
+use serde::{Deserialize, Serialize};
+
+use super::commit::Commit;
+use super::status::WorkStatus;
+
+/// 仓库中正在进行、还没有完成的操作
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum InProgressOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+    ApplyMailbox,
+}
+
+impl InProgressOperation {
+    pub fn from_state(state: RepositoryState) -> Option<Self> {
+        match state {
+            RepositoryState::Clean => None,
+            RepositoryState::Merge => Some(InProgressOperation::Merge),
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge => Some(InProgressOperation::Rebase),
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+                Some(InProgressOperation::CherryPick)
+            }
+            RepositoryState::Revert | RepositoryState::RevertSequence => {
+                Some(InProgressOperation::Revert)
+            }
+            RepositoryState::Bisect => Some(InProgressOperation::Bisect),
+            RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => {
+                Some(InProgressOperation::ApplyMailbox)
+            }
+        }
+    }
+}
+
+/// 仓库概览
+///
+/// - branch: 当前分支，分离头指针时为空
+/// - upstream: 当前分支跟踪的远程分支，没有跟踪时为空
+/// - ahead/behind: 当前分支领先/落后于跟踪分支的提交数，没有跟踪分支时为0
+/// - last_commit: HEAD 指向的提交，空仓库为空
+/// - operation: 正在进行的合并、变基等操作
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoSummary {
+    pub status: WorkStatus,
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    pub stash_count: usize,
+    pub last_commit: Option<Commit>,
+    pub operation: Option<InProgressOperation>,
+}
@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock};
+
+use git2::Oid;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use super::commit::Commit;
+use super::fs::EntryMode;
+
+/// 最多缓存的目录数量
+const LAST_COMMITS_CACHE_SIZE: usize = 64;
+
+/// 最多缓存的路径索引数量，Linux 内核这样的仓库一个索引有几MB
+const PATH_INDEX_CACHE_SIZE: usize = 8;
+
+/// (仓库路径, 树id)
+type TreeKey = (PathBuf, Oid);
+
+/// 已生成的路径索引，树不会改变，缓存不需要失效
+static PATH_INDEXES: LazyLock<Mutex<HashMap<TreeKey, Arc<PathIndex>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// (仓库路径, 提交id, 目录路径)
+type DirKey = (PathBuf, Oid, String);
+
+/// 目录中每一项最后一次修改的提交，key 为项的名称
+pub type LastCommits = HashMap<String, LastCommit>;
+
+/// 已计算的目录的结果，提交的历史不会改变，缓存不需要失效
+static LAST_COMMITS: LazyLock<Mutex<HashMap<DirKey, Arc<LastCommits>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 目录项的类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TreeEntryKind {
+    File,
+    Dir,
+    Link,
+    Submodule,
+}
+
+/// 根据树中的文件模式判断类型
+impl From<i32> for TreeEntryKind {
+    fn from(mode: i32) -> Self {
+        match mode {
+            0o040000 => TreeEntryKind::Dir,
+            0o120000 => TreeEntryKind::Link,
+            0o160000 => TreeEntryKind::Submodule,
+            _ => TreeEntryKind::File,
+        }
+    }
+}
+
+/// 目录中的一项，path 为相对仓库根目录的路径
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeEntry {
+    pub name: String,
+    pub path: String,
+    pub kind: TreeEntryKind,
+    pub mode: EntryMode,
+    pub object_id: String,
+}
+
+/// 目录列表的一页，cursor 为这一页最后一项的名称，为空时没有更多的项
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreePage {
+    pub entries: Vec<TreeEntry>,
+    pub cursor: Option<String>,
+}
+
+/// 按需获取的目录项信息
+///
+/// - size: 文件大小，目录和子模块为空
+/// - last_commit: 最后一次修改这一项的提交
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeEntryDetail {
+    pub path: String,
+    pub size: Option<usize>,
+    pub last_commit: Option<Commit>,
+}
+
+/// 最后一次修改目录项的提交，datetime 为毫秒时间戳
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LastCommit {
+    pub commit_id: String,
+    pub title: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub datetime: i64,
+}
+
+impl From<&git2::Commit<'_>> for LastCommit {
+    fn from(commit: &git2::Commit) -> Self {
+        let author = commit.author();
+        LastCommit {
+            commit_id: commit.id().to_string(),
+            title: commit.summary().unwrap_or_default().to_string(),
+            author_name: author.name().unwrap_or_default().to_string(),
+            author_email: author.email().unwrap_or_default().to_string(),
+            datetime: commit.time().seconds() * 1000,
+        }
+    }
+}
+
+impl LastCommit {
+    pub(crate) fn cached(key: &DirKey) -> Option<Arc<LastCommits>> {
+        LAST_COMMITS.lock().get(key).cloned()
+    }
+
+    pub(crate) fn cache(key: DirKey, commits: LastCommits) -> Arc<LastCommits> {
+        let commits = Arc::new(commits);
+        let mut cache = LAST_COMMITS.lock();
+        if cache.len() >= LAST_COMMITS_CACHE_SIZE {
+            cache.clear();
+        }
+        cache.insert(key, commits.clone());
+        commits
+    }
+}
+
+/// 模糊匹配的结果，positions 为匹配的字符在路径中的字节位置
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuzzyMatch {
+    pub path: String,
+    pub score: i64,
+    pub positions: Vec<usize>,
+}
+
+/// 树中所有文件的路径(不包含目录)，按 git 的顺序排列
+#[derive(Debug, Clone, Default)]
+pub struct PathIndex {
+    pub paths: Vec<String>,
+}
+
+impl PathIndex {
+    pub(crate) fn cached(repo: PathBuf, tree: Oid) -> Option<Arc<PathIndex>> {
+        PATH_INDEXES.lock().get(&(repo, tree)).cloned()
+    }
+
+    pub(crate) fn cache(repo: PathBuf, tree: Oid, index: PathIndex) -> Arc<PathIndex> {
+        let index = Arc::new(index);
+        let mut indexes = PATH_INDEXES.lock();
+        if indexes.len() >= PATH_INDEX_CACHE_SIZE {
+            indexes.clear();
+        }
+        indexes.insert((repo, tree), index.clone());
+        index
+    }
+
+    /// 按模糊匹配的得分从高到低返回最多 limit 个路径，query 为空时按顺序返回
+    pub fn fuzzy_find(&self, query: &str, limit: usize) -> Vec<FuzzyMatch> {
+        let query = query
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect::<Vec<_>>();
+        if query.is_empty() {
+            return self
+                .paths
+                .iter()
+                .take(limit)
+                .map(|path| FuzzyMatch {
+                    path: path.clone(),
+                    score: 0,
+                    positions: vec![],
+                })
+                .collect();
+        }
+        let mut matches = self
+            .paths
+            .iter()
+            .filter_map(|path| fuzzy_score(&query, path))
+            .collect::<Vec<_>>();
+        let order = |a: &FuzzyMatch, b: &FuzzyMatch| {
+            b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path))
+        };
+        // 只需要对得分最高的 limit 个排序
+        if limit < matches.len() {
+            matches.select_nth_unstable_by(limit, order);
+            matches.truncate(limit);
+        }
+        matches.sort_by(order);
+        matches
+    }
+}
+
+/// query 的字符需要按顺序(不区分大小写)出现在路径中
+///
+/// 从文件名开始尽量靠后地匹配，连续的字符、单词开头的字符和文件名中的字符得分更高，
+/// 路径越长得分越低
+fn fuzzy_score(query: &[char], path: &str) -> Option<FuzzyMatch> {
+    let chars = path.char_indices().collect::<Vec<_>>();
+    let name_start = path.rfind('/').map_or(0, |idx| idx + 1);
+    // 从后往前贪心匹配，让匹配尽量落在文件名中
+    let mut positions = Vec::with_capacity(query.len());
+    let mut idx = chars.len();
+    for q in query.iter().rev() {
+        loop {
+            if idx == 0 {
+                return None;
+            }
+            idx -= 1;
+            if chars[idx].1.to_lowercase().eq(std::iter::once(*q)) {
+                positions.push(idx);
+                break;
+            }
+        }
+    }
+    positions.reverse();
+    let mut score = 0i64;
+    for (n, &idx) in positions.iter().enumerate() {
+        score += 1;
+        if n > 0 && positions[n - 1] + 1 == idx {
+            score += 5;
+        }
+        let prev = idx.checked_sub(1).map(|p| chars[p].1);
+        let is_start = match prev {
+            None => true,
+            Some(prev) => {
+                matches!(prev, '/' | '_' | '-' | '.' | ' ')
+                    || (prev.is_lowercase() && chars[idx].1.is_uppercase())
+            }
+        };
+        if is_start {
+            score += 8;
+        }
+        if chars[idx].0 >= name_start {
+            score += 2;
+        }
+    }
+    score -= (chars.len() / 8) as i64;
+    Some(FuzzyMatch {
+        path: path.to_string(),
+        score,
+        positions: positions.into_iter().map(|idx| chars[idx].0).collect(),
+    })
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// 仓库的工作树
+///
+/// - name: 工作树的名称，主工作树为目录名
+/// - branch: 检出的本地分支名，分离头指针时为空
+/// - is_main: 是否是主工作树(仓库本身)
+/// - lock_reason: 锁定的原因，锁定时没有填写原因为空字符串
+/// - prunable: 工作树的目录已经不存在，可以被清理
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Worktree {
+    pub name: String,
+    pub path: String,
+    pub branch: Option<String>,
+    pub head_id: Option<String>,
+    pub is_main: bool,
+    pub locked: bool,
+    pub lock_reason: Option<String>,
+    pub prunable: bool,
+}
+
+/// 添加工作树选项
+///
+/// - branch: 检出的分支，为空时以工作树名称从 HEAD 创建新分支
+/// - new_branch: 为 true 时从 HEAD 创建 branch 分支，否则检出已经存在的本地分支
+/// - lock: 创建后锁定工作树
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AddWorktreeOptions {
+    pub branch: Option<String>,
+    pub new_branch: bool,
+    pub lock: bool,
+}
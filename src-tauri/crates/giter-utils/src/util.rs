@@ -23,34 +23,7 @@ pub fn has_git() -> bool {
 }
 
 pub fn build_commit(commit: &Git2Commit, repo: &Repository) -> Commit {
-    let message = if let Some(message) = commit.message() {
-        message.to_string()
-    } else {
-        "".to_string()
-    };
-    let committer = commit.committer();
-    let author = commit.author();
-    let mut parents = commit.parent_ids().into_iter().collect::<Vec<Oid>>();
-    parents.sort_by(|a, b| {
-        repo.find_commit(*b)
-            .unwrap()
-            .time()
-            .seconds()
-            .cmp(&repo.find_commit(*a).unwrap().time().seconds())
-    });
-    let path = repo.workdir().unwrap().to_str().unwrap().to_string();
-    Commit::new(
-        commit.id().to_string(),
-        author.name().unwrap_or("").to_string(),
-        author.email().unwrap_or("").to_string(),
-        committer.name().unwrap_or("").to_string(),
-        committer.email().unwrap_or("").to_string(),
-        message.lines().next().unwrap_or("").to_string(),
-        message,
-        commit.time().seconds() * 1000,
-        parents,
-        path,
-    )
+    Commit::new(commit, repo)
 }
 
 pub fn change_status_to_file_status(change: &Delta) -> FileStatus {
@@ -155,21 +128,21 @@ pub fn set_owner(path: &str) -> Result<bool, git2::Error> {
 
 pub fn object_is_binary(oid: Oid, repo: &Repository) -> bool {
     match repo.find_blob(oid) {
-        Ok(blob) => is_binary_file_content(blob.content().into()),
+        Ok(blob) => is_binary_file_content(blob.content()),
         Err(_) => return false,
     }
 }
 
 // 判断文件是否为二进制文件(直接输入文件内容)
 /// 返回 `true` 表示是二进制文件，`false` 表示是文本文件
-pub fn is_binary_file_content(content: Vec<u8>) -> bool {
+pub fn is_binary_file_content(content: &[u8]) -> bool {
     // 读取前 1024 字节用于检测（可根据需要调整）
     let head_len = if content.len() < 1024 {
         content.len()
     } else {
         1024
     };
-    let head = content[..head_len].to_vec();
+    let head = &content[..head_len];
 
     // 空字节检查
     if head.contains(&0x00) {
@@ -182,15 +155,8 @@ pub fn is_binary_file_content(content: Vec<u8>) -> bool {
         .filter(|&&byte| !matches!(byte, 0x09 | 0x0A | 0x0D | 0x20..=0xFF))
         .count();
 
-    let non_printable = content
-        .iter()
-        .filter(|&&byte| !matches!(byte, 0x09 | 0x0A | 0x0D | 0x20..=0xFF));
-
     // 如果不可打印字符超过 5%，视为二进制文件
     let threshold = content.len() / 20; // 5%
-    if non_printable_count > threshold {
-        println!("{:?}", non_printable);
-    }
     non_printable_count > threshold
 }
 
@@ -205,7 +171,7 @@ pub fn is_binary_file<P: AsRef<Path>>(path: P) -> std::io::Result<bool> {
     let bytes_read = file.read(&mut buffer)?;
     let content = &buffer[..bytes_read];
 
-    let ret = is_binary_file_content(content.to_vec());
+    let ret = is_binary_file_content(content);
     Ok(ret)
 }
 
@@ -319,3 +285,51 @@ pub fn time_to_ymd(stamp: i64) -> Result<String> {
     // 包含时间和时区信息，使 git --since/--until 能精确匹配用户选择的日期范围
     Ok(datetime.format("%Y-%m-%d %H:%M:%S %z").to_string())
 }
+
+/// 名称或路径是否匹配通配符，`*` 匹配任意个字符(包括 `/`)，`?` 匹配一个字符
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((bp, bn)) = backtrack {
+            p = bp + 1;
+            n = bn + 1;
+            backtrack = Some((bp, bn + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// 构建搜索用的正则表达式，regex 为 false 时按字面文本匹配，word 为 true 时只匹配完整的单词
+pub fn build_regex(
+    pattern: &str,
+    regex: bool,
+    ignore_case: bool,
+    word: bool,
+) -> Result<regex::Regex, GitUtilsErrorCode> {
+    if pattern.is_empty() {
+        return Err(GitUtilsErrorCode::InvalidPattern(pattern.to_string()));
+    }
+    let mut pattern = if regex {
+        pattern.to_string()
+    } else {
+        regex::escape(pattern)
+    };
+    if word {
+        pattern = format!(r"\b(?:{})\b", pattern);
+    }
+    regex::RegexBuilder::new(&pattern)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|e| GitUtilsErrorCode::InvalidPattern(e.to_string()))
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use common::{commit_file, init_repo};
+use giter_utils::types::{
+    clone::CloneOptions, error::GitUtilsErrorCode, fetch::CancelToken,
+    git_data_provider::GitDataProvider,
+};
+
+fn file_url(provider: &GitDataProvider) -> String {
+    format!("file://{}", provider.workdir().display())
+}
+
+fn clone(
+    url: &str,
+    path: &std::path::Path,
+    options: &CloneOptions,
+) -> Result<GitDataProvider, GitUtilsErrorCode> {
+    GitDataProvider::clone_repo(url, path, options, None, &CancelToken::new(), |_| {})
+}
+
+#[test]
+fn test_clone_with_progress() {
+    let (_upstream_dir, upstream) = init_repo();
+    let head = commit_file(&upstream, "a.txt", "a", "first");
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cloned");
+
+    let mut progress = vec![];
+    let provider = GitDataProvider::clone_repo(
+        &file_url(&upstream),
+        &path,
+        &CloneOptions::default(),
+        None,
+        &CancelToken::new(),
+        |p| progress.push(p),
+    )
+    .unwrap();
+    assert_eq!(provider.repository.head().unwrap().target(), Some(head));
+    assert_eq!(std::fs::read_to_string(path.join("a.txt")).unwrap(), "a");
+    let last = progress.last().unwrap();
+    assert!(last.done);
+    assert_eq!(last.received_objects, last.total_objects);
+}
+
+#[test]
+fn test_clone_branch_and_bare() {
+    let (_upstream_dir, upstream) = init_repo();
+    commit_file(&upstream, "a.txt", "a", "first");
+    let repo = &upstream.repository;
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.branch("feature", &head, false).unwrap();
+    repo.set_head("refs/heads/feature").unwrap();
+    let feature = commit_file(&upstream, "b.txt", "b", "feature");
+    repo.set_head("refs/heads/master").unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let options = CloneOptions {
+        branch: Some("feature".to_string()),
+        ..Default::default()
+    };
+    let provider = clone(&file_url(&upstream), &dir.path().join("work"), &options).unwrap();
+    let head = provider.repository.head().unwrap();
+    assert_eq!(head.shorthand(), Some("feature"));
+    assert_eq!(head.target(), Some(feature));
+
+    let options = CloneOptions {
+        bare: true,
+        ..Default::default()
+    };
+    let provider = clone(&file_url(&upstream), &dir.path().join("bare.git"), &options).unwrap();
+    assert!(provider.repository.is_bare());
+    // 裸仓库中也能读取提交
+    let commits = provider.commits(10).unwrap();
+    assert_eq!(commits[0].title, "first");
+    assert_eq!(
+        commits[0].repo,
+        provider.repository.path().to_string_lossy()
+    );
+}
+
+#[test]
+fn test_clone_recurse_submodules() {
+    let (_lib_dir, lib) = init_repo();
+    let lib_head = commit_file(&lib, "lib.txt", "lib", "lib");
+    let (_upstream_dir, upstream) = init_repo();
+    commit_file(&upstream, "a.txt", "a", "first");
+    let mut submodule = upstream
+        .repository
+        .submodule(&file_url(&lib), std::path::Path::new("lib"), true)
+        .unwrap();
+    submodule.clone(None).unwrap();
+    submodule.add_finalize().unwrap();
+    upstream.commit("add submodule", None).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cloned");
+    let options = CloneOptions {
+        recurse_submodules: true,
+        ..Default::default()
+    };
+    let provider = clone(&file_url(&upstream), &path, &options).unwrap();
+    let submodule = provider.repository.find_submodule("lib").unwrap();
+    assert_eq!(submodule.workdir_id(), Some(lib_head));
+    assert_eq!(
+        std::fs::read_to_string(path.join("lib/lib.txt")).unwrap(),
+        "lib"
+    );
+}
+
+#[test]
+fn test_clone_cancelled() {
+    let (_upstream_dir, upstream) = init_repo();
+    commit_file(&upstream, "a.txt", "a", "first");
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cloned");
+    let cancel = CancelToken::new();
+    cancel.cancel();
+
+    let ret = GitDataProvider::clone_repo(
+        &file_url(&upstream),
+        &path,
+        &CloneOptions::default(),
+        None,
+        &cancel,
+        |_| {},
+    );
+    assert!(matches!(ret, Err(GitUtilsErrorCode::OperationCancelled)));
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_clone_destination_not_empty() {
+    let (_upstream_dir, upstream) = init_repo();
+    commit_file(&upstream, "a.txt", "a", "first");
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("file.txt"), "").unwrap();
+
+    let ret = clone(&file_url(&upstream), dir.path(), &CloneOptions::default());
+    assert!(matches!(
+        ret,
+        Err(GitUtilsErrorCode::DestinationNotEmpty(_))
+    ));
+    assert!(dir.path().join("file.txt").exists());
+}
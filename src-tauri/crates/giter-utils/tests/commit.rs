@@ -0,0 +1,150 @@
+mod common;
+
+use common::{USER_EMAIL, USER_NAME, commit_file, file_at, init_repo, stage_file};
+use giter_utils::types::{author::Author, commit_options::CommitOptions, error::GitUtilsErrorCode};
+
+#[test]
+fn test_initial_commit_in_empty_repo() {
+    let (_dir, provider) = init_repo();
+    stage_file(&provider, "README.md", "hello");
+    let oid = provider.commit("init", None).unwrap();
+    let commit = provider.repository.find_commit(oid).unwrap();
+    assert_eq!(commit.parent_count(), 0);
+    assert_eq!(commit.message(), Some("init"));
+    assert_eq!(provider.repository.head().unwrap().target(), Some(oid));
+}
+
+#[test]
+fn test_commit_without_staged_files() {
+    let (_dir, provider) = init_repo();
+    commit_file(&provider, "a.txt", "a", "first");
+    let ret = provider.commit("nothing", None);
+    assert!(matches!(ret, Err(GitUtilsErrorCode::NoStagedFile)));
+}
+
+#[test]
+fn test_allow_empty_commit() {
+    let (_dir, provider) = init_repo();
+    let first = commit_file(&provider, "a.txt", "a", "first");
+    let options = CommitOptions {
+        allow_empty: true,
+        ..Default::default()
+    };
+    let oid = provider
+        .commit_with_options("empty", None, &options)
+        .unwrap();
+    let commit = provider.repository.find_commit(oid).unwrap();
+    assert_eq!(commit.parent_ids().collect::<Vec<_>>(), vec![first]);
+    assert_eq!(
+        commit.tree_id(),
+        provider.repository.find_commit(first).unwrap().tree_id()
+    );
+}
+
+#[test]
+fn test_amend_message() {
+    let (_dir, provider) = init_repo();
+    let first = commit_file(&provider, "a.txt", "a", "first");
+    let second = commit_file(&provider, "b.txt", "b", "second");
+    let options = CommitOptions {
+        amend: true,
+        ..Default::default()
+    };
+    let oid = provider
+        .commit_with_options("second amended", None, &options)
+        .unwrap();
+    assert_ne!(oid, second);
+    let commit = provider.repository.find_commit(oid).unwrap();
+    assert_eq!(commit.message(), Some("second amended"));
+    assert_eq!(commit.parent_ids().collect::<Vec<_>>(), vec![first]);
+    assert_eq!(file_at(&provider, oid, "b.txt").as_deref(), Some("b"));
+}
+
+#[test]
+fn test_amend_content_keeps_message() {
+    let (_dir, provider) = init_repo();
+    let first = commit_file(&provider, "a.txt", "a", "first");
+    stage_file(&provider, "a.txt", "a changed");
+    let options = CommitOptions {
+        amend: true,
+        ..Default::default()
+    };
+    let oid = provider.commit_with_options("", None, &options).unwrap();
+    let commit = provider.repository.find_commit(oid).unwrap();
+    assert_ne!(oid, first);
+    assert_eq!(commit.message(), Some("first"));
+    assert_eq!(commit.parent_count(), 0);
+    assert_eq!(
+        file_at(&provider, oid, "a.txt").as_deref(),
+        Some("a changed")
+    );
+}
+
+#[test]
+fn test_amend_without_commit() {
+    let (_dir, provider) = init_repo();
+    stage_file(&provider, "a.txt", "a");
+    let options = CommitOptions {
+        amend: true,
+        ..Default::default()
+    };
+    let ret = provider.commit_with_options("amend", None, &options);
+    assert!(matches!(ret, Err(GitUtilsErrorCode::NoCommitToAmend)));
+}
+
+#[test]
+fn test_commit_with_author() {
+    let (_dir, provider) = init_repo();
+    stage_file(&provider, "a.txt", "a");
+    let options = CommitOptions {
+        author: Some(Author::new("Alice".into(), "alice@example.com".into())),
+        ..Default::default()
+    };
+    let oid = provider
+        .commit_with_options("by alice", None, &options)
+        .unwrap();
+    let commit = provider.repository.find_commit(oid).unwrap();
+    assert_eq!(commit.author().name(), Some("Alice"));
+    assert_eq!(commit.author().email(), Some("alice@example.com"));
+    assert_eq!(commit.committer().name(), Some(USER_NAME));
+    assert_eq!(commit.committer().email(), Some(USER_EMAIL));
+}
+
+#[test]
+fn test_commit_only_paths() {
+    let (_dir, provider) = init_repo();
+    commit_file(&provider, "a.txt", "a", "first");
+    stage_file(&provider, "a.txt", "a changed");
+    stage_file(&provider, "dir/b.txt", "b");
+    stage_file(&provider, "c.txt", "c");
+    let options = CommitOptions {
+        only_paths: Some(vec!["a.txt".into(), "dir".into()]),
+        ..Default::default()
+    };
+    let oid = provider
+        .commit_with_options("partial", None, &options)
+        .unwrap();
+    assert_eq!(
+        file_at(&provider, oid, "a.txt").as_deref(),
+        Some("a changed")
+    );
+    assert_eq!(file_at(&provider, oid, "dir/b.txt").as_deref(), Some("b"));
+    assert_eq!(file_at(&provider, oid, "c.txt"), None);
+    // 未指定的路径仍然保留在暂存区中
+    let staged = provider.staged_files().unwrap();
+    assert_eq!(staged.len(), 1);
+    assert_eq!(staged[0].path.to_str(), Some("c.txt"));
+}
+
+#[test]
+fn test_commit_only_paths_without_changes() {
+    let (_dir, provider) = init_repo();
+    commit_file(&provider, "a.txt", "a", "first");
+    stage_file(&provider, "c.txt", "c");
+    let options = CommitOptions {
+        only_paths: Some(vec!["a.txt".into()]),
+        ..Default::default()
+    };
+    let ret = provider.commit_with_options("partial", None, &options);
+    assert!(matches!(ret, Err(GitUtilsErrorCode::NoStagedFile)));
+}
@@ -0,0 +1,96 @@
+#![allow(dead_code)]
+
+use std::path::Path;
+
+use git2::{BranchType, Oid, Repository, Signature, Time};
+use giter_utils::types::git_data_provider::GitDataProvider;
+use tempfile::TempDir;
+
+pub const USER_NAME: &str = "Giter Tester";
+pub const USER_EMAIL: &str = "tester@giter.dev";
+
+/// 在临时目录中初始化一个配置好用户信息的空仓库
+pub fn init_repo() -> (TempDir, GitDataProvider) {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("user.name", USER_NAME).unwrap();
+    config.set_str("user.email", USER_EMAIL).unwrap();
+    let provider = GitDataProvider::new(dir.path()).unwrap();
+    (dir, provider)
+}
+
+/// 修改仓库配置
+pub fn set_config(provider: &GitDataProvider, key: &str, value: &str) {
+    let mut config = provider.repository.config().unwrap();
+    config.set_str(key, value).unwrap();
+}
+
+/// 写入工作区文件，path为相对仓库根目录的路径
+pub fn write_file(provider: &GitDataProvider, path: &str, content: &str) {
+    let abs_path = provider.workdir().join(path);
+    if let Some(parent) = abs_path.parent() {
+        std::fs::create_dir_all(parent).unwrap();
+    }
+    std::fs::write(abs_path, content).unwrap();
+}
+
+/// 写入文件并加入暂存区
+pub fn stage_file(provider: &GitDataProvider, path: &str, content: &str) {
+    write_file(provider, path, content);
+    provider
+        .add_to_stage(&Path::new(path).to_path_buf())
+        .unwrap();
+}
+
+/// 写入文件、暂存并提交，返回提交id
+pub fn commit_file(
+    provider: &GitDataProvider,
+    path: &str,
+    content: &str,
+    message: &str,
+) -> git2::Oid {
+    stage_file(provider, path, content);
+    provider.commit(message, None).unwrap()
+}
+
+/// 在空树上创建提交，time 为提交时间(秒)，不会修改 HEAD
+pub fn commit(provider: &GitDataProvider, message: &str, parents: &[Oid], time: i64) -> Oid {
+    let repo = &provider.repository;
+    let sig = Signature::new(USER_NAME, USER_EMAIL, &Time::new(time, 0)).unwrap();
+    let tree = repo
+        .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+        .unwrap();
+    let parents = parents
+        .iter()
+        .map(|id| repo.find_commit(*id).unwrap())
+        .collect::<Vec<_>>();
+    let parents = parents.iter().collect::<Vec<_>>();
+    repo.commit(None, &sig, &sig, message, &tree, &parents)
+        .unwrap()
+}
+
+/// 读取某个提交中文件的内容，文件不存在时返回None
+pub fn file_at(provider: &GitDataProvider, commit: git2::Oid, path: &str) -> Option<String> {
+    let repo = &provider.repository;
+    let tree = repo.find_commit(commit).unwrap().tree().unwrap();
+    let entry = tree.get_path(Path::new(path)).ok()?;
+    let blob = repo.find_blob(entry.id()).unwrap();
+    Some(String::from_utf8_lossy(blob.content()).to_string())
+}
+
+/// 创建裸仓库作为 origin，推送第一个提交并设置上游分支
+pub fn setup_remote(provider: &GitDataProvider) -> (TempDir, Repository) {
+    let remote_dir = tempfile::tempdir().unwrap();
+    let bare = Repository::init_bare(remote_dir.path()).unwrap();
+    let repo = &provider.repository;
+    let mut remote = repo
+        .remote("origin", remote_dir.path().to_str().unwrap())
+        .unwrap();
+    commit_file(provider, "a.txt", "a", "first");
+    remote.push(&["refs/heads/master"], None).unwrap();
+    remote.fetch(&["master"], None, None).unwrap();
+    let mut branch = repo.find_branch("master", BranchType::Local).unwrap();
+    branch.set_upstream(Some("origin/master")).unwrap();
+    (remote_dir, bare)
+}
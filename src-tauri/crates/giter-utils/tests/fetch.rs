@@ -0,0 +1,168 @@
+mod common;
+
+use common::{commit_file, init_repo};
+use git2::Repository;
+use giter_utils::types::{
+    error::GitUtilsErrorCode,
+    fetch::{CancelToken, FetchOptions},
+    git_data_provider::GitDataProvider,
+};
+use tempfile::TempDir;
+
+/// 创建一个远程仓库，并把它作为 name 添加到 provider 中
+fn add_remote(provider: &GitDataProvider, name: &str) -> (TempDir, GitDataProvider) {
+    let (dir, upstream) = init_repo();
+    commit_file(&upstream, "a.txt", "a", "first");
+    provider
+        .repository
+        .remote(name, dir.path().to_str().unwrap())
+        .unwrap();
+    (dir, upstream)
+}
+
+fn fetch(provider: &GitDataProvider, remote: Option<&str>, options: &FetchOptions) {
+    provider
+        .fetch(remote, options, None, &CancelToken::new(), |_| {})
+        .unwrap();
+}
+
+fn remote_ref(provider: &GitDataProvider, name: &str) -> Option<git2::Oid> {
+    provider.repository.refname_to_id(name).ok()
+}
+
+#[test]
+fn test_fetch_remote() {
+    let (_dir, provider) = init_repo();
+    let (_remote_dir, upstream) = add_remote(&provider, "origin");
+    let head = upstream.repository.head().unwrap().target();
+
+    let mut progress = vec![];
+    provider
+        .fetch(
+            Some("origin"),
+            &FetchOptions::default(),
+            None,
+            &CancelToken::new(),
+            |p| progress.push(p),
+        )
+        .unwrap();
+    assert_eq!(remote_ref(&provider, "refs/remotes/origin/master"), head);
+    let last = progress.last().unwrap();
+    assert!(last.done);
+    assert_eq!(last.remote, "origin");
+    assert_eq!(last.received_objects, last.total_objects);
+    assert!(last.total_objects > 0);
+}
+
+#[test]
+fn test_fetch_all_remotes() {
+    let (_dir, provider) = init_repo();
+    let (_origin_dir, origin) = add_remote(&provider, "origin");
+    let (_fork_dir, fork) = add_remote(&provider, "fork");
+    commit_file(&fork, "b.txt", "b", "second");
+
+    fetch(&provider, None, &FetchOptions::default());
+    assert_eq!(
+        remote_ref(&provider, "refs/remotes/origin/master"),
+        origin.repository.head().unwrap().target()
+    );
+    assert_eq!(
+        remote_ref(&provider, "refs/remotes/fork/master"),
+        fork.repository.head().unwrap().target()
+    );
+}
+
+#[test]
+fn test_fetch_prune() {
+    let (_dir, provider) = init_repo();
+    let (_remote_dir, upstream) = add_remote(&provider, "origin");
+    let head = upstream
+        .repository
+        .head()
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    let mut feature = upstream.repository.branch("feature", &head, false).unwrap();
+    fetch(&provider, Some("origin"), &FetchOptions::default());
+    assert!(remote_ref(&provider, "refs/remotes/origin/feature").is_some());
+
+    feature.delete().unwrap();
+    fetch(&provider, Some("origin"), &FetchOptions::default());
+    assert!(remote_ref(&provider, "refs/remotes/origin/feature").is_some());
+    let options = FetchOptions {
+        prune: true,
+        ..Default::default()
+    };
+    fetch(&provider, Some("origin"), &options);
+    assert!(remote_ref(&provider, "refs/remotes/origin/feature").is_none());
+}
+
+#[test]
+fn test_fetch_tags() {
+    let (_dir, provider) = init_repo();
+    let (_remote_dir, upstream) = add_remote(&provider, "origin");
+    let repo: &Repository = &upstream.repository;
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.tag_lightweight("v1", head.as_object(), false).unwrap();
+
+    let options = FetchOptions {
+        tags: Some(false),
+        ..Default::default()
+    };
+    fetch(&provider, Some("origin"), &options);
+    assert!(remote_ref(&provider, "refs/tags/v1").is_none());
+    let options = FetchOptions {
+        tags: Some(true),
+        ..Default::default()
+    };
+    fetch(&provider, Some("origin"), &options);
+    assert_eq!(remote_ref(&provider, "refs/tags/v1"), Some(head.id()));
+}
+
+#[test]
+fn test_fetch_cancelled() {
+    let (_dir, provider) = init_repo();
+    let (_remote_dir, _upstream) = add_remote(&provider, "origin");
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let ret = provider.fetch(
+        Some("origin"),
+        &FetchOptions::default(),
+        None,
+        &cancel,
+        |_| {},
+    );
+    assert!(matches!(ret, Err(GitUtilsErrorCode::OperationCancelled)));
+    assert!(remote_ref(&provider, "refs/remotes/origin/master").is_none());
+}
+
+#[test]
+fn test_fetch_cancelled_without_transfer() {
+    let (_dir, provider) = init_repo();
+    let (_remote_dir, _upstream) = add_remote(&provider, "origin");
+    fetch(&provider, Some("origin"), &FetchOptions::default());
+    // 已经是最新的，不会调用传输进度回调，连接之前也要检查是否取消
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let ret = provider.fetch(
+        Some("origin"),
+        &FetchOptions::default(),
+        None,
+        &cancel,
+        |_| {},
+    );
+    assert!(matches!(ret, Err(GitUtilsErrorCode::OperationCancelled)));
+}
+
+#[test]
+fn test_fetch_remote_not_found() {
+    let (_dir, provider) = init_repo();
+    let ret = provider.fetch(
+        Some("origin"),
+        &FetchOptions::default(),
+        None,
+        &CancelToken::new(),
+        |_| {},
+    );
+    assert!(matches!(ret, Err(GitUtilsErrorCode::RemoteNotFound(_))));
+}
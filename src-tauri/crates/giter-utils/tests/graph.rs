@@ -0,0 +1,201 @@
+mod common;
+
+use common::{commit, init_repo};
+use git2::Oid;
+use giter_utils::types::{
+    git_data_provider::GitDataProvider,
+    graph::{GraphEdge, GraphRow},
+};
+
+fn set_branch(provider: &GitDataProvider, name: &str, id: Oid) {
+    provider
+        .repository
+        .reference(&format!("refs/heads/{}", name), id, true, "")
+        .unwrap();
+}
+
+fn edge(from: usize, to: usize, color: usize) -> GraphEdge {
+    GraphEdge { from, to, color }
+}
+
+fn layout(rows: &[GraphRow]) -> Vec<(String, usize, Vec<GraphEdge>)> {
+    rows.iter()
+        .map(|r| (r.commit.title.clone(), r.column, r.edges.clone()))
+        .collect()
+}
+
+#[test]
+fn test_graph_merge() {
+    let (_dir, provider) = init_repo();
+    let a = commit(&provider, "a", &[], 1);
+    let b = commit(&provider, "b", &[a], 2);
+    let c = commit(&provider, "c", &[a], 3);
+    let m = commit(&provider, "m", &[b, c], 4);
+    set_branch(&provider, "master", m);
+
+    let page = provider
+        .commit_graph(&["master".to_string()], 10, None)
+        .unwrap();
+    assert!(page.cursor.is_none());
+    assert_eq!(
+        layout(&page.rows),
+        [
+            ("m".to_string(), 0, vec![]),
+            ("c".to_string(), 1, vec![edge(0, 0, 0), edge(0, 1, 1)]),
+            ("b".to_string(), 0, vec![edge(0, 0, 0), edge(1, 1, 1)]),
+            // 两条线汇合到 a
+            ("a".to_string(), 0, vec![edge(0, 0, 0), edge(1, 0, 1)]),
+        ]
+    );
+    assert_eq!(page.rows[1].color, 1);
+    assert_eq!(page.rows[1].width, 2);
+}
+
+#[test]
+fn test_graph_octopus_and_multiple_refs() {
+    let (_dir, provider) = init_repo();
+    let a = commit(&provider, "a", &[], 1);
+    let b = commit(&provider, "b", &[a], 2);
+    let c = commit(&provider, "c", &[a], 3);
+    let d = commit(&provider, "d", &[a], 4);
+    let m = commit(&provider, "m", &[b, c, d], 5);
+    let f = commit(&provider, "f", &[a], 6);
+    set_branch(&provider, "master", m);
+    set_branch(&provider, "feature", f);
+
+    let page = provider
+        .commit_graph(&["master".to_string(), "feature".to_string()], 10, None)
+        .unwrap();
+    let rows = layout(&page.rows);
+    assert_eq!(rows[0], ("f".to_string(), 0, vec![]));
+    assert_eq!(rows[1].0, "m");
+    assert_eq!(rows[1].1, 1);
+    // 章鱼合并的三个父提交分别占用一列
+    let octopus = &page.rows[2].edges;
+    assert!(octopus.contains(&edge(1, 1, 1)));
+    assert!(octopus.contains(&edge(1, 2, 2)));
+    assert!(octopus.contains(&edge(1, 3, 3)));
+    assert_eq!(page.rows[2].width, 4);
+    // 三条线依次汇合到 feature 所在的列，最后到达根提交
+    let titles = rows.iter().map(|r| r.0.as_str()).collect::<Vec<_>>();
+    assert_eq!(titles, ["f", "m", "d", "c", "b", "a"]);
+    assert!(rows[3].2.contains(&edge(3, 0, 3)));
+    assert!(rows[4].2.contains(&edge(2, 0, 2)));
+    assert_eq!(rows[5].1, 0);
+    assert_eq!(rows[5].2, [edge(0, 0, 0), edge(1, 0, 1)]);
+}
+
+#[test]
+fn test_graph_paging_matches_full_layout() {
+    let (_dir, provider) = init_repo();
+    let mut main = commit(&provider, "0", &[], 0);
+    let mut side = main;
+    for i in 1..30 {
+        if i % 3 == 0 {
+            side = commit(&provider, &format!("side {}", i), &[side], i);
+        } else if i % 7 == 0 {
+            main = commit(&provider, &format!("merge {}", i), &[main, side], i);
+        } else {
+            main = commit(&provider, &format!("{}", i), &[main], i);
+        }
+    }
+    set_branch(&provider, "master", main);
+    set_branch(&provider, "side", side);
+    let refs = ["master".to_string(), "side".to_string()];
+
+    let full = provider.commit_graph(&refs, 1000, None).unwrap();
+    let mut paged = vec![];
+    let mut cursor = None;
+    loop {
+        let page = provider.commit_graph(&refs, 4, cursor.as_ref()).unwrap();
+        paged.extend(page.rows);
+        cursor = page.cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(paged.len(), 30);
+    assert_eq!(layout(&full.rows), layout(&paged));
+}
+
+#[test]
+fn test_graph_paging_keeps_first_page_tips() {
+    let (_dir, provider) = init_repo();
+    let mut head = commit(&provider, "0", &[], 0);
+    for i in 1..6 {
+        head = commit(&provider, &format!("{}", i), &[head], i);
+    }
+    set_branch(&provider, "master", head);
+    let refs = ["master".to_string()];
+
+    let first = provider.commit_graph(&refs, 3, None).unwrap();
+    // 引用移动后继续翻页，仍然是第一页的历史
+    let moved = commit(&provider, "6", &[head], 6);
+    set_branch(&provider, "master", moved);
+    let second = provider
+        .commit_graph(&refs, 3, first.cursor.as_ref())
+        .unwrap();
+    assert!(second.cursor.is_none());
+    let titles = first
+        .rows
+        .iter()
+        .chain(&second.rows)
+        .map(|r| r.commit.title.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(titles, ["5", "4", "3", "2", "1", "0"]);
+}
+
+#[test]
+fn test_graph_invalid_cursor() {
+    let (_dir, provider) = init_repo();
+    let a = commit(&provider, "a", &[], 1);
+    let b = commit(&provider, "b", &[a], 2);
+    let c = commit(&provider, "c", &[a], 3);
+    let m = commit(&provider, "m", &[b, c], 4);
+    set_branch(&provider, "master", m);
+    let refs = ["master".to_string()];
+
+    let page = provider.commit_graph(&refs, 1, None).unwrap();
+    let cursor = serde_json::to_value(page.cursor.unwrap()).unwrap();
+    let broken = |f: &dyn Fn(&mut serde_json::Value)| {
+        let mut cursor = cursor.clone();
+        f(&mut cursor);
+        let cursor = serde_json::from_value(cursor).unwrap();
+        provider.commit_graph(&refs, 10, Some(&cursor)).unwrap_err()
+    };
+    // 列数和起点数量不一致
+    broken(&|c| c["sources"] = serde_json::json!([]));
+    // 颜色超出范围
+    broken(&|c| c["nextColor"] = serde_json::json!(0));
+    // 无效的提交id
+    broken(&|c| c["lanes"][0] = serde_json::json!("xyz"));
+    broken(&|c| c["tips"][0] = serde_json::json!("xyz"));
+    // offset 超出历史
+    broken(&|c| c["offset"] = serde_json::json!(100));
+}
+
+#[test]
+fn test_graph_parent_newer_than_child() {
+    let (_dir, provider) = init_repo();
+    let a = commit(&provider, "a", &[], 10);
+    let b = commit(&provider, "b", &[a], 5);
+    let c = commit(&provider, "c", &[a], 1);
+    let m = commit(&provider, "m", &[b, c], 20);
+    set_branch(&provider, "master", m);
+
+    // 按时间倒序 a 先于 c 出现，c 不再连线到 a
+    let page = provider
+        .commit_graph(&["master".to_string()], 10, None)
+        .unwrap();
+    assert!(page.cursor.is_none());
+    assert_eq!(
+        layout(&page.rows),
+        [
+            ("m".to_string(), 0, vec![]),
+            ("b".to_string(), 0, vec![edge(0, 0, 0), edge(0, 1, 1)]),
+            ("a".to_string(), 0, vec![edge(0, 0, 0), edge(1, 1, 1)]),
+            ("c".to_string(), 1, vec![edge(1, 1, 1)]),
+        ]
+    );
+    assert_eq!(page.rows[3].width, 2);
+}
@@ -0,0 +1,171 @@
+mod common;
+
+use common::{commit_file, init_repo, stage_file, write_file};
+use git2::{IndexEntry, IndexTime, Repository};
+use giter_utils::types::{
+    error::GitUtilsErrorCode,
+    git_data_provider::GitDataProvider,
+    grep::{GrepOptions, GrepSource},
+};
+
+fn options(pattern: &str) -> GrepOptions {
+    GrepOptions {
+        pattern: pattern.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_grep_commit_with_context() {
+    let (_dir, provider) = init_repo();
+    commit_file(
+        &provider,
+        "src/lib.rs",
+        "fn a() {}\n// TODO one\nfn b() {}\nfn c() {}\n",
+        "first",
+    );
+    let head = commit_file(&provider, "README.md", "todo list\n", "second");
+    write_file(&provider, "README.md", "TODO changed\n");
+
+    let mut opts = options("TODO");
+    opts.context = 1;
+    let result = provider
+        .grep(&GrepSource::Commit(head.to_string()), &opts)
+        .unwrap();
+    assert_eq!(result.files_searched, 2);
+    assert_eq!(result.matches.len(), 1);
+    let found = &result.matches[0];
+    assert_eq!(found.path, "src/lib.rs");
+    assert_eq!(found.line_number, 2);
+    assert_eq!(found.ranges, [(3, 7)]);
+    assert_eq!(found.before[0].content, "fn a() {}");
+    assert_eq!(found.after.len(), 1);
+    assert_eq!(found.after[0].line_number, 3);
+
+    opts.ignore_case = true;
+    let result = provider
+        .grep(&GrepSource::Commit("HEAD".to_string()), &opts)
+        .unwrap();
+    let paths = result
+        .matches
+        .iter()
+        .map(|m| m.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(paths, ["README.md", "src/lib.rs"]);
+}
+
+#[test]
+fn test_grep_worktree_and_index() {
+    let (_dir, provider) = init_repo();
+    commit_file(&provider, ".gitignore", "target/\n", "ignore");
+    stage_file(&provider, "a.txt", "needle staged\n");
+    write_file(&provider, "a.txt", "needle in worktree\n");
+    write_file(&provider, "b/c.txt", "another needle\n");
+    write_file(&provider, "target/out.txt", "needle ignored\n");
+    write_file(&provider, "image.bin", "needle\0\0\0binary");
+
+    let result = provider
+        .grep(&GrepSource::Worktree, &options("needle"))
+        .unwrap();
+    let found = result
+        .matches
+        .iter()
+        .map(|m| (m.path.as_str(), m.content.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            ("a.txt", "needle in worktree"),
+            ("b/c.txt", "another needle")
+        ]
+    );
+
+    let result = provider
+        .grep(&GrepSource::Index, &options("needle"))
+        .unwrap();
+    assert_eq!(result.matches.len(), 1);
+    assert_eq!(result.matches[0].content, "needle staged");
+}
+
+#[test]
+fn test_grep_word_globs_and_limit() {
+    let (_dir, provider) = init_repo();
+    commit_file(
+        &provider,
+        "src/a.rs",
+        "let foo = 1;\nlet foobar = 2;\n",
+        "a",
+    );
+    commit_file(&provider, "src/b.ts", "foo\nfoo\nfoo\n", "b");
+    commit_file(&provider, "docs/c.rs", "foo\n", "c");
+    let head = GrepSource::Commit("HEAD".to_string());
+
+    let mut opts = options("foo");
+    opts.word = true;
+    opts.include = vec!["*.rs".to_string()];
+    opts.exclude = vec!["docs/*".to_string()];
+    let result = provider.grep(&head, &opts).unwrap();
+    assert_eq!(result.matches.len(), 1);
+    assert_eq!(result.matches[0].path, "src/a.rs");
+    assert_eq!(result.matches[0].line_number, 1);
+
+    let mut opts = options("foo");
+    opts.max_results = 3;
+    let result = provider.grep(&head, &opts).unwrap();
+    assert_eq!(result.matches.len(), 3);
+    assert!(result.truncated);
+
+    let mut opts = options("[");
+    opts.regex = true;
+    let ret = provider.grep(&head, &opts);
+    assert!(matches!(ret, Err(GitUtilsErrorCode::InvalidPattern(_))));
+}
+
+#[test]
+fn test_grep_index_skips_conflict_stages() {
+    let (_dir, provider) = init_repo();
+    commit_file(&provider, "a.txt", "TODO base\n", "first");
+    let repo = &provider.repository;
+    // 冲突时暂存区中 stage 1-3 分别为共同祖先、ours 和 theirs
+    let mut index = repo.index().unwrap();
+    for (stage, content) in [
+        (1, "TODO ancestor\n"),
+        (2, "TODO ours\n"),
+        (3, "TODO theirs\n"),
+    ] {
+        let entry = IndexEntry {
+            ctime: IndexTime::new(0, 0),
+            mtime: IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode: 0o100644,
+            uid: 0,
+            gid: 0,
+            file_size: content.len() as u32,
+            id: repo.blob(content.as_bytes()).unwrap(),
+            flags: stage << 12,
+            flags_extended: 0,
+            path: b"b.txt".to_vec(),
+        };
+        index.add(&entry).unwrap();
+    }
+    assert!(index.has_conflicts());
+    index.write().unwrap();
+
+    let result = provider.grep(&GrepSource::Index, &options("TODO")).unwrap();
+    let paths = result
+        .matches
+        .iter()
+        .map(|m| m.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(paths, ["a.txt"]);
+}
+
+#[test]
+fn test_grep_worktree_in_bare_repo() {
+    let dir = tempfile::tempdir().unwrap();
+    Repository::init_bare(dir.path()).unwrap();
+    let provider = GitDataProvider::new(dir.path()).unwrap();
+    let ret = provider.grep(&GrepSource::Worktree, &options("TODO"));
+    assert!(matches!(ret, Err(GitUtilsErrorCode::RepoIsBare(_))));
+}
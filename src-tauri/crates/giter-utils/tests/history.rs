@@ -0,0 +1,136 @@
+mod common;
+
+use std::collections::HashMap;
+use std::process::Command;
+
+use common::{commit, init_repo};
+use git2::Oid;
+use giter_utils::types::{
+    error::GitUtilsErrorCode, git_data_provider::GitDataProvider, worktree::AddWorktreeOptions,
+};
+
+/// a - b ----- m (master)
+///  \- c(较新) -/
+fn setup() -> (tempfile::TempDir, GitDataProvider, [Oid; 4]) {
+    let (dir, provider) = init_repo();
+    let a = commit(&provider, "a", &[], 1);
+    let b = commit(&provider, "b", &[a], 2);
+    let c = commit(&provider, "c", &[a], 3);
+    let m = commit(&provider, "m", &[b, c], 4);
+    provider
+        .repository
+        .reference("refs/heads/master", m, true, "")
+        .unwrap();
+    provider.repository.set_head("refs/heads/master").unwrap();
+    (dir, provider, [a, b, c, m])
+}
+
+#[test]
+fn test_merge_parents_sorted_by_time() {
+    let (_dir, provider, [a, b, c, _]) = setup();
+    let commits = provider.commits(10).unwrap();
+    assert_eq!(commits.len(), 4);
+    assert_eq!(commits[0].parents, [c, b]);
+    let single = commits.iter().find(|c| c.title == "b").unwrap();
+    assert_eq!(single.parents, [a]);
+}
+
+#[test]
+fn test_commits_count() {
+    let (_dir, provider, [_, b, ..]) = setup();
+    assert_eq!(provider.commits_count("master").unwrap(), 4);
+    assert_eq!(provider.commits_count(&b.to_string()).unwrap(), 2);
+    assert_eq!(
+        provider.before_reference_commits_count("master").unwrap(),
+        4
+    );
+    let ret = provider.commits_count("missing");
+    assert!(matches!(ret, Err(GitUtilsErrorCode::BranchNotFound(_))));
+}
+
+#[test]
+fn test_write_commit_graph() {
+    let (_dir, provider, _) = setup();
+    assert!(!provider.has_commit_graph());
+    provider.write_commit_graph().unwrap();
+    assert!(provider.has_commit_graph());
+    // 有 commit-graph 文件时结果不变
+    assert_eq!(provider.commits_count("master").unwrap(), 4);
+    assert_eq!(
+        provider.before_reference_commits_count("master").unwrap(),
+        4
+    );
+    let titles = provider
+        .commits(10)
+        .unwrap()
+        .into_iter()
+        .map(|c| c.title)
+        .collect::<Vec<_>>();
+    assert_eq!(titles.len(), 4);
+    assert_eq!(titles[0], "m");
+}
+
+#[test]
+fn test_history_pages_match_rev_list() {
+    let (_dir, provider) = init_repo();
+    let mut main = commit(&provider, "0", &[], 0);
+    let mut side = main;
+    for i in 1..40 {
+        // 有相同时间的提交，顺序和 git rev-list 一致
+        let time = i / 2;
+        if i % 3 == 0 {
+            side = commit(&provider, &format!("side {}", i), &[side], time);
+        } else if i % 7 == 0 {
+            main = commit(&provider, &format!("merge {}", i), &[main, side], time);
+        } else {
+            main = commit(&provider, &format!("{}", i), &[main], time);
+        }
+    }
+    provider
+        .repository
+        .reference("refs/heads/master", main, true, "")
+        .unwrap();
+    let output = Command::new("git")
+        .args(["rev-list", "master"])
+        .current_dir(provider.workdir())
+        .output()
+        .unwrap();
+    let expected = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|l| l.to_string())
+        .collect::<Vec<_>>();
+
+    let filter = HashMap::new();
+    let mut paged = vec![];
+    for page in 0.. {
+        let commits = provider
+            .reference_commit_filter_details("master", &filter, Some(page * 6), Some(6))
+            .unwrap();
+        if commits.is_empty() {
+            break;
+        }
+        paged.extend(commits.into_iter().map(|c| c.commit_id));
+    }
+    assert_eq!(paged, expected);
+    // 同一个起点的遍历已经保存，再次读取前面的页结果不变
+    let first = provider.get_commits_before(main, 6).unwrap();
+    let first = first.into_iter().map(|c| c.commit_id).collect::<Vec<_>>();
+    assert_eq!(first, expected[..6]);
+}
+
+#[test]
+fn test_commit_graph_in_linked_worktree() {
+    let (_dir, provider, _) = setup();
+    let wt_dir = tempfile::tempdir().unwrap();
+    provider
+        .add_worktree(
+            "wt",
+            wt_dir.path().join("wt"),
+            &AddWorktreeOptions::default(),
+        )
+        .unwrap();
+    provider.write_commit_graph().unwrap();
+    let linked = GitDataProvider::new(wt_dir.path().join("wt")).unwrap();
+    assert!(linked.has_commit_graph());
+}
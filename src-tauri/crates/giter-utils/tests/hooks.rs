@@ -0,0 +1,252 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::os::unix::fs::PermissionsExt;
+
+use common::{commit_file, init_repo, set_config, stage_file};
+use git2::{BranchType, Repository};
+use giter_traits::ExposeError;
+use giter_utils::types::{
+    commit_options::CommitOptions,
+    error::GitUtilsErrorCode,
+    git_data_provider::GitDataProvider,
+    push::{PushMode, PushOptions},
+};
+
+fn write_hook(provider: &GitDataProvider, name: &str, content: &str) {
+    let path = provider.repository.path().join("hooks").join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, content).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+fn test_pre_commit_failed() {
+    let (_dir, provider) = init_repo();
+    write_hook(
+        &provider,
+        "pre-commit",
+        "#!/bin/sh\necho checking\necho lint failed >&2\nexit 3\n",
+    );
+    stage_file(&provider, "a.txt", "a");
+    let err = provider.commit("first", None).unwrap_err();
+    match &err {
+        GitUtilsErrorCode::HookFailed(output) => {
+            assert_eq!(output.hook, "pre-commit");
+            assert_eq!(output.code, Some(3));
+            assert_eq!(output.stdout, "checking\n");
+            assert_eq!(output.stderr, "lint failed\n");
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert_eq!(err.to_string(), "pre-commit hook failed: lint failed");
+    let data = err.data().unwrap();
+    assert_eq!(data["stderr"], "lint failed\n");
+    assert!(provider.repository.head().is_err());
+}
+
+#[test]
+fn test_pre_commit_can_stage_files() {
+    let (_dir, provider) = init_repo();
+    write_hook(
+        &provider,
+        "pre-commit",
+        "#!/bin/sh\necho generated > gen.txt\ngit add gen.txt\n",
+    );
+    stage_file(&provider, "a.txt", "a");
+    let oid = provider.commit("first", None).unwrap();
+    assert_eq!(
+        common::file_at(&provider, oid, "gen.txt").as_deref(),
+        Some("generated\n")
+    );
+}
+
+#[test]
+fn test_commit_msg_hook_rewrites_message() {
+    let (_dir, provider) = init_repo();
+    write_hook(
+        &provider,
+        "prepare-commit-msg",
+        "#!/bin/sh\n[ \"$2\" = message ] || exit 1\nprintf '[app] ' | cat - \"$1\" > \"$1.tmp\" && mv \"$1.tmp\" \"$1\"\n",
+    );
+    write_hook(
+        &provider,
+        "commit-msg",
+        "#!/bin/sh\nprintf '\\n# comment\\n\\nSigned-off-by: tester\\n' >> \"$1\"\n",
+    );
+    stage_file(&provider, "a.txt", "a");
+    let oid = provider.commit("first", None).unwrap();
+    let commit = provider.repository.find_commit(oid).unwrap();
+    assert_eq!(
+        commit.message(),
+        Some("[app] first\n\nSigned-off-by: tester\n")
+    );
+}
+
+#[test]
+fn test_commit_msg_hook_rejects_message() {
+    let (_dir, provider) = init_repo();
+    write_hook(
+        &provider,
+        "commit-msg",
+        "#!/bin/sh\ngrep -q JIRA- \"$1\" || { echo missing issue id >&2; exit 1; }\n",
+    );
+    stage_file(&provider, "a.txt", "a");
+    let ret = provider.commit("first", None);
+    assert!(matches!(ret, Err(GitUtilsErrorCode::HookFailed(ref o)) if o.hook == "commit-msg"));
+    assert!(provider.commit("JIRA-1 first", None).is_ok());
+}
+
+#[test]
+fn test_no_verify_skips_hooks() {
+    let (_dir, provider) = init_repo();
+    write_hook(&provider, "pre-commit", "#!/bin/sh\nexit 1\n");
+    write_hook(&provider, "commit-msg", "#!/bin/sh\nexit 1\n");
+    set_config(&provider, "giter.conventionalCommits", "true");
+    stage_file(&provider, "a.txt", "a");
+    let options = CommitOptions {
+        no_verify: true,
+        ..Default::default()
+    };
+    assert!(provider.commit_with_options("wip", None, &options).is_ok());
+}
+
+#[test]
+fn test_hook_not_executable_is_ignored() {
+    let (_dir, provider) = init_repo();
+    write_hook(&provider, "pre-commit", "#!/bin/sh\nexit 1\n");
+    let path = provider.repository.path().join("hooks/pre-commit");
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+    stage_file(&provider, "a.txt", "a");
+    assert!(provider.commit("first", None).is_ok());
+}
+
+#[test]
+fn test_conventional_commit_validation() {
+    let (_dir, provider) = init_repo();
+    set_config(&provider, "giter.conventionalCommits", "true");
+    stage_file(&provider, "a.txt", "a");
+    for message in ["add a file", "feature: add a file", "feat: add\nbody"] {
+        let ret = provider.commit(message, None);
+        assert!(
+            matches!(ret, Err(GitUtilsErrorCode::ConventionalCommitInvalid(_))),
+            "{} should be invalid",
+            message
+        );
+    }
+    assert!(
+        provider
+            .commit("feat(core)!: add a file\n\nbody", None)
+            .is_ok()
+    );
+
+    set_config(
+        &provider,
+        "giter.conventionalCommitTypes",
+        "feature, bugfix",
+    );
+    stage_file(&provider, "b.txt", "b");
+    assert!(provider.commit("feat: add b", None).is_err());
+    assert!(provider.commit("feature: add b", None).is_ok());
+}
+
+#[test]
+fn test_pre_push_hook() {
+    let (_dir, provider) = init_repo();
+    let remote_dir = tempfile::tempdir().unwrap();
+    Repository::init_bare(remote_dir.path()).unwrap();
+    let repo = &provider.repository;
+    let remote_url = remote_dir.path().to_str().unwrap();
+    let mut remote = repo.remote("origin", remote_url).unwrap();
+    let first = commit_file(&provider, "a.txt", "a", "first");
+    remote.push(&["refs/heads/master"], None).unwrap();
+    repo.reference("refs/remotes/origin/master", first, true, "")
+        .unwrap();
+    let mut branch = repo.find_branch("master", BranchType::Local).unwrap();
+    branch.set_upstream(Some("origin/master")).unwrap();
+    let second = commit_file(&provider, "b.txt", "b", "second");
+
+    let args = provider.repository.path().join("pre-push.args");
+    write_hook(
+        &provider,
+        "pre-push",
+        &format!(
+            "#!/bin/sh\necho \"$1 $2\" > {}\ncat >> {}\necho push rejected >&2\nexit 1\n",
+            args.display(),
+            args.display()
+        ),
+    );
+    let ret = provider.push("origin", "master", None, &PushOptions::default());
+    assert!(matches!(ret, Err(GitUtilsErrorCode::HookFailed(ref o)) if o.hook == "pre-push"));
+    assert_eq!(
+        std::fs::read_to_string(&args).unwrap(),
+        format!(
+            "origin {}\nrefs/heads/master {} refs/heads/master {}\n",
+            remote_url, second, first
+        )
+    );
+    let remote_repo = Repository::open_bare(remote_dir.path()).unwrap();
+    assert_eq!(
+        remote_repo.refname_to_id("refs/heads/master").unwrap(),
+        first
+    );
+
+    let no_verify = PushOptions {
+        no_verify: true,
+        ..Default::default()
+    };
+    provider.push("origin", "master", None, &no_verify).unwrap();
+    assert_eq!(
+        remote_repo.refname_to_id("refs/heads/master").unwrap(),
+        second
+    );
+}
+
+#[test]
+fn test_force_with_lease_checked_on_push_connection() {
+    let (_dir, provider) = init_repo();
+    let remote_dir = tempfile::tempdir().unwrap();
+    let remote_repo = Repository::init_bare(remote_dir.path()).unwrap();
+    let repo = &provider.repository;
+    let mut remote = repo
+        .remote("origin", remote_dir.path().to_str().unwrap())
+        .unwrap();
+    let first = commit_file(&provider, "a.txt", "a", "first");
+    let second = commit_file(&provider, "b.txt", "b", "second");
+    remote.push(&["refs/heads/master"], None).unwrap();
+    repo.reference("refs/remotes/origin/master", second, true, "")
+        .unwrap();
+    let mut branch = repo.find_branch("master", BranchType::Local).unwrap();
+    branch.set_upstream(Some("origin/master")).unwrap();
+    let options = CommitOptions {
+        amend: true,
+        ..Default::default()
+    };
+    provider
+        .commit_with_options("rewritten", None, &options)
+        .unwrap();
+
+    // 读取远程分支之后、推送之前，其他人更新了远程分支
+    write_hook(
+        &provider,
+        "pre-push",
+        &format!(
+            "#!/bin/sh
+git --git-dir={} update-ref refs/heads/master {}
+",
+            remote_dir.path().display(),
+            first
+        ),
+    );
+    let options = PushOptions {
+        mode: PushMode::ForceWithLease,
+        ..Default::default()
+    };
+    let ret = provider.push("origin", "master", None, &options);
+    assert!(matches!(ret, Err(GitUtilsErrorCode::StaleRemoteRef(_))));
+    assert_eq!(
+        remote_repo.refname_to_id("refs/heads/master").unwrap(),
+        first
+    );
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use giter_utils::types::{
+    error::GitUtilsErrorCode,
+    git_data_provider::GitDataProvider,
+    init::{InitOptions, gitignore_template},
+};
+
+/// 预先写入仓库配置，初始提交需要用户信息
+fn write_user_config(path: &std::path::Path) {
+    std::fs::create_dir_all(path.join(".git")).unwrap();
+    std::fs::write(
+        path.join(".git/config"),
+        format!(
+            "[user]\n\tname = {}\n\temail = {}\n",
+            common::USER_NAME,
+            common::USER_EMAIL
+        ),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_init_repo_with_branch() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("project");
+    let options = InitOptions {
+        initial_branch: Some("trunk".to_string()),
+        ..Default::default()
+    };
+    let provider = GitDataProvider::init_repo(&path, &options).unwrap();
+    let head = provider.repository.find_reference("HEAD").unwrap();
+    assert_eq!(head.symbolic_target(), Some("refs/heads/trunk"));
+    assert!(provider.repository.head().is_err());
+
+    let ret = GitDataProvider::init_repo(&path, &options);
+    assert!(matches!(ret, Err(GitUtilsErrorCode::RepoAlreadyExists(_))));
+}
+
+#[test]
+fn test_init_repo_with_gitignore_and_initial_commit() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path();
+    std::fs::write(path.join("main.rs"), "fn main() {}").unwrap();
+    std::fs::create_dir(path.join("target")).unwrap();
+    std::fs::write(path.join("target/app"), "binary").unwrap();
+    write_user_config(path);
+
+    let options = InitOptions {
+        gitignore: Some("rust".to_string()),
+        initial_commit: Some("Initial commit".to_string()),
+        ..Default::default()
+    };
+    let provider = GitDataProvider::init_repo(path, &options).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(path.join(".gitignore")).unwrap(),
+        gitignore_template("Rust").unwrap()
+    );
+    let commit = provider
+        .repository
+        .head()
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(commit.message(), Some("Initial commit"));
+    let tree = commit.tree().unwrap();
+    let mut names = tree
+        .iter()
+        .map(|e| e.name().unwrap().to_string())
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec![".gitignore", "main.rs"]);
+}
+
+#[test]
+fn test_init_repo_keeps_existing_gitignore() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join(".gitignore"), "custom\n").unwrap();
+    let options = InitOptions {
+        gitignore: Some("Node".to_string()),
+        ..Default::default()
+    };
+    GitDataProvider::init_repo(dir.path(), &options).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join(".gitignore")).unwrap(),
+        "custom\n"
+    );
+}
+
+#[test]
+fn test_init_repo_invalid_options() {
+    let dir = tempfile::tempdir().unwrap();
+    let options = InitOptions {
+        gitignore: Some("Cobol".to_string()),
+        ..Default::default()
+    };
+    let ret = GitDataProvider::init_repo(dir.path(), &options);
+    assert!(matches!(
+        ret,
+        Err(GitUtilsErrorCode::GitignoreTemplateNotFound(_))
+    ));
+    let options = InitOptions {
+        initial_branch: Some("bad..name".to_string()),
+        ..Default::default()
+    };
+    let ret = GitDataProvider::init_repo(dir.path(), &options);
+    assert!(matches!(ret, Err(GitUtilsErrorCode::InvalidBranchName(_))));
+    assert!(!dir.path().join(".git").exists());
+}
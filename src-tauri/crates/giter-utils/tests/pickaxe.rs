@@ -0,0 +1,160 @@
+mod common;
+
+use common::{commit_file, init_repo};
+use giter_utils::types::{
+    error::GitUtilsErrorCode,
+    fetch::CancelToken,
+    git_data_provider::GitDataProvider,
+    pickaxe::{PickaxeMode, PickaxeOptions},
+};
+
+fn titles(provider: &GitDataProvider, options: &PickaxeOptions) -> Vec<String> {
+    provider
+        .pickaxe(options, &CancelToken::new(), |_| {})
+        .unwrap()
+        .matches
+        .into_iter()
+        .map(|m| m.commit.title)
+        .collect()
+}
+
+/// 依次提交：添加 TODO、修改 TODO 所在的行、删除 TODO
+fn setup() -> (tempfile::TempDir, GitDataProvider) {
+    let (dir, provider) = init_repo();
+    commit_file(&provider, "a.txt", "one\ntwo\n", "init");
+    commit_file(&provider, "a.txt", "one\nTODO fix\ntwo\n", "add todo");
+    commit_file(&provider, "b.txt", "other\n", "other file");
+    commit_file(
+        &provider,
+        "a.txt",
+        "one\nTODO fix later\ntwo\n",
+        "edit todo",
+    );
+    commit_file(&provider, "a.txt", "one\ntwo\n", "remove todo");
+    (dir, provider)
+}
+
+#[test]
+fn test_pickaxe_occurrence() {
+    let (_dir, provider) = setup();
+    let options = PickaxeOptions {
+        pattern: "TODO".to_string(),
+        ..Default::default()
+    };
+    // 修改行时出现的次数没有变化
+    assert_eq!(titles(&provider, &options), ["remove todo", "add todo"]);
+
+    let page = provider
+        .pickaxe(&options, &CancelToken::new(), |_| {})
+        .unwrap();
+    let hunk = &page.matches[1].hunks[0];
+    assert_eq!(hunk.path, "a.txt");
+    let matched = hunk.lines.iter().filter(|l| l.matched).collect::<Vec<_>>();
+    assert_eq!(matched.len(), 1);
+    assert_eq!(matched[0].origin, '+');
+    assert_eq!(matched[0].content, "TODO fix");
+    assert_eq!(matched[0].new_lineno, Some(2));
+}
+
+#[test]
+fn test_pickaxe_diff_regex() {
+    let (_dir, provider) = setup();
+    let options = PickaxeOptions {
+        pattern: r"todo\s+\w+".to_string(),
+        regex: true,
+        ignore_case: true,
+        mode: PickaxeMode::Diff,
+        ..Default::default()
+    };
+    assert_eq!(
+        titles(&provider, &options),
+        ["remove todo", "edit todo", "add todo"]
+    );
+
+    let options = PickaxeOptions {
+        pattern: "(".to_string(),
+        regex: true,
+        ..Default::default()
+    };
+    let ret = provider.pickaxe(&options, &CancelToken::new(), |_| {});
+    assert!(matches!(ret, Err(GitUtilsErrorCode::InvalidPattern(_))));
+}
+
+#[test]
+fn test_pickaxe_paging_and_path() {
+    let (_dir, provider) = setup();
+    let mut options = PickaxeOptions {
+        pattern: "TODO".to_string(),
+        mode: PickaxeMode::Diff,
+        count: 2,
+        ..Default::default()
+    };
+    let mut streamed = vec![];
+    let page = provider
+        .pickaxe(&options, &CancelToken::new(), |m| {
+            streamed.push(m.commit.title.clone())
+        })
+        .unwrap();
+    assert_eq!(streamed, ["remove todo", "edit todo"]);
+    assert!(page.has_more);
+
+    // 下一页从上一页最后检查的提交继续
+    options.cursor = page.cursor;
+    let page = provider
+        .pickaxe(&options, &CancelToken::new(), |_| {})
+        .unwrap();
+    assert_eq!(page.matches.len(), 1);
+    assert_eq!(page.matches[0].commit.title, "add todo");
+    assert!(!page.has_more);
+    assert!(page.cursor.is_none());
+
+    let options = PickaxeOptions {
+        pattern: "other".to_string(),
+        path: Some("a.txt".to_string()),
+        ..Default::default()
+    };
+    assert!(titles(&provider, &options).is_empty());
+}
+
+#[test]
+fn test_pickaxe_cursor() {
+    let (_dir, provider) = setup();
+    // 没有保存遍历状态的 cursor 会从头遍历到 cursor 再继续
+    let other = provider.repository.revparse_single("HEAD~2").unwrap().id();
+    let mut options = PickaxeOptions {
+        pattern: "TODO".to_string(),
+        cursor: Some(other.to_string()),
+        ..Default::default()
+    };
+    assert_eq!(titles(&provider, &options), ["add todo"]);
+
+    // 遍历完成前数量已满时 has_more 为 true，即使后面没有更多匹配
+    options.cursor = None;
+    options.count = 1;
+    options.reference = Some("HEAD~1".to_string());
+    let page = provider
+        .pickaxe(&options, &CancelToken::new(), |_| {})
+        .unwrap();
+    assert_eq!(page.matches[0].commit.title, "add todo");
+    assert!(page.has_more);
+
+    options.cursor = Some("not a commit".to_string());
+    let ret = provider.pickaxe(&options, &CancelToken::new(), |_| {});
+    assert!(matches!(ret, Err(GitUtilsErrorCode::InvalidCursor(_))));
+    options.cursor = Some(git2::Oid::zero().to_string());
+    let ret = provider.pickaxe(&options, &CancelToken::new(), |_| {});
+    assert!(matches!(ret, Err(GitUtilsErrorCode::InvalidCursor(_))));
+}
+
+#[test]
+fn test_pickaxe_cancelled() {
+    let (_dir, provider) = setup();
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let options = PickaxeOptions {
+        pattern: "TODO".to_string(),
+        ..Default::default()
+    };
+    let ret = provider.pickaxe(&options, &cancel, |_| {});
+    assert!(matches!(ret, Err(GitUtilsErrorCode::OperationCancelled)));
+}
@@ -0,0 +1,222 @@
+mod common;
+
+use common::{commit_file, init_repo, write_file};
+use git2::{Repository, RepositoryState};
+use giter_utils::types::{
+    error::GitUtilsErrorCode,
+    git_data_provider::GitDataProvider,
+    pull::{PullOptions, PullStrategy},
+};
+use tempfile::TempDir;
+
+/// 克隆 upstream，返回跟踪 origin/master 的本地仓库
+fn clone(upstream: &GitDataProvider) -> (TempDir, GitDataProvider) {
+    let dir = tempfile::tempdir().unwrap();
+    Repository::clone(upstream.workdir().to_str().unwrap(), dir.path()).unwrap();
+    let provider = GitDataProvider::new(dir.path()).unwrap();
+    let mut config = provider.repository.config().unwrap();
+    config.set_str("user.name", common::USER_NAME).unwrap();
+    config.set_str("user.email", common::USER_EMAIL).unwrap();
+    (dir, provider)
+}
+
+fn setup() -> (TempDir, GitDataProvider, TempDir, GitDataProvider) {
+    let (upstream_dir, upstream) = init_repo();
+    commit_file(&upstream, "a.txt", "a", "first");
+    let (dir, provider) = clone(&upstream);
+    (upstream_dir, upstream, dir, provider)
+}
+
+fn pull(
+    provider: &GitDataProvider,
+    strategy: Option<PullStrategy>,
+) -> Result<(), GitUtilsErrorCode> {
+    let options = PullOptions {
+        strategy,
+        ..Default::default()
+    };
+    provider.pull("origin", "master", None, &options)
+}
+
+fn head(provider: &GitDataProvider) -> git2::Commit<'_> {
+    provider
+        .repository
+        .head()
+        .unwrap()
+        .peel_to_commit()
+        .unwrap()
+}
+
+fn read(provider: &GitDataProvider, path: &str) -> String {
+    std::fs::read_to_string(provider.workdir().join(path)).unwrap()
+}
+
+#[test]
+fn test_pull_fast_forward_keeps_local_changes() {
+    let (_upstream_dir, upstream) = init_repo();
+    commit_file(&upstream, "a.txt", "a", "first");
+    commit_file(&upstream, "c.txt", "c", "c");
+    let (_dir, provider) = clone(&upstream);
+    let second = commit_file(&upstream, "a.txt", "a2", "second");
+    write_file(&provider, "c.txt", "dirty");
+
+    pull(&provider, None).unwrap();
+    assert_eq!(head(&provider).id(), second);
+    assert_eq!(read(&provider, "a.txt"), "a2");
+    assert_eq!(read(&provider, "c.txt"), "dirty");
+}
+
+#[test]
+fn test_pull_refuses_to_overwrite_dirty_files() {
+    let (_upstream_dir, upstream, _dir, provider) = setup();
+    let first = head(&provider).id();
+    commit_file(&upstream, "a.txt", "a2", "second");
+    write_file(&provider, "a.txt", "dirty");
+
+    let ret = pull(&provider, None);
+    assert!(matches!(
+        ret,
+        Err(GitUtilsErrorCode::CommitBeforePullWouldBeOverwrittenByMerge)
+    ));
+    assert_eq!(head(&provider).id(), first);
+    assert_eq!(read(&provider, "a.txt"), "dirty");
+}
+
+#[test]
+fn test_pull_merge_message() {
+    let (_upstream_dir, upstream, _dir, provider) = setup();
+    let remote = commit_file(&upstream, "b.txt", "b", "remote");
+    let local = commit_file(&provider, "c.txt", "c", "local");
+
+    pull(&provider, Some(PullStrategy::Merge)).unwrap();
+    let merge = head(&provider);
+    assert_eq!(merge.parent_ids().collect::<Vec<_>>(), vec![local, remote]);
+    assert_eq!(
+        merge.message().unwrap(),
+        format!(
+            "Merge branch 'master' of {}",
+            upstream.workdir().to_str().unwrap()
+        )
+    );
+    assert_eq!(provider.repository.state(), RepositoryState::Clean);
+}
+
+#[test]
+fn test_pull_merge_conflict() {
+    let (_upstream_dir, upstream, _dir, provider) = setup();
+    commit_file(&upstream, "a.txt", "remote", "remote");
+    commit_file(&provider, "a.txt", "local", "local");
+
+    let ret = pull(&provider, Some(PullStrategy::Merge));
+    assert!(matches!(ret, Err(GitUtilsErrorCode::RepoHasConflicts(ref p)) if p == "a.txt"));
+    assert_eq!(provider.repository.state(), RepositoryState::Merge);
+    let message = std::fs::read_to_string(provider.repository.path().join("MERGE_MSG")).unwrap();
+    assert!(message.starts_with("Merge branch 'master' of "));
+}
+
+#[test]
+fn test_pull_fast_forward_only() {
+    let (_upstream_dir, upstream, _dir, provider) = setup();
+    commit_file(&upstream, "b.txt", "b", "remote");
+    let local = commit_file(&provider, "c.txt", "c", "local");
+    provider
+        .repository
+        .config()
+        .unwrap()
+        .set_str("pull.ff", "only")
+        .unwrap();
+
+    let ret = pull(&provider, None);
+    assert!(matches!(ret, Err(GitUtilsErrorCode::CannotFastForward(_))));
+    assert_eq!(head(&provider).id(), local);
+}
+
+#[test]
+fn test_pull_rebase_from_config() {
+    let (_upstream_dir, upstream, _dir, provider) = setup();
+    let remote = commit_file(&upstream, "b.txt", "b", "remote");
+    commit_file(&provider, "c.txt", "c", "local");
+    provider
+        .repository
+        .config()
+        .unwrap()
+        .set_bool("pull.rebase", true)
+        .unwrap();
+
+    pull(&provider, None).unwrap();
+    let head = head(&provider);
+    assert_eq!(head.message().unwrap(), "local");
+    assert_eq!(head.parent_ids().collect::<Vec<_>>(), vec![remote]);
+    assert_eq!(read(&provider, "b.txt"), "b");
+    assert_eq!(provider.repository.state(), RepositoryState::Clean);
+}
+
+#[test]
+fn test_pull_rebase_conflict_aborts() {
+    let (_upstream_dir, upstream, _dir, provider) = setup();
+    commit_file(&upstream, "a.txt", "remote", "remote");
+    let local = commit_file(&provider, "a.txt", "local", "local");
+
+    let ret = pull(&provider, Some(PullStrategy::Rebase));
+    assert!(matches!(ret, Err(GitUtilsErrorCode::RepoHasConflicts(_))));
+    assert_eq!(head(&provider).id(), local);
+    assert_eq!(read(&provider, "a.txt"), "local");
+    assert_eq!(provider.repository.state(), RepositoryState::Clean);
+}
+
+#[test]
+fn test_pull_autostash() {
+    let (_upstream_dir, upstream, _dir, provider) = setup();
+    let remote = commit_file(&upstream, "b.txt", "b", "remote");
+    commit_file(&provider, "c.txt", "c", "local");
+    write_file(&provider, "c.txt", "dirty");
+
+    let ret = pull(&provider, Some(PullStrategy::Rebase));
+    assert!(matches!(
+        ret,
+        Err(GitUtilsErrorCode::RebaseUncommittedChanges)
+    ));
+
+    let options = PullOptions {
+        strategy: Some(PullStrategy::Rebase),
+        autostash: Some(true),
+    };
+    provider.pull("origin", "master", None, &options).unwrap();
+    assert_eq!(head(&provider).parent_ids().next(), Some(remote));
+    assert_eq!(read(&provider, "c.txt"), "dirty");
+    let mut repo = Repository::open(provider.workdir()).unwrap();
+    let mut stashes = 0;
+    repo.stash_foreach(|_, _, _| {
+        stashes += 1;
+        true
+    })
+    .unwrap();
+    assert_eq!(stashes, 0);
+}
+
+#[test]
+fn test_pull_autostash_conflict_keeps_stash() {
+    let (_upstream_dir, upstream, _dir, provider) = setup();
+    let remote = commit_file(&upstream, "a.txt", "remote", "remote");
+    write_file(&provider, "a.txt", "dirty");
+
+    let options = PullOptions {
+        strategy: None,
+        autostash: Some(true),
+    };
+    let ret = provider.pull("origin", "master", None, &options);
+    assert!(
+        matches!(ret, Err(GitUtilsErrorCode::AutostashConflict)),
+        "{:?}",
+        ret
+    );
+    assert_eq!(head(&provider).id(), remote);
+    let mut repo = Repository::open(provider.workdir()).unwrap();
+    let mut stashes = 0;
+    repo.stash_foreach(|_, _, _| {
+        stashes += 1;
+        true
+    })
+    .unwrap();
+    assert_eq!(stashes, 1);
+}
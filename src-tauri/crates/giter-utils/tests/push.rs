@@ -0,0 +1,151 @@
+mod common;
+
+use common::{commit_file, init_repo, setup_remote};
+use git2::{BranchType, Oid, Repository};
+use giter_utils::types::{
+    commit_options::CommitOptions,
+    error::GitUtilsErrorCode,
+    git_data_provider::GitDataProvider,
+    push::{PushMode, PushOptions},
+};
+use tempfile::TempDir;
+
+/// 模拟其他人向远程推送了一个提交
+fn push_from_other(remote_dir: &TempDir) -> Oid {
+    let dir = tempfile::tempdir().unwrap();
+    Repository::clone(remote_dir.path().to_str().unwrap(), dir.path()).unwrap();
+    let other = GitDataProvider::new(dir.path()).unwrap();
+    let mut config = other.repository.config().unwrap();
+    config.set_str("user.name", common::USER_NAME).unwrap();
+    config.set_str("user.email", common::USER_EMAIL).unwrap();
+    let id = commit_file(&other, "other.txt", "other", "other");
+    other
+        .push("origin", "master", None, &PushOptions::default())
+        .unwrap();
+    id
+}
+
+fn remote_head(bare: &Repository, name: &str) -> Option<Oid> {
+    bare.refname_to_id(name).ok()
+}
+
+fn mode(mode: PushMode) -> PushOptions {
+    PushOptions {
+        mode,
+        ..Default::default()
+    }
+}
+
+/// 修改最后一次提交，使本地分支与远程分叉
+fn amend(provider: &GitDataProvider) -> Oid {
+    let options = CommitOptions {
+        amend: true,
+        allow_empty: true,
+        ..Default::default()
+    };
+    provider
+        .commit_with_options("rewritten", None, &options)
+        .unwrap()
+}
+
+#[test]
+fn test_push_multiple_commits() {
+    let (_dir, provider) = init_repo();
+    let (_remote_dir, bare) = setup_remote(&provider);
+    commit_file(&provider, "b.txt", "b", "second");
+    let third = commit_file(&provider, "c.txt", "c", "third");
+
+    provider
+        .push("origin", "master", None, &PushOptions::default())
+        .unwrap();
+    assert_eq!(remote_head(&bare, "refs/heads/master"), Some(third));
+    assert_eq!(
+        provider
+            .repository
+            .refname_to_id("refs/remotes/origin/master")
+            .ok(),
+        Some(third)
+    );
+}
+
+#[test]
+fn test_push_rejects_non_fast_forward() {
+    let (_dir, provider) = init_repo();
+    let (remote_dir, bare) = setup_remote(&provider);
+    let first = remote_head(&bare, "refs/heads/master");
+    amend(&provider);
+    let ret = provider.push("origin", "master", None, &PushOptions::default());
+    assert!(matches!(ret, Err(GitUtilsErrorCode::NonFastForward(_))));
+    assert_eq!(remote_head(&bare, "refs/heads/master"), first);
+
+    // 远程有本地没有的提交时需要先拉取
+    push_from_other(&remote_dir);
+    let ret = provider.push("origin", "master", None, &PushOptions::default());
+    assert!(matches!(
+        ret,
+        Err(GitUtilsErrorCode::RemoteHeadHasNotInLocal)
+    ));
+}
+
+#[test]
+fn test_push_force_with_lease() {
+    let (_dir, provider) = init_repo();
+    let (remote_dir, bare) = setup_remote(&provider);
+    let rewritten = amend(&provider);
+    provider
+        .push("origin", "master", None, &mode(PushMode::ForceWithLease))
+        .unwrap();
+    assert_eq!(remote_head(&bare, "refs/heads/master"), Some(rewritten));
+
+    // 远程在上次拉取后被其他人更新，拒绝覆盖
+    let other = push_from_other(&remote_dir);
+    let rewritten = amend(&provider);
+    let ret = provider.push("origin", "master", None, &mode(PushMode::ForceWithLease));
+    assert!(matches!(ret, Err(GitUtilsErrorCode::StaleRemoteRef(_))));
+    assert_eq!(remote_head(&bare, "refs/heads/master"), Some(other));
+
+    provider
+        .push("origin", "master", None, &mode(PushMode::Force))
+        .unwrap();
+    assert_eq!(remote_head(&bare, "refs/heads/master"), Some(rewritten));
+}
+
+#[test]
+fn test_push_to_other_branch_and_set_upstream() {
+    let (_dir, provider) = init_repo();
+    let (_remote_dir, bare) = setup_remote(&provider);
+    let repo = &provider.repository;
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.branch("feature", &head, false).unwrap();
+
+    let ret = provider.push("origin", "feature", None, &PushOptions::default());
+    assert!(matches!(ret, Err(GitUtilsErrorCode::BranchNotTrackAny(_))));
+
+    let options = PushOptions {
+        remote_branch: Some("remote-feature".to_string()),
+        set_upstream: true,
+        ..Default::default()
+    };
+    provider.push("origin", "feature", None, &options).unwrap();
+    assert_eq!(
+        remote_head(&bare, "refs/heads/remote-feature"),
+        Some(head.id())
+    );
+    let branch = repo.find_branch("feature", BranchType::Local).unwrap();
+    assert_eq!(
+        branch.upstream().unwrap().name().unwrap(),
+        Some("origin/remote-feature")
+    );
+
+    // 之后的推送使用上游分支
+    repo.set_head("refs/heads/feature").unwrap();
+    let second = commit_file(&provider, "b.txt", "b", "second");
+    provider
+        .push("origin", "feature", None, &PushOptions::default())
+        .unwrap();
+    assert_eq!(
+        remote_head(&bare, "refs/heads/remote-feature"),
+        Some(second)
+    );
+    assert_eq!(remote_head(&bare, "refs/heads/feature"), None);
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use common::{commit_file, init_repo};
+use git2::BranchType;
+use giter_utils::types::{error::GitUtilsErrorCode, remote::Remote};
+
+#[test]
+fn test_add_remote_without_fetch() {
+    let (_dir, provider) = init_repo();
+    assert!(provider.remotes().unwrap().is_empty());
+
+    let remote = provider
+        .add_remote("origin", "https://example.com/giter.git")
+        .unwrap();
+    assert_eq!(
+        remote,
+        Remote {
+            name: "origin".to_string(),
+            url: "https://example.com/giter.git".to_string(),
+            push_url: None,
+            fetch_refspecs: vec!["+refs/heads/*:refs/remotes/origin/*".to_string()],
+            push_refspecs: vec![],
+        }
+    );
+    provider
+        .add_remote("fork", "https://example.com/fork.git")
+        .unwrap();
+    let names = provider
+        .remotes()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["fork", "origin"]);
+}
+
+#[test]
+fn test_add_remote_invalid() {
+    let (_dir, provider) = init_repo();
+    provider.add_remote("origin", "/srv/giter.git").unwrap();
+    assert!(matches!(
+        provider.add_remote("origin", "/srv/other.git"),
+        Err(GitUtilsErrorCode::RemoteAlreadyExists(_))
+    ));
+    assert!(matches!(
+        provider.add_remote("bad name", "/srv/other.git"),
+        Err(GitUtilsErrorCode::InvalidRemoteName(_))
+    ));
+}
+
+#[test]
+fn test_rename_remote() {
+    let (_dir, provider) = init_repo();
+    let first = commit_file(&provider, "a.txt", "a", "first");
+    provider.add_remote("origin", "/srv/giter.git").unwrap();
+    let repo = &provider.repository;
+    repo.reference("refs/remotes/origin/master", first, true, "")
+        .unwrap();
+    let mut branch = repo.find_branch("master", BranchType::Local).unwrap();
+    branch.set_upstream(Some("origin/master")).unwrap();
+
+    let problems = provider.rename_remote("origin", "upstream").unwrap();
+    assert!(problems.is_empty());
+    let remotes = provider.remotes().unwrap();
+    assert_eq!(remotes.len(), 1);
+    assert_eq!(remotes[0].name, "upstream");
+    assert_eq!(
+        remotes[0].fetch_refspecs,
+        vec!["+refs/heads/*:refs/remotes/upstream/*"]
+    );
+    assert!(repo.find_reference("refs/remotes/upstream/master").is_ok());
+    let branch = repo.find_branch("master", BranchType::Local).unwrap();
+    assert_eq!(
+        branch.upstream().unwrap().name().unwrap(),
+        Some("upstream/master")
+    );
+
+    assert!(matches!(
+        provider.rename_remote("origin", "other"),
+        Err(GitUtilsErrorCode::RemoteNotFound(_))
+    ));
+}
+
+#[test]
+fn test_remove_remote() {
+    let (_dir, provider) = init_repo();
+    let first = commit_file(&provider, "a.txt", "a", "first");
+    provider.add_remote("origin", "/srv/giter.git").unwrap();
+    provider
+        .repository
+        .reference("refs/remotes/origin/master", first, true, "")
+        .unwrap();
+
+    provider.remove_remote("origin").unwrap();
+    assert!(provider.remotes().unwrap().is_empty());
+    assert!(
+        provider
+            .repository
+            .find_reference("refs/remotes/origin/master")
+            .is_err()
+    );
+    assert!(matches!(
+        provider.remove_remote("origin"),
+        Err(GitUtilsErrorCode::RemoteNotFound(_))
+    ));
+}
+
+#[test]
+fn test_set_remote_url() {
+    let (_dir, provider) = init_repo();
+    provider.add_remote("origin", "/srv/giter.git").unwrap();
+
+    let remote = provider
+        .set_remote_url("origin", "/srv/mirror.git", Some("/srv/push.git"))
+        .unwrap();
+    assert_eq!(remote.url, "/srv/mirror.git");
+    assert_eq!(remote.push_url.as_deref(), Some("/srv/push.git"));
+
+    let remote = provider
+        .set_remote_url("origin", "/srv/mirror.git", None)
+        .unwrap();
+    assert_eq!(remote.push_url, None);
+}
@@ -0,0 +1,185 @@
+mod common;
+
+use common::{commit_file, init_repo, setup_remote};
+use git2::{ErrorClass, ErrorCode};
+use giter_utils::types::{error::GitUtilsErrorCode, pull::PullOptions, push::PushOptions};
+
+fn classify(code: ErrorCode, class: ErrorClass, message: &str) -> GitUtilsErrorCode {
+    let e = git2::Error::new(code, class, message);
+    GitUtilsErrorCode::from_remote(&e, GitUtilsErrorCode::FetchError)
+}
+
+#[test]
+fn test_classify_git2_errors() {
+    assert!(matches!(
+        classify(ErrorCode::Auth, ErrorClass::Http, "authentication required"),
+        GitUtilsErrorCode::RemoteAuthFailed(m) if m == "authentication required"
+    ));
+    assert!(matches!(
+        classify(
+            ErrorCode::GenericError,
+            ErrorClass::Ssh,
+            "Failed to authenticate SSH session"
+        ),
+        GitUtilsErrorCode::RemoteAuthFailed(_)
+    ));
+    assert!(matches!(
+        classify(
+            ErrorCode::User,
+            ErrorClass::Callback,
+            "Credentials required"
+        ),
+        GitUtilsErrorCode::PushNeedNameAndPassword
+    ));
+    assert!(matches!(
+        classify(
+            ErrorCode::Certificate,
+            ErrorClass::Ssh,
+            "invalid or unknown remote ssh hostkey"
+        ),
+        GitUtilsErrorCode::HostKeyUnknown(_)
+    ));
+    assert!(matches!(
+        classify(
+            ErrorCode::GenericError,
+            ErrorClass::Ssh,
+            "hostkey mismatch for host"
+        ),
+        GitUtilsErrorCode::HostKeyMismatch(_)
+    ));
+    assert!(matches!(
+        classify(
+            ErrorCode::NotFastForward,
+            ErrorClass::Reference,
+            "cannot push non-fastforwardable reference"
+        ),
+        GitUtilsErrorCode::NonFastForward(_)
+    ));
+    assert!(matches!(
+        classify(
+            ErrorCode::Locked,
+            ErrorClass::Reference,
+            "failed to lock file"
+        ),
+        GitUtilsErrorCode::RemoteRefLocked(_)
+    ));
+    assert!(matches!(
+        classify(
+            ErrorCode::GenericError,
+            ErrorClass::Net,
+            "failed to resolve address for example.invalid"
+        ),
+        GitUtilsErrorCode::NetworkUnreachable(_)
+    ));
+    assert!(matches!(
+        classify(
+            ErrorCode::GenericError,
+            ErrorClass::Os,
+            "failed to connect to example.com: Operation timed out"
+        ),
+        GitUtilsErrorCode::RemoteTimeout(_)
+    ));
+    assert!(matches!(
+        classify(
+            ErrorCode::Certificate,
+            ErrorClass::Net,
+            "the SSL certificate is invalid"
+        ),
+        GitUtilsErrorCode::CertificateInvalid(_)
+    ));
+    assert!(matches!(
+        classify(
+            ErrorCode::GenericError,
+            ErrorClass::Ssl,
+            "SSL error: certificate verify failed"
+        ),
+        GitUtilsErrorCode::CertificateInvalid(_)
+    ));
+    assert!(matches!(
+        classify(ErrorCode::Timeout, ErrorClass::Net, "read error"),
+        GitUtilsErrorCode::RemoteTimeout(_)
+    ));
+    // 其他 Net 类的错误(TLS 握手、HTTP 状态码等)使用传入的默认错误
+    assert!(matches!(
+        classify(
+            ErrorCode::GenericError,
+            ErrorClass::Net,
+            "unexpected http status code: 502"
+        ),
+        GitUtilsErrorCode::FetchError(m) if m == "unexpected http status code: 502"
+    ));
+    assert!(matches!(
+        classify(
+            ErrorCode::GenericError,
+            ErrorClass::Ssl,
+            "SSL error: handshake failed"
+        ),
+        GitUtilsErrorCode::FetchError(_)
+    ));
+    assert!(matches!(
+        classify(ErrorCode::GenericError, ErrorClass::Odb, "object not found"),
+        GitUtilsErrorCode::FetchError(m) if m == "object not found"
+    ));
+}
+
+#[test]
+fn test_classify_push_status() {
+    let status = |status: &str, remote: &str| {
+        GitUtilsErrorCode::from_push_status("refs/heads/master", status, remote)
+    };
+    assert!(matches!(
+        status("pre-receive hook declined", "GitLab: You are not allowed to push code to protected branches on this project.\n"),
+        GitUtilsErrorCode::ProtectedBranch(m) if m.starts_with("refs/heads/master: pre-receive hook declined\nGitLab:")
+    ));
+    assert!(matches!(
+        status("protected branch hook declined", ""),
+        GitUtilsErrorCode::ProtectedBranch(_)
+    ));
+    assert!(matches!(
+        status("non-fast-forward", ""),
+        GitUtilsErrorCode::NonFastForward(_)
+    ));
+    assert!(matches!(
+        status(
+            "failed to update ref: cannot lock ref 'refs/heads/master'",
+            ""
+        ),
+        GitUtilsErrorCode::RemoteRefLocked(_)
+    ));
+    assert!(matches!(
+        status("blocked by policy", ""),
+        GitUtilsErrorCode::PushRejected(m) if m == "refs/heads/master: blocked by policy"
+    ));
+}
+
+#[test]
+fn test_push_remote_ref_locked() {
+    let (_dir, provider) = init_repo();
+    let (remote_dir, _bare) = setup_remote(&provider);
+    commit_file(&provider, "b.txt", "b", "second");
+    let lock = remote_dir.path().join("refs/heads/master.lock");
+    std::fs::write(&lock, "").unwrap();
+
+    let ret = provider.push("origin", "master", None, &PushOptions::default());
+    assert!(
+        matches!(ret, Err(GitUtilsErrorCode::RemoteRefLocked(_))),
+        "{:?}",
+        ret
+    );
+}
+
+#[test]
+fn test_pull_unreachable_remote() {
+    let (_dir, provider) = init_repo();
+    let (_remote_dir, _bare) = setup_remote(&provider);
+    provider
+        .repository
+        .remote_set_url("origin", "https://127.0.0.1:1/giter.git")
+        .unwrap();
+    let ret = provider.pull("origin", "master", None, &PullOptions::default());
+    assert!(
+        matches!(ret, Err(GitUtilsErrorCode::NetworkUnreachable(_))),
+        "{:?}",
+        ret
+    );
+}
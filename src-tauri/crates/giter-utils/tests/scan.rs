@@ -0,0 +1,149 @@
+mod common;
+
+use std::path::Path;
+
+use common::{USER_EMAIL, USER_NAME};
+use git2::{Repository, Signature};
+use giter_utils::types::{
+    error::GitUtilsErrorCode,
+    fetch::CancelToken,
+    scan::{ScanOptions, ScannedRepo, scan_repos},
+};
+
+/// 在 path 创建仓库并提交一个空提交
+fn create_repo(path: &Path) -> Repository {
+    let repo = Repository::init(path).unwrap();
+    {
+        let sig =
+            Signature::new(USER_NAME, USER_EMAIL, &git2::Time::new(1_700_000_000, 0)).unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "first", &tree, &[])
+            .unwrap();
+    }
+    repo
+}
+
+fn scan(root: &Path, options: &ScanOptions) -> Vec<ScannedRepo> {
+    scan_repos(root, options, &CancelToken::new(), |_| {}).unwrap()
+}
+
+fn relative(root: &Path, repos: &[ScannedRepo]) -> Vec<String> {
+    repos
+        .iter()
+        .map(|r| {
+            Path::new(&r.path)
+                .strip_prefix(root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect()
+}
+
+#[test]
+fn test_scan_nested_repos_with_metadata() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let repo = create_repo(&root.join("a/b/c/deep"));
+    repo.remote("origin", "https://example.com/deep.git")
+        .unwrap();
+    create_repo(&root.join("a/top"));
+    // 仓库中嵌套的目录不会被扫描
+    create_repo(&root.join("a/top/nested"));
+    Repository::init_bare(root.join("server/bare.git")).unwrap();
+
+    let mut found = vec![];
+    let repos = scan_repos(root, &ScanOptions::default(), &CancelToken::new(), |r| {
+        found.push(r.clone())
+    })
+    .unwrap();
+    assert_eq!(found, repos);
+    assert_eq!(
+        relative(root, &repos),
+        vec!["a/b/c/deep", "a/top", "server/bare.git"]
+    );
+    let deep = &repos[0];
+    assert!(!deep.bare);
+    assert_eq!(deep.branch.as_deref(), Some("master"));
+    assert_eq!(
+        deep.remote_url.as_deref(),
+        Some("https://example.com/deep.git")
+    );
+    assert_eq!(deep.last_commit_time, Some(1_700_000_000));
+    let bare = &repos[2];
+    assert!(bare.bare);
+    assert_eq!(bare.last_commit_time, None);
+
+    let options = ScanOptions {
+        include_bare: false,
+        max_depth: 2,
+        ..Default::default()
+    };
+    assert_eq!(relative(root, &scan(root, &options)), vec!["a/top"]);
+}
+
+#[test]
+fn test_scan_exclude_and_hidden() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    create_repo(&root.join("node_modules/pkg"));
+    create_repo(&root.join(".cache/repo"));
+    create_repo(&root.join("tmp-1/repo"));
+    create_repo(&root.join("src/app"));
+
+    let options = ScanOptions {
+        exclude: vec!["node_modules".to_string(), "tmp-?".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(relative(root, &scan(root, &options)), vec!["src/app"]);
+
+    let options = ScanOptions {
+        exclude: vec!["*_modules".to_string()],
+        include_hidden: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        relative(root, &scan(root, &options)),
+        vec![".cache/repo", "src/app", "tmp-1/repo"]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_scan_symlink_loop() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    create_repo(&root.join("a/repo"));
+    std::os::unix::fs::symlink(root, root.join("a/loop")).unwrap();
+    std::os::unix::fs::symlink(root.join("a/repo"), root.join("link")).unwrap();
+
+    let options = ScanOptions {
+        max_depth: 20,
+        ..Default::default()
+    };
+    assert_eq!(relative(root, &scan(root, &options)), vec!["a/repo"]);
+}
+
+#[test]
+fn test_scan_cancel_and_invalid_root() {
+    let dir = tempfile::tempdir().unwrap();
+    create_repo(&dir.path().join("repo"));
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let ret = scan_repos(dir.path(), &ScanOptions::default(), &cancel, |_| {});
+    assert!(matches!(ret, Err(GitUtilsErrorCode::OperationCancelled)));
+
+    let ret = scan_repos(
+        &dir.path().join("missing"),
+        &ScanOptions::default(),
+        &CancelToken::new(),
+        |_| {},
+    );
+    assert!(matches!(ret, Err(GitUtilsErrorCode::DirectoryNotFound(_))));
+
+    // 扫描目录本身是仓库
+    let repos = scan(&dir.path().join("repo"), &ScanOptions::default());
+    assert_eq!(repos.len(), 1);
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use common::{commit_file, init_repo};
+use git2::Signature;
+use giter_utils::types::{
+    git_data_provider::GitDataProvider,
+    search::{CommitQuery, merge_search_results},
+};
+
+/// 以指定的作者和时间(秒)提交文件
+fn commit_as(provider: &GitDataProvider, path: &str, message: &str, name: &str, time: i64) {
+    common::stage_file(provider, path, message);
+    let repo = &provider.repository;
+    let sig = Signature::new(
+        name,
+        &format!("{}@example.com", name),
+        &git2::Time::new(time, 0),
+    )
+    .unwrap();
+    let tree = repo
+        .find_tree(repo.index().unwrap().write_tree().unwrap())
+        .unwrap();
+    let parents = repo
+        .head()
+        .ok()
+        .map(|h| vec![h.peel_to_commit().unwrap()])
+        .unwrap_or_default();
+    let parents = parents.iter().collect::<Vec<_>>();
+    repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+        .unwrap();
+}
+
+#[test]
+fn test_search_commits_by_conditions() {
+    let (_dir, provider) = init_repo();
+    commit_as(&provider, "a.txt", "init project", "alice", 1_000);
+    commit_as(&provider, "src/lib.rs", "Fix parser bug", "bob", 2_000);
+    commit_as(&provider, "src/main.rs", "fix cli bug", "alice", 3_000);
+    commit_as(&provider, "README.md", "docs", "alice", 4_000);
+
+    let query = CommitQuery {
+        author: Some("ALICE".to_string()),
+        ..Default::default()
+    };
+    let titles = provider
+        .search_commits(&query, 10)
+        .unwrap()
+        .into_iter()
+        .map(|c| c.title)
+        .collect::<Vec<_>>();
+    assert_eq!(titles, ["docs", "fix cli bug", "init project"]);
+
+    let query = CommitQuery {
+        message: Some("fix".to_string()),
+        path: Some("src/".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(provider.search_commits(&query, 10).unwrap().len(), 2);
+    assert_eq!(provider.search_commits(&query, 1).unwrap().len(), 1);
+
+    let query = CommitQuery {
+        start_time: Some(2_000_000),
+        end_time: Some(3_000_000),
+        ..Default::default()
+    };
+    let titles = provider
+        .search_commits(&query, 10)
+        .unwrap()
+        .into_iter()
+        .map(|c| c.title)
+        .collect::<Vec<_>>();
+    assert_eq!(titles, ["fix cli bug", "Fix parser bug"]);
+}
+
+#[test]
+fn test_search_commits_start_time_with_clock_skew() {
+    let (_dir, provider) = init_repo();
+    let start = 1_000_000;
+    commit_as(&provider, "a.txt", "old", "alice", start - 3 * 24 * 3600);
+    commit_as(&provider, "a.txt", "in range", "alice", start + 100);
+    // 时钟误差导致比父提交早的提交，不会让搜索提前结束
+    commit_as(&provider, "a.txt", "skewed", "alice", start - 3600);
+    commit_as(&provider, "a.txt", "head", "alice", start + 200);
+
+    let query = CommitQuery {
+        start_time: Some(start * 1000),
+        ..Default::default()
+    };
+    let titles = |limit| {
+        provider
+            .search_commits(&query, limit)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.title)
+            .collect::<Vec<_>>()
+    };
+    // 下一页从上一次停下的位置继续，结果和一次取完相同
+    assert_eq!(titles(1), ["head"]);
+    assert_eq!(titles(10), ["head", "in range"]);
+    assert_eq!(titles(10), ["head", "in range"]);
+}
+
+#[test]
+fn test_search_commits_branches() {
+    let (_dir, provider) = init_repo();
+    let head = commit_file(&provider, "a.txt", "a", "first");
+    let repo = &provider.repository;
+    repo.branch("feature", &repo.find_commit(head).unwrap(), false)
+        .unwrap();
+    repo.set_head("refs/heads/feature").unwrap();
+    commit_file(&provider, "b.txt", "b", "feature work");
+    repo.set_head("refs/heads/master").unwrap();
+
+    // 默认搜索所有本地分支
+    let all = provider
+        .search_commits(&CommitQuery::default(), 10)
+        .unwrap();
+    assert_eq!(all.len(), 2);
+
+    let query = CommitQuery {
+        branches: vec!["master".to_string(), "missing".to_string()],
+        ..Default::default()
+    };
+    let commits = provider.search_commits(&query, 10).unwrap();
+    assert_eq!(commits.len(), 1);
+    assert_eq!(commits[0].title, "first");
+}
+
+#[test]
+fn test_merge_search_results_pages() {
+    let (_dir_a, a) = init_repo();
+    let (_dir_b, b) = init_repo();
+    commit_as(&a, "a.txt", "a1", "alice", 1_000);
+    commit_as(&b, "b.txt", "b1", "alice", 2_000);
+    commit_as(&a, "a.txt", "a2", "alice", 3_000);
+    commit_as(&b, "b.txt", "b2", "alice", 4_000);
+
+    let query = CommitQuery::default();
+    let search = |offset: usize, count: usize| {
+        let results = [&a, &b]
+            .iter()
+            .map(|p| p.search_commits(&query, offset + count + 1).unwrap())
+            .collect();
+        merge_search_results(results, offset, count, vec![])
+    };
+    let page = search(0, 3);
+    let titles = page
+        .commits
+        .iter()
+        .map(|c| c.title.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(titles, ["b2", "a2", "b1"]);
+    assert!(page.has_more);
+
+    let page = search(3, 3);
+    assert_eq!(page.commits.len(), 1);
+    assert_eq!(page.commits[0].title, "a1");
+    assert!(!page.has_more);
+}
@@ -0,0 +1,267 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use common::{commit_file, init_repo, set_config, stage_file};
+use giter_utils::types::{
+    commit_options::CommitOptions, error::GitUtilsErrorCode, git_data_provider::GitDataProvider,
+    sign::SignatureStatus,
+};
+use tempfile::TempDir;
+
+/// 模拟 gpg：签名时输出标准输入的校验和，验证时比较签名文件中的校验和
+const GPG_STUB: &str = r#"#!/bin/sh
+case "$*" in
+  *-bsau*)
+    sum=$(cksum | cut -d' ' -f1)
+    echo "[GNUPG:] SIG_CREATED D 1 8 00 0 STUBKEY" >&2
+    printf -- "-----BEGIN PGP SIGNATURE-----\n\n%s\n-----END PGP SIGNATURE-----\n" "$sum"
+    ;;
+  *--verify*)
+    while [ "$1" != "--verify" ]; do shift; done
+    expected=$(sed -n 3p "$2")
+    sum=$(cksum | cut -d' ' -f1)
+    if [ "$sum" = "$expected" ]; then
+      echo "[GNUPG:] GOODSIG STUBKEY Giter Tester"
+      echo "[GNUPG:] TRUST_ULTIMATE 0 pgp"
+    else
+      echo "[GNUPG:] BADSIG STUBKEY Giter Tester"
+      exit 1
+    fi
+    ;;
+esac
+"#;
+
+/// 模拟 gpg：签名失败
+const GPG_FAIL_STUB: &str = r#"#!/bin/sh
+cat > /dev/null
+echo "gpg: signing failed: No secret key" >&2
+exit 2
+"#;
+
+/// 模拟 ssh-keygen：find-principals 总是返回 tester，验证时比较校验和
+const SSH_STUB: &str = r#"#!/bin/sh
+mode=$2
+sig=""
+while [ $# -gt 0 ]; do
+  if [ "$1" = "-s" ]; then sig=$2; fi
+  shift
+done
+case "$mode" in
+  sign)
+    sum=$(cksum | cut -d' ' -f1)
+    printf -- "-----BEGIN SSH SIGNATURE-----\n%s\n-----END SSH SIGNATURE-----\n" "$sum"
+    ;;
+  find-principals)
+    echo "tester@giter.dev"
+    ;;
+  verify|check-novalidate)
+    expected=$(sed -n 2p "$sig")
+    sum=$(cksum | cut -d' ' -f1)
+    [ "$sum" = "$expected" ] || exit 1
+    ;;
+esac
+"#;
+
+fn write_stub(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+    let path = dir.path().join(name);
+    std::fs::write(&path, content).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+fn enable_gpg_sign(provider: &GitDataProvider, program: &Path) {
+    set_config(provider, "commit.gpgsign", "true");
+    set_config(provider, "user.signingkey", "STUBKEY");
+    set_config(provider, "gpg.program", program.to_str().unwrap());
+}
+
+#[test]
+fn test_unsigned_commit_status() {
+    let (_dir, provider) = init_repo();
+    let oid = commit_file(&provider, "a.txt", "a", "first");
+    let commit = provider.get_commit(oid).unwrap();
+    assert_eq!(commit.signature_status, SignatureStatus::None);
+    assert_eq!(provider.verify_commit(oid).unwrap(), SignatureStatus::None);
+}
+
+#[test]
+fn test_gpg_signed_commit() {
+    let (_dir, provider) = init_repo();
+    let stubs = tempfile::tempdir().unwrap();
+    enable_gpg_sign(&provider, &write_stub(&stubs, "gpg", GPG_STUB));
+    let first = commit_file(&provider, "a.txt", "a", "first");
+    let second = commit_file(&provider, "b.txt", "b", "second");
+
+    let repo = &provider.repository;
+    let (signature, _) = repo.extract_signature(&second, None).unwrap();
+    assert!(
+        signature
+            .as_str()
+            .unwrap()
+            .starts_with("-----BEGIN PGP SIGNATURE-----")
+    );
+    // 签名提交需要手动更新分支
+    assert_eq!(repo.head().unwrap().target(), Some(second));
+    assert!(repo.head().unwrap().is_branch());
+    let commit = repo.find_commit(second).unwrap();
+    assert_eq!(commit.parent_ids().collect::<Vec<_>>(), vec![first]);
+
+    // 返回的提交列表中验证签名
+    let commits = provider.commits(10).unwrap();
+    assert_eq!(commits.len(), 2);
+    assert!(
+        commits
+            .iter()
+            .all(|c| c.signature_status == SignatureStatus::Good)
+    );
+    for commit in [first, second] {
+        assert_eq!(
+            provider.verify_commit(commit).unwrap(),
+            SignatureStatus::Good
+        );
+    }
+}
+
+#[test]
+fn test_gpg_signed_amend() {
+    let (_dir, provider) = init_repo();
+    let stubs = tempfile::tempdir().unwrap();
+    enable_gpg_sign(&provider, &write_stub(&stubs, "gpg", GPG_STUB));
+    let first = commit_file(&provider, "a.txt", "a", "first");
+    stage_file(&provider, "a.txt", "a changed");
+    let options = CommitOptions {
+        amend: true,
+        ..Default::default()
+    };
+    let oid = provider.commit_with_options("", None, &options).unwrap();
+    assert_ne!(oid, first);
+    let commit = provider.get_commit(oid).unwrap();
+    assert_eq!(commit.message, "first");
+    assert!(commit.parents.is_empty());
+    assert_eq!(commit.signature_status, SignatureStatus::Good);
+    assert_eq!(provider.verify_commit(oid).unwrap(), SignatureStatus::Good);
+    assert_eq!(provider.repository.head().unwrap().target(), Some(oid));
+}
+
+#[test]
+fn test_sign_option_overrides_config() {
+    let (_dir, provider) = init_repo();
+    let stubs = tempfile::tempdir().unwrap();
+    enable_gpg_sign(&provider, &write_stub(&stubs, "gpg", GPG_STUB));
+    stage_file(&provider, "a.txt", "a");
+    let options = CommitOptions {
+        sign: Some(false),
+        ..Default::default()
+    };
+    let oid = provider
+        .commit_with_options("first", None, &options)
+        .unwrap();
+    assert!(provider.repository.extract_signature(&oid, None).is_err());
+}
+
+#[test]
+fn test_gpg_sign_failed() {
+    let (_dir, provider) = init_repo();
+    let stubs = tempfile::tempdir().unwrap();
+    enable_gpg_sign(&provider, &write_stub(&stubs, "gpg", GPG_FAIL_STUB));
+    stage_file(&provider, "a.txt", "a");
+    let ret = provider.commit("first", None);
+    match ret {
+        Err(GitUtilsErrorCode::SignCommitError(msg)) => assert!(msg.contains("No secret key")),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(provider.repository.head().is_err());
+}
+
+#[test]
+fn test_ssh_signed_commit() {
+    let (_dir, provider) = init_repo();
+    let stubs = tempfile::tempdir().unwrap();
+    let program = write_stub(&stubs, "ssh-keygen", SSH_STUB);
+    set_config(&provider, "gpg.format", "ssh");
+    set_config(&provider, "gpg.ssh.program", program.to_str().unwrap());
+    set_config(&provider, "user.signingkey", "ssh-ed25519 AAAAstub tester");
+    stage_file(&provider, "a.txt", "a");
+    let options = CommitOptions {
+        sign: Some(true),
+        ..Default::default()
+    };
+    let oid = provider
+        .commit_with_options("first", None, &options)
+        .unwrap();
+    let (signature, _) = provider.repository.extract_signature(&oid, None).unwrap();
+    assert!(
+        signature
+            .as_str()
+            .unwrap()
+            .starts_with("-----BEGIN SSH SIGNATURE-----")
+    );
+    // 没有配置 allowedSignersFile 时，签名者可信度未知
+    assert_eq!(
+        provider.verify_commit(oid).unwrap(),
+        SignatureStatus::UnknownValidity
+    );
+}
+
+#[test]
+fn test_ssh_signature_with_allowed_signers() {
+    let (_dir, provider) = init_repo();
+    let stubs = tempfile::tempdir().unwrap();
+    let program = write_stub(&stubs, "ssh-keygen", SSH_STUB);
+    let allowed = write_stub(
+        &stubs,
+        "allowed_signers",
+        "tester@giter.dev ssh-ed25519 AAAAstub\n",
+    );
+    set_config(&provider, "commit.gpgsign", "true");
+    set_config(&provider, "gpg.format", "ssh");
+    set_config(&provider, "gpg.ssh.program", program.to_str().unwrap());
+    set_config(
+        &provider,
+        "gpg.ssh.allowedSignersFile",
+        allowed.to_str().unwrap(),
+    );
+    set_config(&provider, "user.signingkey", "/path/to/id_ed25519");
+    let oid = commit_file(&provider, "a.txt", "a", "first");
+    assert_eq!(provider.verify_commit(oid).unwrap(), SignatureStatus::Good);
+}
+
+#[test]
+fn test_verify_cache_follows_config() {
+    let (_dir, provider) = init_repo();
+    let stubs = tempfile::tempdir().unwrap();
+    let program = write_stub(&stubs, "ssh-keygen", SSH_STUB);
+    set_config(&provider, "commit.gpgsign", "true");
+    set_config(&provider, "gpg.format", "ssh");
+    set_config(&provider, "gpg.ssh.program", program.to_str().unwrap());
+    set_config(&provider, "user.signingkey", "/path/to/id_ed25519");
+    let oid = commit_file(&provider, "a.txt", "a", "first");
+    // 不确定的结果不缓存，配置 allowedSignersFile 后重新验证
+    assert_eq!(
+        provider.verify_commit(oid).unwrap(),
+        SignatureStatus::UnknownValidity
+    );
+    let allowed = write_stub(
+        &stubs,
+        "allowed_signers",
+        "tester@giter.dev ssh-ed25519 AAAAstub\n",
+    );
+    set_config(
+        &provider,
+        "gpg.ssh.allowedSignersFile",
+        allowed.to_str().unwrap(),
+    );
+    assert_eq!(provider.verify_commit(oid).unwrap(), SignatureStatus::Good);
+    // 验证程序变化后缓存失效
+    let failing = write_stub(
+        &stubs,
+        "ssh-keygen-fail",
+        "#!/bin/sh\ncat > /dev/null\nexit 1\n",
+    );
+    set_config(&provider, "gpg.ssh.program", failing.to_str().unwrap());
+    assert_ne!(provider.verify_commit(oid).unwrap(), SignatureStatus::Good);
+}
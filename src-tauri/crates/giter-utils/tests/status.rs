@@ -0,0 +1,74 @@
+mod common;
+
+use common::{commit_file, init_repo};
+use git2::Repository;
+use giter_utils::types::{
+    fetch::{CancelToken, FetchOptions},
+    git_data_provider::GitDataProvider,
+    status::WorkStatus,
+};
+
+/// 克隆 upstream，返回跟踪 origin/master 的本地仓库
+fn clone(upstream: &GitDataProvider) -> (tempfile::TempDir, GitDataProvider) {
+    let dir = tempfile::tempdir().unwrap();
+    Repository::clone(upstream.workdir().to_str().unwrap(), dir.path()).unwrap();
+    let provider = GitDataProvider::new(dir.path()).unwrap();
+    let mut config = provider.repository.config().unwrap();
+    config.set_str("user.name", common::USER_NAME).unwrap();
+    config.set_str("user.email", common::USER_EMAIL).unwrap();
+    (dir, provider)
+}
+
+fn fetch(provider: &GitDataProvider) {
+    provider
+        .fetch(
+            Some("origin"),
+            &FetchOptions::default(),
+            None,
+            &CancelToken::new(),
+            |_| {},
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_work_status_behind() {
+    let (_upstream_dir, upstream) = init_repo();
+    commit_file(&upstream, "a.txt", "a", "first");
+    let (_dir, provider) = clone(&upstream);
+    assert_eq!(
+        provider.work_status().unwrap().bits(),
+        WorkStatus::Ok.bits()
+    );
+
+    commit_file(&upstream, "b.txt", "b", "second");
+    // 拉取前不知道远程的变化
+    assert!(!provider.unpulled_commits().unwrap());
+    fetch(&provider);
+    assert!(provider.unpulled_commits().unwrap());
+    assert_eq!(
+        provider.work_status().unwrap().bits(),
+        WorkStatus::Behind.bits()
+    );
+}
+
+#[test]
+fn test_work_status_diverged() {
+    let (_upstream_dir, upstream) = init_repo();
+    commit_file(&upstream, "a.txt", "a", "first");
+    let (_dir, provider) = clone(&upstream);
+    commit_file(&upstream, "b.txt", "b", "remote");
+    commit_file(&provider, "c.txt", "c", "local");
+    fetch(&provider);
+    let status = provider.work_status().unwrap();
+    assert!(status.contains(WorkStatus::Behind));
+    assert!(status.contains(WorkStatus::Unpushed));
+}
+
+#[test]
+fn test_work_status_without_upstream() {
+    let (_dir, provider) = init_repo();
+    commit_file(&provider, "a.txt", "a", "first");
+    assert!(!provider.unpulled_commits().unwrap());
+    assert!(!provider.work_status().unwrap().contains(WorkStatus::Behind));
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use common::{commit_file, init_repo, write_file};
+use git2::Repository;
+use giter_utils::types::{
+    error::GitUtilsErrorCode, fetch::CancelToken, git_data_provider::GitDataProvider,
+    status::WorkStatus,
+};
+use tempfile::TempDir;
+
+fn file_url(provider: &GitDataProvider) -> String {
+    format!("file://{}", provider.workdir().display())
+}
+
+/// 创建包含子模块 libs/sub 的父仓库，返回 (子模块上游, 父仓库)
+fn setup() -> (TempDir, GitDataProvider, TempDir, GitDataProvider) {
+    let (sub_dir, upstream) = init_repo();
+    commit_file(&upstream, "lib.txt", "lib", "lib");
+    let (dir, provider) = init_repo();
+    commit_file(&provider, "a.txt", "a", "first");
+    let mut submodule = provider
+        .repository
+        .submodule(&file_url(&upstream), std::path::Path::new("libs/sub"), true)
+        .unwrap();
+    submodule.clone(None).unwrap();
+    submodule.add_finalize().unwrap();
+    drop(submodule);
+    provider.commit("add submodule", None).unwrap();
+    (sub_dir, upstream, dir, provider)
+}
+
+fn update(provider: &GitDataProvider, path: Option<&str>) -> Result<(), GitUtilsErrorCode> {
+    provider.update_submodule(path, true, None, &CancelToken::new(), |_| {})
+}
+
+#[test]
+fn test_list_submodules() {
+    let (_sub_dir, upstream, _dir, provider) = setup();
+    let head = upstream.repository.head().unwrap().target().unwrap();
+    let submodules = provider.submodules().unwrap();
+    assert_eq!(submodules.len(), 1);
+    let submodule = &submodules[0];
+    assert_eq!(submodule.path, "libs/sub");
+    assert_eq!(submodule.url.as_deref(), Some(file_url(&upstream).as_str()));
+    assert_eq!(submodule.recorded_id, Some(head.to_string()));
+    assert_eq!(submodule.checked_out_id, Some(head.to_string()));
+    assert!(submodule.initialized && submodule.checked_out);
+    assert!(!submodule.dirty && !submodule.out_of_date);
+    assert_eq!(
+        provider.work_status().unwrap().bits(),
+        WorkStatus::Ok.bits()
+    );
+}
+
+#[test]
+fn test_submodule_changes_in_work_status() {
+    let (_sub_dir, _upstream, _dir, provider) = setup();
+    let sub = provider.open_submodule("libs/sub/").unwrap();
+    write_file(&sub, "lib.txt", "changed");
+    let submodule = &provider.submodules().unwrap()[0];
+    assert!(submodule.dirty);
+    assert!(!submodule.out_of_date);
+    let status = provider.work_status().unwrap();
+    assert!(status.contains(WorkStatus::Submodule));
+    assert!(!status.contains(WorkStatus::Modified));
+
+    let mut config = sub.repository.config().unwrap();
+    config.set_str("user.name", common::USER_NAME).unwrap();
+    config.set_str("user.email", common::USER_EMAIL).unwrap();
+    let moved = commit_file(&sub, "lib.txt", "changed", "change lib");
+    let submodule = &provider.submodules().unwrap()[0];
+    assert!(!submodule.dirty);
+    assert!(submodule.out_of_date);
+    assert_eq!(submodule.checked_out_id, Some(moved.to_string()));
+
+    write_file(&provider, "a.txt", "a2");
+    let status = provider.work_status().unwrap();
+    assert!(status.contains(WorkStatus::Submodule | WorkStatus::Modified));
+}
+
+#[test]
+fn test_update_and_open_submodule() {
+    let (_sub_dir, upstream, _dir, parent) = setup();
+    let head = upstream.repository.head().unwrap().target().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    Repository::clone(&file_url(&parent), dir.path()).unwrap();
+    let provider = GitDataProvider::new(dir.path()).unwrap();
+
+    let submodule = &provider.submodules().unwrap()[0];
+    assert!(!submodule.initialized && !submodule.checked_out);
+    assert!(matches!(
+        provider.open_submodule("libs/sub"),
+        Err(GitUtilsErrorCode::SubmoduleNotCheckedOut(_))
+    ));
+    assert!(matches!(
+        update(&provider, Some("libs/other")),
+        Err(GitUtilsErrorCode::SubmoduleNotFound(_))
+    ));
+
+    update(&provider, Some("libs/sub")).unwrap();
+    let submodule = &provider.submodules().unwrap()[0];
+    assert!(submodule.initialized && submodule.checked_out);
+    let sub = provider.open_submodule("libs/sub").unwrap();
+    assert_eq!(sub.repository.head().unwrap().target(), Some(head));
+    assert_eq!(
+        std::fs::read_to_string(sub.workdir().join("lib.txt")).unwrap(),
+        "lib"
+    );
+}
+
+#[test]
+fn test_init_and_sync_submodule() {
+    let (_sub_dir, _upstream, _dir, mut provider) = setup();
+    provider
+        .repository
+        .submodule_set_url("libs/sub", "https://example.com/sub.git")
+        .unwrap();
+    provider.init_submodule("libs/sub", false).unwrap();
+    let config = provider.repository.config().unwrap().snapshot().unwrap();
+    assert_ne!(
+        config.get_str("submodule.libs/sub.url").unwrap(),
+        "https://example.com/sub.git"
+    );
+
+    provider.sync_submodule(None).unwrap();
+    let config = provider.repository.config().unwrap().snapshot().unwrap();
+    assert_eq!(
+        config.get_str("submodule.libs/sub.url").unwrap(),
+        "https://example.com/sub.git"
+    );
+    let sub = provider.open_submodule("libs/sub").unwrap();
+    let origin = sub.repository.find_remote("origin").unwrap();
+    assert_eq!(origin.url(), Some("https://example.com/sub.git"));
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::{USER_EMAIL, USER_NAME, commit_file, init_repo, write_file};
+use git2::{Repository, Signature};
+use giter_utils::types::{status::WorkStatus, summary::InProgressOperation};
+
+#[test]
+fn test_summary_empty_repo() {
+    let (_dir, provider) = init_repo();
+    let summary = provider.summary().unwrap();
+    assert!(summary.branch.is_none());
+    assert!(summary.last_commit.is_none());
+    assert!(summary.upstream.is_none());
+    assert_eq!((summary.ahead, summary.behind), (0, 0));
+    assert_eq!(summary.stash_count, 0);
+    assert!(summary.operation.is_none());
+}
+
+#[test]
+fn test_summary_ahead_behind_upstream() {
+    let (_dir, provider) = init_repo();
+    let base = commit_file(&provider, "a.txt", "a", "base");
+    let repo = &provider.repository;
+    // 模拟远程比本地多一个提交
+    let base_commit = repo.find_commit(base).unwrap();
+    let sig = Signature::now(USER_NAME, USER_EMAIL).unwrap();
+    let remote_id = repo
+        .commit(
+            None,
+            &sig,
+            &sig,
+            "remote",
+            &base_commit.tree().unwrap(),
+            &[&base_commit],
+        )
+        .unwrap();
+    repo.reference("refs/remotes/origin/master", remote_id, true, "")
+        .unwrap();
+    let mut config = repo.config().unwrap();
+    config.set_str("branch.master.remote", "origin").unwrap();
+    config
+        .set_str("branch.master.merge", "refs/heads/master")
+        .unwrap();
+    repo.remote("origin", "https://example.com/repo.git")
+        .unwrap();
+    commit_file(&provider, "b.txt", "b", "local 1");
+    commit_file(&provider, "c.txt", "c", "local 2");
+
+    let summary = provider.summary().unwrap();
+    assert_eq!(summary.branch.as_deref(), Some("master"));
+    assert_eq!(summary.upstream.as_deref(), Some("origin/master"));
+    assert_eq!((summary.ahead, summary.behind), (2, 1));
+    assert_eq!(summary.last_commit.unwrap().title, "local 2");
+    assert!(summary.status.contains(WorkStatus::Unpushed));
+    assert!(summary.status.contains(WorkStatus::Behind));
+}
+
+#[test]
+fn test_summary_stash_count() {
+    let (dir, provider) = init_repo();
+    commit_file(&provider, "a.txt", "a", "first");
+    let mut repo = Repository::open(dir.path()).unwrap();
+    let sig = Signature::now(USER_NAME, USER_EMAIL).unwrap();
+    for content in ["b", "c"] {
+        write_file(&provider, "a.txt", content);
+        repo.stash_save(&sig, content, None).unwrap();
+    }
+
+    let summary = provider.summary().unwrap();
+    assert_eq!(summary.stash_count, 2);
+    assert!(summary.status.contains(WorkStatus::Ok));
+}
+
+#[test]
+fn test_summary_merge_in_progress() {
+    let (dir, provider) = init_repo();
+    let head = commit_file(&provider, "a.txt", "a", "first");
+    std::fs::write(dir.path().join(".git/MERGE_HEAD"), format!("{}\n", head)).unwrap();
+
+    let summary = provider.summary().unwrap();
+    assert_eq!(summary.operation, Some(InProgressOperation::Merge));
+}
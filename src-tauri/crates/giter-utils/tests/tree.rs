@@ -0,0 +1,231 @@
+mod common;
+
+use common::{USER_NAME, commit_file, init_repo};
+use giter_utils::{
+    types::{error::GitUtilsErrorCode, fs::FsNode, tree::TreeEntryKind},
+    util::str_to_oid,
+};
+
+#[test]
+fn test_list_tree_pages() {
+    let (_dir, provider) = init_repo();
+    for name in ["a.txt", "b.txt", "c.txt", "d.txt"] {
+        commit_file(&provider, &format!("src/{}", name), name, name);
+    }
+    commit_file(&provider, "README.md", "readme", "readme");
+
+    let root = provider.list_tree("HEAD", "", None, 10).unwrap();
+    let names = root
+        .entries
+        .iter()
+        .map(|e| e.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["README.md", "src"]);
+    assert_eq!(root.entries[1].kind, TreeEntryKind::Dir);
+    assert!(root.cursor.is_none());
+
+    let mut paths = vec![];
+    let mut cursor = None;
+    loop {
+        let page = provider
+            .list_tree("HEAD", "src/", cursor.as_deref(), 3)
+            .unwrap();
+        paths.extend(page.entries.into_iter().map(|e| e.path));
+        cursor = page.cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(paths, ["src/a.txt", "src/b.txt", "src/c.txt", "src/d.txt"]);
+
+    // 目录中没有 cursor 时不会从第一项重新开始
+    let ret = provider.list_tree("HEAD", "src", Some("e.txt"), 3);
+    assert!(matches!(ret, Err(GitUtilsErrorCode::InvalidCursor(_))));
+
+    let ret = provider.list_tree("HEAD", "missing", None, 10);
+    assert!(matches!(ret, Err(GitUtilsErrorCode::TreeNotFound(_))));
+}
+
+#[test]
+fn test_tree_entry_details() {
+    let (_dir, provider) = init_repo();
+    commit_file(&provider, "src/a.txt", "one", "add a");
+    commit_file(&provider, "src/b.txt", "two", "add b");
+    commit_file(&provider, "README.md", "hello", "add readme");
+    commit_file(&provider, "src/a.txt", "changed", "change a");
+    commit_file(&provider, "README.md", "hello world", "change readme");
+
+    let paths = ["src/a.txt", "src/b.txt", "src", "missing"].map(String::from);
+    let details = provider.tree_entry_details("HEAD", &paths).unwrap();
+    let found = details
+        .iter()
+        .map(|d| {
+            let title = d.last_commit.as_ref().map(|c| c.title.as_str());
+            (d.path.as_str(), d.size, title)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            ("src/a.txt", Some(7), Some("change a")),
+            ("src/b.txt", Some(3), Some("add b")),
+            ("src", None, Some("change a")),
+        ]
+    );
+}
+
+#[test]
+fn test_tree_recursive_and_path_index() {
+    let (_dir, provider) = init_repo();
+    commit_file(&provider, "src/main.rs", "fn main() {}", "main");
+    commit_file(&provider, "src/types/tree.rs", "", "tree");
+    let head = commit_file(&provider, "docs/readme.md", "docs", "docs");
+
+    let root = provider.get_tree_recursive(head, None).unwrap();
+    let FsNode::Dir(src) = &root.children[1] else {
+        panic!("src should be a directory");
+    };
+    assert_eq!(src.name, "src");
+    let FsNode::Dir(types) = &src.children[1] else {
+        panic!("src/types should be a directory");
+    };
+    assert_eq!(types.path, "src");
+    let FsNode::File(file) = &types.children[0] else {
+        panic!("src/types/tree.rs should be a file");
+    };
+    assert_eq!(file.path, "src/types");
+    assert_eq!(file.name, "tree.rs");
+
+    let index = provider.path_index("HEAD").unwrap();
+    assert_eq!(
+        index.paths,
+        ["docs/readme.md", "src/main.rs", "src/types/tree.rs"]
+    );
+    let found = provider.find_files("HEAD", "str", 10).unwrap();
+    assert_eq!(found[0].path, "src/types/tree.rs");
+    assert_eq!(found[0].positions, [8, 10, 15]);
+    assert_eq!(found.len(), 1);
+    // query 为空时按索引顺序返回
+    let found = provider.find_files("HEAD", "", 2).unwrap();
+    let paths = found.iter().map(|m| m.path.as_str()).collect::<Vec<_>>();
+    assert_eq!(paths, ["docs/readme.md", "src/main.rs"]);
+    // 只返回得分最高的 limit 个，顺序与完整结果相同
+    let all = provider.find_files("HEAD", "r", 10).unwrap();
+    assert_eq!(all.len(), 3);
+    assert_eq!(provider.find_files("HEAD", "r", 2).unwrap(), all[..2]);
+}
+
+#[test]
+fn test_get_tree_last_commits() {
+    let (_dir, provider) = init_repo();
+    commit_file(&provider, "src/a.txt", "one", "add a");
+    commit_file(&provider, "README.md", "hello", "add readme");
+    commit_file(&provider, "src/b.txt", "two", "add b");
+    commit_file(&provider, "docs/c.md", "docs", "add docs");
+    let head = commit_file(&provider, "README.md", "hello world", "change readme");
+
+    let mut root = provider.get_tree(head, None).unwrap();
+    // get_tree 不会遍历历史
+    assert!(root.children.iter().all(|c| match c {
+        FsNode::File(f) => f.metadata.last_commit.is_none(),
+        FsNode::Dir(d) => d.metadata.last_commit.is_none(),
+    }));
+    provider.annotate_last_commits(head, &mut root).unwrap();
+    let titles = root
+        .children
+        .iter()
+        .map(|c| match c {
+            FsNode::File(f) => (f.name.as_str(), f.metadata.last_commit.as_ref()),
+            FsNode::Dir(d) => (d.name.as_str(), d.metadata.last_commit.as_ref()),
+        })
+        .map(|(name, c)| (name, c.map(|c| c.title.as_str())))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        titles,
+        [
+            ("README.md", Some("change readme")),
+            ("docs", Some("add docs")),
+            ("src", Some("add b")),
+        ]
+    );
+
+    let FsNode::Dir(src) = &root.children[2] else {
+        panic!("src should be a directory");
+    };
+    let mut src = provider
+        .get_tree(
+            str_to_oid(&src.metadata.object_id).unwrap(),
+            Some("src".into()),
+        )
+        .unwrap();
+    assert!(src.children.iter().all(|c| match c {
+        FsNode::File(f) => f.metadata.last_commit.is_none(),
+        FsNode::Dir(_) => false,
+    }));
+    provider.annotate_last_commits(head, &mut src).unwrap();
+    let commits = provider.tree_last_commits(head, "src").unwrap();
+    assert_eq!(commits["a.txt"].title, "add a");
+    assert_eq!(commits["b.txt"].title, "add b");
+    assert_eq!(commits["a.txt"].author_name, USER_NAME);
+}
+
+#[test]
+fn test_last_commits_follow_merges() {
+    let (_dir, provider) = init_repo();
+    let repo = &provider.repository;
+    commit_file(&provider, "a.txt", "a", "add a");
+    let base = commit_file(&provider, "b.txt", "b", "add b");
+    let main = commit_file(&provider, "a.txt", "a2", "change a on main");
+
+    // 从 base 创建分支修改 b.txt，然后合并到主线
+    let sig = repo.signature().unwrap();
+    let base_commit = repo.find_commit(base).unwrap();
+    let mut builder = repo
+        .treebuilder(Some(&base_commit.tree().unwrap()))
+        .unwrap();
+    builder
+        .insert("b.txt", repo.blob(b"b2").unwrap(), 0o100644)
+        .unwrap();
+    let side_tree = repo.find_tree(builder.write().unwrap()).unwrap();
+    let side = repo
+        .commit(
+            None,
+            &sig,
+            &sig,
+            "change b on side",
+            &side_tree,
+            &[&base_commit],
+        )
+        .unwrap();
+    let mut builder = repo.treebuilder(Some(&side_tree)).unwrap();
+    builder
+        .insert("a.txt", repo.blob(b"a2").unwrap(), 0o100644)
+        .unwrap();
+    let merged_tree = repo.find_tree(builder.write().unwrap()).unwrap();
+    let main_commit = repo.find_commit(main).unwrap();
+    let side_commit = repo.find_commit(side).unwrap();
+    let merge = repo
+        .commit(
+            None,
+            &sig,
+            &sig,
+            "merge side",
+            &merged_tree,
+            &[&main_commit, &side_commit],
+        )
+        .unwrap();
+
+    let commits = provider.tree_last_commits(merge, "").unwrap();
+    assert_eq!(commits["a.txt"].title, "change a on main");
+    assert_eq!(commits["b.txt"].title, "change b on side");
+    assert_eq!(commits["b.txt"].commit_id, side.to_string());
+
+    let paths = ["b.txt".to_string()];
+    let details = provider
+        .tree_entry_details(&merge.to_string(), &paths)
+        .unwrap();
+    assert_eq!(
+        details[0].last_commit.as_ref().unwrap().title,
+        "change b on side"
+    );
+}
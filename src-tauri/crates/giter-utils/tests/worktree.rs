@@ -0,0 +1,173 @@
+mod common;
+
+use common::{commit_file, init_repo};
+use git2::BranchType;
+use giter_utils::types::{
+    error::GitUtilsErrorCode,
+    git_data_provider::GitDataProvider,
+    worktree::{AddWorktreeOptions, Worktree},
+};
+
+fn find<'a>(worktrees: &'a [Worktree], name: &str) -> &'a Worktree {
+    worktrees.iter().find(|w| w.name == name).unwrap()
+}
+
+#[test]
+fn test_add_and_list_worktrees() {
+    let (_dir, provider) = init_repo();
+    let head = commit_file(&provider, "a.txt", "a", "first");
+    let repo = &provider.repository;
+    repo.branch("feature", &repo.find_commit(head).unwrap(), false)
+        .unwrap();
+    let wt_dir = tempfile::tempdir().unwrap();
+
+    let added = provider
+        .add_worktree(
+            "wt-new",
+            wt_dir.path().join("new"),
+            &AddWorktreeOptions::default(),
+        )
+        .unwrap();
+    assert_eq!(added.branch.as_deref(), Some("wt-new"));
+    assert_eq!(added.head_id, Some(head.to_string()));
+    assert!(wt_dir.path().join("new/a.txt").exists());
+
+    let options = AddWorktreeOptions {
+        branch: Some("feature".to_string()),
+        lock: true,
+        ..Default::default()
+    };
+    provider
+        .add_worktree("wt-feature", wt_dir.path().join("feature"), &options)
+        .unwrap();
+
+    let worktrees = provider.worktrees().unwrap();
+    assert_eq!(worktrees.len(), 3);
+    assert!(worktrees[0].is_main);
+    assert_eq!(worktrees[0].branch.as_deref(), Some("master"));
+    let feature = find(&worktrees, "wt-feature");
+    assert_eq!(feature.branch.as_deref(), Some("feature"));
+    assert!(feature.locked);
+    assert_eq!(feature.lock_reason.as_deref(), Some(""));
+
+    // 从链接的工作树中也能看到所有工作树
+    let linked = GitDataProvider::new(wt_dir.path().join("new")).unwrap();
+    assert_eq!(linked.worktrees().unwrap(), worktrees);
+    assert_eq!(
+        linked.main_worktree().unwrap().canonicalize().unwrap(),
+        provider.workdir().canonicalize().unwrap()
+    );
+    assert_eq!(provider.main_worktree(), None);
+}
+
+#[test]
+fn test_add_worktree_errors() {
+    let (_dir, provider) = init_repo();
+    commit_file(&provider, "a.txt", "a", "first");
+    let wt_dir = tempfile::tempdir().unwrap();
+
+    let options = AddWorktreeOptions {
+        branch: Some("master".to_string()),
+        ..Default::default()
+    };
+    let ret = provider.add_worktree("wt", wt_dir.path().join("wt"), &options);
+    assert!(matches!(
+        ret,
+        Err(GitUtilsErrorCode::BranchAlreadyCheckedOut(_))
+    ));
+    let options = AddWorktreeOptions {
+        branch: Some("missing".to_string()),
+        ..Default::default()
+    };
+    let ret = provider.add_worktree("wt", wt_dir.path().join("wt"), &options);
+    assert!(matches!(ret, Err(GitUtilsErrorCode::BranchNotFound(_))));
+
+    let options = AddWorktreeOptions {
+        branch: Some("topic".to_string()),
+        new_branch: true,
+        ..Default::default()
+    };
+    provider
+        .add_worktree("wt", wt_dir.path().join("wt"), &options)
+        .unwrap();
+    let ret = provider.add_worktree("wt", wt_dir.path().join("other"), &options);
+    assert!(matches!(
+        ret,
+        Err(GitUtilsErrorCode::WorktreeAlreadyExists(_))
+    ));
+    assert!(
+        provider
+            .repository
+            .find_branch("topic", BranchType::Local)
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_branches_show_worktree() {
+    let (_dir, provider) = init_repo();
+    commit_file(&provider, "a.txt", "a", "first");
+    let wt_dir = tempfile::tempdir().unwrap();
+    let path = wt_dir.path().join("wt");
+    provider
+        .add_worktree("wt", &path, &AddWorktreeOptions::default())
+        .unwrap();
+
+    let branches = provider.branches().unwrap();
+    let worktree_of = |name: &str| {
+        branches
+            .iter()
+            .find(|b| b.name == name)
+            .unwrap()
+            .worktree
+            .clone()
+    };
+    assert_eq!(
+        worktree_of("master"),
+        Some(
+            provider
+                .workdir()
+                .to_string_lossy()
+                .trim_end_matches('/')
+                .to_string()
+        )
+    );
+    assert_eq!(worktree_of("wt").map(std::path::PathBuf::from), Some(path));
+}
+
+#[test]
+fn test_lock_and_prune_worktrees() {
+    let (_dir, provider) = init_repo();
+    commit_file(&provider, "a.txt", "a", "first");
+    let wt_dir = tempfile::tempdir().unwrap();
+    for name in ["wt-a", "wt-b"] {
+        provider
+            .add_worktree(
+                name,
+                wt_dir.path().join(name),
+                &AddWorktreeOptions::default(),
+            )
+            .unwrap();
+    }
+    provider.lock_worktree("wt-a", Some("usb disk")).unwrap();
+    let worktrees = provider.worktrees().unwrap();
+    assert_eq!(
+        find(&worktrees, "wt-a").lock_reason.as_deref(),
+        Some("usb disk")
+    );
+    assert!(matches!(
+        provider.lock_worktree("wt-c", None),
+        Err(GitUtilsErrorCode::WorktreeNotFound(_))
+    ));
+
+    std::fs::remove_dir_all(wt_dir.path().join("wt-a")).unwrap();
+    std::fs::remove_dir_all(wt_dir.path().join("wt-b")).unwrap();
+    let worktrees = provider.worktrees().unwrap();
+    assert!(!find(&worktrees, "wt-a").prunable);
+    assert!(find(&worktrees, "wt-b").prunable);
+    assert_eq!(provider.prune_worktrees().unwrap(), vec!["wt-b"]);
+
+    provider.unlock_worktree("wt-a").unwrap();
+    assert_eq!(provider.prune_worktrees().unwrap(), vec!["wt-a"]);
+    assert_eq!(provider.worktrees().unwrap().len(), 1);
+}
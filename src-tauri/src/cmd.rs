@@ -1,34 +1,64 @@
 use crate::{
-    core::handle,
-    emit::emit_branch_contribution,
+    core::{
+        credential_store::EncryptedCredentialStore,
+        group::{self, GroupOperation, RepoOperationResult, RepoSummaryResult},
+        handle,
+        store::GitStore,
+    },
+    emit::{
+        emit_branch_contribution, emit_pickaxe_match, emit_scanned_repo, emit_transfer_progress,
+    },
     types::{
         cache::RepoPath,
-        error::{CommandError, CommonErrorCode as CommonError},
+        error::{CommandError, CommonErrorCode as CommonError, FromTaskFailure},
         fs::Dir,
-        store,
+        store::{self, RepoGroup},
     },
     utils::{
         dirs,
         fs::{get_first_level_dirs, get_logical_driver, scan_repos_in_folder},
     },
+    RemoteTasks,
 };
 use giter_macros::command_result;
 use giter_utils::{
     types::{
         author::Author,
         branch::Branch,
+        clone::CloneOptions,
         commit::Commit,
+        commit_options::CommitOptions,
+        credential::Credential,
         diff::ContentDiff,
         error::GitUtilsErrorCode,
+        fetch::{CancelToken, FetchOptions},
         file::{ChangedFile, CommittedEntry, FileHistoryEntry},
         git_data_provider::GitDataProvider,
+        graph::{GraphCursor, GraphPage},
+        grep::{GrepOptions, GrepResult, GrepSource},
+        init::{InitOptions, GITIGNORE_TEMPLATES},
+        pickaxe::{PickaxeOptions, PickaxePage},
+        pull::PullOptions,
+        push::PushOptions,
+        remote::Remote,
+        scan::{self, ScanOptions, ScannedRepo},
+        search::{CommitQuery, CommitSearchPage},
+        sign::SignatureStatus,
         status::WorkStatus,
+        submodule::Submodule,
+        tree::{FuzzyMatch, LastCommits, TreeEntryDetail, TreePage},
+        worktree::{AddWorktreeOptions, Worktree},
     },
     util::{is_git_repo, set_owner, str_to_oid},
 };
 use giter_watcher::{error::WatcherErrorCode as WatcherError, modify_watcher::ModifyWatcher};
 use serde_json::Value;
-use std::{collections::HashMap, path::PathBuf, sync::Mutex, thread};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+};
 use tauri::{ipc::Response, Manager, Url, WebviewBuilder};
 
 type DataResult<T> = std::result::Result<T, CommandError<GitUtilsErrorCode>>;
@@ -41,6 +71,49 @@ fn get_provider(repo: &str) -> Result<GitDataProvider, GitUtilsErrorCode> {
     Ok(provider)
 }
 
+/// 在后台线程中执行耗时的任务，错误包装为 func 命令的 CommandError
+async fn run_blocking<T, E>(
+    func: &str,
+    task: impl FnOnce() -> Result<T, E> + Send + 'static,
+) -> Result<T, CommandError<E>>
+where
+    T: Send + 'static,
+    E: FromTaskFailure + std::fmt::Display + giter_traits::ExposeError + Send + 'static,
+{
+    let etype = std::any::type_name::<E>()
+        .rsplit("::")
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .unwrap_or_else(|e| Err(E::task_failed(e.to_string())))
+        .map_err(|e| CommandError::new(func, e, etype))
+}
+
+/// 在后台线程中执行可以取消的任务，任务开始之前注册 task_id，
+/// 执行期间可以通过 cancel_remote_task 取消
+async fn run_cancellable<T, E>(
+    func: &str,
+    task_id: String,
+    task: impl FnOnce(&CancelToken, &str) -> Result<T, E> + Send + 'static,
+) -> Result<T, CommandError<E>>
+where
+    T: Send + 'static,
+    E: FromTaskFailure + std::fmt::Display + giter_traits::ExposeError + Send + 'static,
+{
+    let app = handle::Handle::global().app_handle().unwrap();
+    let cancel = CancelToken::new();
+    app.state::<RemoteTasks>()
+        .0
+        .write()
+        .insert(task_id.clone(), cancel.clone());
+    let id = task_id.clone();
+    let ret = run_blocking(func, move || task(&cancel, &id)).await;
+    app.state::<RemoteTasks>().0.write().remove(&task_id);
+    ret
+}
+
 fn watch(repo: RepoPath) -> Result<(), WatcherError> {
     let app = handle::Handle::global().app_handle().unwrap();
     let watch_center = app.state::<Mutex<ModifyWatcher>>();
@@ -74,6 +147,248 @@ pub async fn repos() -> CommonResult<Vec<store::Repository>> {
     Ok(repos)
 }
 
+/// 把仓库添加到仓库列表中并开始监控
+#[tauri::command]
+#[command_result]
+pub async fn add_repo(path: String, alias: Option<String>) -> CommonResult<store::Repository> {
+    if !is_git_repo(&path) {
+        return Err(CommonError::PathInvalid(path));
+    }
+    let store = handle::Handle::global().store().unwrap();
+    let repo = store
+        .add_repo(
+            path.clone(),
+            alias.filter(|a| !a.is_empty()),
+            Some(true),
+            None,
+            None,
+        )
+        .map_err(CommonError::StoreError)?;
+    if let Err(e) = watch(path) {
+        log::error!("watch repo error: {}", e);
+    }
+    Ok(repo)
+}
+
+/// 更新仓库的信息，包括分组、标签、颜色、备注和默认分支
+#[tauri::command]
+#[command_result]
+pub async fn update_repo(repo: store::Repository) -> CommonResult<()> {
+    let store = handle::Handle::global().store().unwrap();
+    store.update_repo(repo).map_err(CommonError::StoreError)
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn rename_repo(id: i32, alias: String) -> CommonResult<()> {
+    let store = handle::Handle::global().store().unwrap();
+    store
+        .rename_repo(id, &alias)
+        .map_err(CommonError::StoreError)
+}
+
+/// 从仓库列表中删除仓库并停止监控，不会删除仓库的文件
+#[tauri::command]
+#[command_result]
+pub async fn delete_repo(id: i32) -> CommonResult<()> {
+    let store = handle::Handle::global().store().unwrap();
+    let repo = store
+        .get_repo(id)
+        .ok_or_else(|| CommonError::RepoNotFound(id.to_string()))?;
+    store
+        .delete_repo(&repo.path)
+        .map_err(CommonError::StoreError)?;
+    let app = handle::Handle::global().app_handle().unwrap();
+    let watch_center = app.state::<Mutex<ModifyWatcher>>();
+    let mut watcher = watch_center
+        .lock()
+        .map_err(|_| CommonError::GetWatcherCenterFailed)?;
+    let _ = watcher.remove_watch(repo.path);
+    Ok(())
+}
+
+/// 记录仓库最后一次打开的时间
+#[tauri::command]
+#[command_result]
+pub async fn touch_repo(path: String) -> CommonResult<()> {
+    let store = handle::Handle::global().store().unwrap();
+    store.touch_repo(&path).map_err(CommonError::StoreError)
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn groups() -> CommonResult<Vec<RepoGroup>> {
+    let store = handle::Handle::global().store().unwrap();
+    store.get_groups().map_err(CommonError::StoreError)
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn add_group(name: String) -> CommonResult<RepoGroup> {
+    let store = handle::Handle::global().store().unwrap();
+    store.add_group(&name).map_err(CommonError::StoreError)
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn rename_group(id: i32, name: String) -> CommonResult<()> {
+    let store = handle::Handle::global().store().unwrap();
+    store
+        .rename_group(id, &name)
+        .map_err(CommonError::StoreError)
+}
+
+/// 删除仓库组，组中的仓库保留在仓库列表中
+#[tauri::command]
+#[command_result]
+pub async fn delete_group(id: i32) -> CommonResult<()> {
+    let store = handle::Handle::global().store().unwrap();
+    store.delete_group(id).map_err(CommonError::StoreError)
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn reorder_groups(ids: Vec<i32>) -> CommonResult<()> {
+    let store = handle::Handle::global().store().unwrap();
+    store.reorder_groups(&ids).map_err(CommonError::StoreError)
+}
+
+/// 组或者仓库不存在时返回 GroupNotFound 或 RepoNotFound
+fn check_group_repos(store: &GitStore, group_id: i32, repo_ids: &[i32]) -> Result<(), CommonError> {
+    if !store.has_group(group_id).map_err(CommonError::StoreError)? {
+        return Err(CommonError::GroupNotFound(group_id.to_string()));
+    }
+    for id in repo_ids {
+        if !store.has_repo(*id).map_err(CommonError::StoreError)? {
+            return Err(CommonError::RepoNotFound(id.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// 替换组中的仓库，组内顺序与 repo_ids 相同
+#[tauri::command]
+#[command_result]
+pub async fn set_group_repos(group_id: i32, repo_ids: Vec<i32>) -> CommonResult<()> {
+    let store = handle::Handle::global().store().unwrap();
+    check_group_repos(&store, group_id, &repo_ids)?;
+    store
+        .set_group_repos(group_id, &repo_ids)
+        .map_err(CommonError::StoreError)
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn add_repo_to_group(group_id: i32, repo_id: i32) -> CommonResult<()> {
+    let store = handle::Handle::global().store().unwrap();
+    check_group_repos(&store, group_id, &[repo_id])?;
+    store
+        .add_repo_to_group(group_id, repo_id)
+        .map_err(CommonError::StoreError)
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn remove_repo_from_group(group_id: i32, repo_id: i32) -> CommonResult<()> {
+    let store = handle::Handle::global().store().unwrap();
+    store
+        .remove_repo_from_group(group_id, repo_id)
+        .map_err(CommonError::StoreError)
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn group_repos(group_id: i32) -> CommonResult<Vec<store::Repository>> {
+    let store = handle::Handle::global().store().unwrap();
+    store.group_repos(group_id).map_err(CommonError::StoreError)
+}
+
+/// 在后台线程中对组中的所有仓库执行操作，单个仓库失败不影响其他仓库，
+/// 可以通过 task_id 取消还没有开始的仓库
+async fn run_group_task(
+    name: &str,
+    group_id: i32,
+    operation: GroupOperation,
+    task_id: String,
+) -> CommonResult<Vec<RepoOperationResult>> {
+    run_cancellable(name, task_id, move |cancel, _| {
+        let store = handle::Handle::global().store().unwrap();
+        check_group_repos(&store, group_id, &[])?;
+        let paths = store
+            .group_repos(group_id)
+            .map_err(CommonError::StoreError)?
+            .into_iter()
+            .map(|r| r.path)
+            .collect::<Vec<_>>();
+        Ok(group::run_group(&paths, operation, cancel))
+    })
+    .await
+}
+
+/// 拉取组中所有仓库的远程
+#[tauri::command]
+pub async fn fetch_group(group_id: i32, task_id: String) -> CommonResult<Vec<RepoOperationResult>> {
+    run_group_task("fetch_group", group_id, GroupOperation::Fetch, task_id).await
+}
+
+/// 获取组中所有仓库的状态
+#[tauri::command]
+pub async fn group_status(
+    group_id: i32,
+    task_id: String,
+) -> CommonResult<Vec<RepoOperationResult>> {
+    run_group_task("group_status", group_id, GroupOperation::Status, task_id).await
+}
+
+/// 组中的仓库从上游拉取当前分支，没有上游的仓库返回错误
+#[tauri::command]
+pub async fn pull_group(group_id: i32, task_id: String) -> CommonResult<Vec<RepoOperationResult>> {
+    run_group_task("pull_group", group_id, GroupOperation::Pull, task_id).await
+}
+
+/// 并行获取仓库列表中所有仓库的概览，单个仓库失败时只在该仓库的结果中返回错误
+#[tauri::command]
+pub async fn repos_summary() -> CommonResult<Vec<RepoSummaryResult>> {
+    run_blocking("repos_summary", || {
+        let store = handle::Handle::global().store().unwrap();
+        let paths = store
+            .get_repos()
+            .map_err(|_| CommonError::GetReposFailed)?
+            .into_iter()
+            .map(|r| r.path)
+            .collect::<Vec<_>>();
+        Ok(group::summarize(&paths))
+    })
+    .await
+}
+
+/// 在所有仓库或者 group_id 组中的仓库中搜索提交，结果按时间倒序分页
+#[tauri::command]
+pub async fn search_commits(
+    query: CommitQuery,
+    group_id: Option<i32>,
+    offset: Option<usize>,
+    count: Option<usize>,
+) -> CommonResult<CommitSearchPage> {
+    run_blocking("search_commits", move || {
+        let store = handle::Handle::global().store().unwrap();
+        let repos = match group_id {
+            Some(group_id) => store
+                .group_repos(group_id)
+                .map_err(CommonError::StoreError)?,
+            None => store.get_repos().map_err(|_| CommonError::GetReposFailed)?,
+        };
+        let paths = repos.into_iter().map(|r| r.path).collect::<Vec<_>>();
+        Ok(group::search_commits(
+            &paths,
+            &query,
+            offset.unwrap_or(0),
+            count.unwrap_or(50),
+        ))
+    })
+    .await
+}
+
 #[tauri::command]
 #[command_result]
 pub async fn add_watch(repo: RepoPath) -> WatcherResult<()> {
@@ -182,6 +497,19 @@ pub async fn branch_commits(repo: RepoPath, branch: Branch, count: i32) -> DataR
     provider.branch_commits(&branch, count)
 }
 
+/// 计算多个引用的提交图，cursor 为上一页返回的 cursor，为空时从第一页开始
+#[tauri::command]
+#[command_result]
+pub async fn commit_graph(
+    repo: RepoPath,
+    refs: Vec<String>,
+    count: usize,
+    cursor: Option<GraphCursor>,
+) -> DataResult<GraphPage> {
+    let provider = get_provider(&repo)?;
+    provider.commit_graph(&refs, count, cursor.as_ref())
+}
+
 #[tauri::command]
 #[command_result]
 pub async fn before_reference_commits_count(repo: RepoPath, reference: String) -> DataResult<i32> {
@@ -189,6 +517,14 @@ pub async fn before_reference_commits_count(repo: RepoPath, reference: String) -
     provider.before_reference_commits_count(&reference)
 }
 
+/// 生成或更新 commit-graph 文件，加快大仓库中 git 命令计算提交数量
+#[tauri::command]
+#[command_result]
+pub async fn write_commit_graph(repo: RepoPath) -> DataResult<()> {
+    let provider = get_provider(&repo)?;
+    provider.write_commit_graph()
+}
+
 #[tauri::command]
 #[command_result]
 pub async fn current_branch(repo: RepoPath) -> DataResult<Branch> {
@@ -236,6 +572,15 @@ pub async fn get_commit(repo: RepoPath, cid: String) -> DataResult<Commit> {
     provider.get_commit(commit_id)
 }
 
+/// 验证提交的签名，提交列表中有签名的提交为 Unverified，需要显示验证结果时调用
+#[tauri::command]
+#[command_result]
+pub async fn verify_commit(repo: RepoPath, cid: String) -> DataResult<SignatureStatus> {
+    let provider = get_provider(&repo)?;
+    let commit_id = str_to_oid(&cid)?;
+    provider.verify_commit(commit_id)
+}
+
 #[tauri::command]
 #[command_result]
 pub async fn get_branch_commit_contribution(
@@ -332,9 +677,15 @@ pub async fn checkout_file(repo: RepoPath, path: String) -> DataResult<()> {
 
 #[tauri::command]
 #[command_result]
-pub async fn commit(repo: RepoPath, message: &str, update_ref: Option<&str>) -> DataResult<String> {
+pub async fn commit(
+    repo: RepoPath,
+    message: &str,
+    update_ref: Option<&str>,
+    options: Option<CommitOptions>,
+) -> DataResult<String> {
     let provider = get_provider(&repo)?;
-    let commit_id = provider.commit(message, update_ref)?;
+    let options = options.unwrap_or_default();
+    let commit_id = provider.commit_with_options(message, update_ref, &options)?;
     Ok::<String, GitUtilsErrorCode>(commit_id.to_string())
 }
 
@@ -345,10 +696,137 @@ pub async fn push(
     remote: String,
     branch: String,
     credentials: Option<(String, String)>,
+    options: Option<PushOptions>,
 ) -> DataResult<()> {
     let provider = get_provider(&repo)?;
-    provider.push(&remote, &branch, credentials)
+    provider.push(&remote, &branch, credentials, &options.unwrap_or_default())
 }
+/// 拉取远程更新，在后台线程中执行以便可以被取消，
+/// 进度通过 giter://transfer_progress/{task_id} 事件发送
+#[tauri::command]
+pub async fn fetch(
+    repo: RepoPath,
+    remote: Option<String>,
+    options: Option<FetchOptions>,
+    credentials: Option<(String, String)>,
+    task_id: String,
+) -> DataResult<()> {
+    run_cancellable("fetch", task_id, move |cancel, task_id| {
+        let provider = get_provider(&repo)?;
+        provider.fetch(
+            remote.as_deref(),
+            &options.unwrap_or_default(),
+            credentials,
+            cancel,
+            |progress| emit_transfer_progress(task_id, progress),
+        )
+    })
+    .await
+}
+
+/// 克隆远程仓库，在后台线程中执行以便可以被取消，
+/// 进度通过 giter://transfer_progress/{task_id} 事件发送，
+/// 克隆成功后把仓库添加到仓库列表中并开始监控
+#[tauri::command]
+pub async fn clone_repo(
+    url: String,
+    path: String,
+    options: Option<CloneOptions>,
+    credentials: Option<(String, String)>,
+    task_id: String,
+) -> DataResult<store::Repository> {
+    run_cancellable("clone_repo", task_id, move |cancel, task_id| {
+        let options = options.unwrap_or_default();
+        GitDataProvider::clone_repo(&url, &path, &options, credentials, cancel, |progress| {
+            emit_transfer_progress(task_id, progress)
+        })?;
+        let store = handle::Handle::global().store().unwrap();
+        let repo = store
+            .add_repo(path.clone(), None, Some(!options.bare), None, None)
+            .map_err(GitUtilsErrorCode::OtherError)?;
+        if !options.bare {
+            if let Err(e) = watch(path) {
+                log::error!("watch cloned repo error: {}", e);
+            }
+        }
+        Ok(repo)
+    })
+    .await
+}
+
+/// 在 path 初始化仓库，成功后仓库会被添加到仓库列表中
+#[tauri::command]
+#[command_result]
+pub async fn init_repo(
+    path: String,
+    options: Option<InitOptions>,
+) -> DataResult<store::Repository> {
+    GitDataProvider::init_repo(&path, &options.unwrap_or_default())?;
+    let store = handle::Handle::global().store().unwrap();
+    let repo = store
+        .add_repo(path.clone(), None, Some(true), None, None)
+        .map_err(GitUtilsErrorCode::OtherError)?;
+    if let Err(e) = watch(path) {
+        log::error!("watch initialized repo error: {}", e);
+    }
+    Ok(repo)
+}
+
+/// 内置的 .gitignore 模板名称
+#[tauri::command]
+pub fn gitignore_templates() -> Vec<&'static str> {
+    GITIGNORE_TEMPLATES.iter().map(|(name, _)| *name).collect()
+}
+
+/// 取消正在进行的远程操作
+#[tauri::command]
+pub fn cancel_remote_task(task_id: String, tasks: tauri::State<RemoteTasks>) {
+    if let Some(cancel) = tasks.0.read().get(&task_id) {
+        cancel.cancel();
+    }
+}
+
+fn credential_store() -> Arc<EncryptedCredentialStore> {
+    let app = handle::Handle::global().app_handle().unwrap();
+    app.state::<Arc<EncryptedCredentialStore>>().inner().clone()
+}
+
+/// 保存远程 url 所在 host 的凭证
+#[tauri::command]
+#[command_result]
+pub async fn save_credential(url: String, credential: Credential) -> CommonResult<()> {
+    credential_store()
+        .add(&url, credential)
+        .map_err(|e| CommonError::CredentialStoreError(e.to_string()))
+}
+
+/// 删除远程 url 所在 host 保存的凭证
+#[tauri::command]
+#[command_result]
+pub async fn remove_credential(url: String) -> CommonResult<()> {
+    credential_store()
+        .remove(&url)
+        .map_err(|e| CommonError::CredentialStoreError(e.to_string()))
+}
+
+/// 保存了凭证的 host 列表
+#[tauri::command]
+#[command_result]
+pub async fn credential_hosts() -> CommonResult<Vec<String>> {
+    credential_store()
+        .hosts()
+        .map_err(|e| CommonError::CredentialStoreError(e.to_string()))
+}
+
+/// 清除保存的凭证和密钥，密钥丢失或损坏导致凭证无法解密时由用户确认调用
+#[tauri::command]
+#[command_result]
+pub async fn reset_credentials() -> CommonResult<()> {
+    credential_store()
+        .reset()
+        .map_err(|e| CommonError::CredentialStoreError(e.to_string()))
+}
+
 #[tauri::command]
 #[command_result]
 pub async fn pull(
@@ -356,9 +834,163 @@ pub async fn pull(
     remote: String,
     branch: String,
     credentials: Option<(String, String)>,
+    options: Option<PullOptions>,
+) -> DataResult<()> {
+    let provider = get_provider(&repo)?;
+    provider.pull(&remote, &branch, credentials, &options.unwrap_or_default())
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn remotes(repo: RepoPath) -> DataResult<Vec<Remote>> {
+    let provider = get_provider(&repo)?;
+    provider.remotes()
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn add_remote(repo: RepoPath, name: String, url: String) -> DataResult<Remote> {
+    let provider = get_provider(&repo)?;
+    provider.add_remote(&name, &url)
+}
+
+/// 返回无法自动更新的 fetch refspec
+#[tauri::command]
+#[command_result]
+pub async fn rename_remote(
+    repo: RepoPath,
+    name: String,
+    new_name: String,
+) -> DataResult<Vec<String>> {
+    let provider = get_provider(&repo)?;
+    provider.rename_remote(&name, &new_name)
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn remove_remote(repo: RepoPath, name: String) -> DataResult<()> {
+    let provider = get_provider(&repo)?;
+    provider.remove_remote(&name)
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn set_remote_url(
+    repo: RepoPath,
+    name: String,
+    url: String,
+    push_url: Option<String>,
+) -> DataResult<Remote> {
+    let provider = get_provider(&repo)?;
+    provider.set_remote_url(&name, &url, push_url.as_deref())
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn submodules(repo: RepoPath) -> DataResult<Vec<Submodule>> {
+    let provider = get_provider(&repo)?;
+    provider.submodules()
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn init_submodule(repo: RepoPath, path: String, overwrite: bool) -> DataResult<()> {
+    let provider = get_provider(&repo)?;
+    provider.init_submodule(&path, overwrite)
+}
+
+/// path 为空时同步所有子模块
+#[tauri::command]
+#[command_result]
+pub async fn sync_submodule(repo: RepoPath, path: Option<String>) -> DataResult<()> {
+    let provider = get_provider(&repo)?;
+    provider.sync_submodule(path.as_deref())
+}
+
+/// 初始化并更新子模块，path 为空时更新所有子模块，在后台线程中执行以便可以被取消，
+/// 进度通过 giter://transfer_progress/{task_id} 事件发送
+#[tauri::command]
+pub async fn update_submodule(
+    repo: RepoPath,
+    path: Option<String>,
+    recursive: bool,
+    credentials: Option<(String, String)>,
+    task_id: String,
 ) -> DataResult<()> {
+    run_cancellable("update_submodule", task_id, move |cancel, task_id| {
+        let provider = get_provider(&repo)?;
+        provider.update_submodule(
+            path.as_deref(),
+            recursive,
+            credentials,
+            cancel,
+            |progress| emit_transfer_progress(task_id, progress),
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn worktrees(repo: RepoPath) -> DataResult<Vec<Worktree>> {
+    let provider = get_provider(&repo)?;
+    provider.worktrees()
+}
+
+/// 添加工作树，成功后工作树会作为仓库添加到仓库列表中
+#[tauri::command]
+#[command_result]
+pub async fn add_worktree(
+    repo: RepoPath,
+    name: String,
+    path: String,
+    options: Option<AddWorktreeOptions>,
+) -> DataResult<Worktree> {
+    let provider = get_provider(&repo)?;
+    let worktree = provider.add_worktree(&name, &path, &options.unwrap_or_default())?;
+    let store = handle::Handle::global().store().unwrap();
+    store
+        .add_repo(path.clone(), None, Some(true), None, None)
+        .map_err(GitUtilsErrorCode::OtherError)?;
+    if let Err(e) = watch(path) {
+        log::error!("watch worktree error: {}", e);
+    }
+    Ok(worktree)
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn lock_worktree(repo: RepoPath, name: String, reason: Option<String>) -> DataResult<()> {
+    let provider = get_provider(&repo)?;
+    provider.lock_worktree(&name, reason.as_deref())
+}
+
+#[tauri::command]
+#[command_result]
+pub async fn unlock_worktree(repo: RepoPath, name: String) -> DataResult<()> {
     let provider = get_provider(&repo)?;
-    provider.pull(&remote, &branch, credentials)
+    provider.unlock_worktree(&name)
+}
+
+/// 返回被清理的工作树名称
+#[tauri::command]
+#[command_result]
+pub async fn prune_worktrees(repo: RepoPath) -> DataResult<Vec<String>> {
+    let provider = get_provider(&repo)?;
+    provider.prune_worktrees()
+}
+
+/// 返回子模块的工作目录，可以作为单独的仓库打开
+#[tauri::command]
+#[command_result]
+pub async fn open_submodule(repo: RepoPath, path: String) -> DataResult<String> {
+    let provider = get_provider(&repo)?;
+    let submodule = provider.open_submodule(&path)?;
+    Ok(submodule
+        .workdir()
+        .to_string_lossy()
+        .trim_end_matches(['/', '\\'])
+        .to_string())
 }
 
 #[tauri::command]
@@ -385,16 +1017,95 @@ pub async fn get_commit_tree_recursive(
     provider.get_tree_recursive(commit_id, None)
 }
 
+/// 传入浏览的提交 commit_id 时为每一项添加最后一次修改的提交，需要遍历历史，不传时不会计算
 #[tauri::command]
 #[command_result]
 pub async fn get_tree(
     repo: RepoPath,
     object_id: String,
     tree_path: Option<String>,
+    commit_id: Option<String>,
 ) -> DataResult<giter_utils::types::fs::Dir> {
     let provider = get_provider(&repo)?;
     let object_id = str_to_oid(&object_id)?;
-    provider.get_tree(object_id, tree_path)
+    let mut dir = provider.get_tree(object_id, tree_path)?;
+    if let Some(commit_id) = commit_id {
+        provider.annotate_last_commits(str_to_oid(&commit_id)?, &mut dir)?;
+    }
+    Ok(dir)
+}
+
+/// 提交中 dir 目录下每一项最后一次修改的提交
+#[tauri::command]
+#[command_result]
+pub async fn tree_last_commits(
+    repo: RepoPath,
+    commit_id: String,
+    dir: String,
+) -> DataResult<LastCommits> {
+    let provider = get_provider(&repo)?;
+    let commit_id = str_to_oid(&commit_id)?;
+    let commits = provider.tree_last_commits(commit_id, &dir)?;
+    Ok(commits.as_ref().clone())
+}
+
+/// 分页列出 rev 所指的树中 path 目录下的项
+#[tauri::command]
+#[command_result]
+pub async fn list_tree(
+    repo: RepoPath,
+    rev: String,
+    path: String,
+    cursor: Option<String>,
+    count: usize,
+) -> DataResult<TreePage> {
+    let provider = get_provider(&repo)?;
+    provider.list_tree(&rev, &path, cursor.as_deref(), count)
+}
+
+/// 获取多个路径的大小和最后一次修改的提交
+#[tauri::command]
+#[command_result]
+pub async fn tree_entry_details(
+    repo: RepoPath,
+    rev: String,
+    paths: Vec<String>,
+) -> DataResult<Vec<TreeEntryDetail>> {
+    let provider = get_provider(&repo)?;
+    provider.tree_entry_details(&rev, &paths)
+}
+
+/// 树中所有文件的路径
+#[tauri::command]
+#[command_result]
+pub async fn tree_paths(repo: RepoPath, rev: String) -> DataResult<Vec<String>> {
+    let provider = get_provider(&repo)?;
+    Ok(provider.path_index(&rev)?.paths.clone())
+}
+
+/// 在树中模糊查找文件
+#[tauri::command]
+#[command_result]
+pub async fn find_files(
+    repo: RepoPath,
+    rev: String,
+    query: String,
+    limit: usize,
+) -> DataResult<Vec<FuzzyMatch>> {
+    let provider = get_provider(&repo)?;
+    provider.find_files(&rev, &query, limit)
+}
+
+/// 在工作区、暂存区或者提交的文件中搜索内容
+#[tauri::command]
+#[command_result]
+pub async fn grep(
+    repo: RepoPath,
+    source: GrepSource,
+    options: GrepOptions,
+) -> DataResult<GrepResult> {
+    let provider = get_provider(&repo)?;
+    provider.grep(&source, &options)
 }
 
 #[tauri::command]
@@ -461,3 +1172,37 @@ pub async fn scan_repos_folder(folder_path: String) -> CommonResult<Vec<String>>
         .map_err(|e| CommonError::PathInvalid(e.to_string()))?;
     Ok(repos)
 }
+
+/// 在提交历史中搜索新增或删除了匹配内容的提交，在后台线程中执行以便可以被取消，
+/// 每找到一个提交通过 giter://pickaxe_match/{task_id} 事件发送
+#[tauri::command]
+pub async fn pickaxe(
+    repo: RepoPath,
+    options: PickaxeOptions,
+    task_id: String,
+) -> DataResult<PickaxePage> {
+    run_cancellable("pickaxe", task_id, move |cancel, task_id| {
+        let provider = get_provider(&repo)?;
+        provider.pickaxe(&options, cancel, |found| emit_pickaxe_match(task_id, found))
+    })
+    .await
+}
+
+/// 递归扫描文件夹下的仓库，在后台线程中执行以便可以被取消，
+/// 每发现一个仓库通过 giter://scanned_repo/{task_id} 事件发送
+#[tauri::command]
+pub async fn scan_repos(
+    folder_path: String,
+    options: Option<ScanOptions>,
+    task_id: String,
+) -> DataResult<Vec<ScannedRepo>> {
+    run_cancellable("scan_repos", task_id, move |cancel, task_id| {
+        scan::scan_repos(
+            Path::new(&folder_path),
+            &options.unwrap_or_default(),
+            cancel,
+            |repo| emit_scanned_repo(task_id, repo),
+        )
+    })
+    .await
+}
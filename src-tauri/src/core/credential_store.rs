@@ -0,0 +1,253 @@
+use std::{collections::HashMap, fs, io::ErrorKind, path::PathBuf};
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use anyhow::{anyhow, Result};
+use giter_utils::types::credential::{url_host, Credential, CredentialStore};
+use parking_lot::Mutex;
+
+use crate::utils::dirs;
+
+const NONCE_LEN: usize = 12;
+
+/// 应用保存的凭证，按远程的 host 分组，使用 AES-256-GCM 加密后保存在本地
+#[derive(Debug)]
+pub struct EncryptedCredentialStore {
+    path: PathBuf,
+    key_path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl EncryptedCredentialStore {
+    pub fn new() -> Result<Self> {
+        let store = Self::with_paths(dirs::credential_file()?, dirs::credential_key_file()?);
+        // 旧版本的密钥和凭证保存在同一个目录下，移动到新的位置
+        let legacy_key = dirs::legacy_credential_key_file()?;
+        if legacy_key.exists() && !store.key_path.exists() {
+            if let Some(parent) = store.key_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(&legacy_key, &store.key_path)?;
+        }
+        Ok(store)
+    }
+
+    pub fn with_paths(path: PathBuf, key_path: PathBuf) -> Self {
+        Self {
+            path,
+            key_path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// 读取密钥，密钥和凭证文件都不存在时生成新的密钥
+    ///
+    /// 密钥无效、读取失败，或者凭证文件存在但密钥丢失时返回错误，不会丢弃已保存的凭证，
+    /// 由用户决定是否调用 reset 清除
+    fn cipher(&self) -> Result<Aes256Gcm> {
+        match fs::read(&self.key_path) {
+            Ok(bytes) if bytes.len() == 32 => {
+                return Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&bytes)))
+            }
+            Ok(_) => {
+                return Err(anyhow!(
+                    "credential key {} is invalid",
+                    self.key_path.display()
+                ))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        if self.path.exists() {
+            return Err(anyhow!(
+                "credential key {} is missing, saved credentials can not be decrypted",
+                self.key_path.display()
+            ));
+        }
+        let key = Aes256Gcm::generate_key(OsRng);
+        if let Some(parent) = self.key_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // windows 上密钥在本地(不漫游)的应用数据目录中，继承用户目录只有当前用户可以访问的权限
+        fs::write(&self.key_path, key.as_slice())?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&self.key_path, fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(Aes256Gcm::new(&key))
+    }
+
+    /// 清除保存的凭证和密钥，密钥丢失或损坏后由用户确认调用
+    pub fn reset(&self) -> Result<()> {
+        let _guard = self.lock.lock();
+        for path in [&self.path, &self.key_path] {
+            match fs::remove_file(path) {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn load(&self) -> Result<HashMap<String, Vec<Credential>>> {
+        let data = match fs::read(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+        if data.len() < NONCE_LEN {
+            return Err(anyhow!("credential file is corrupted"));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher()?
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("decrypt credentials failed"))?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    fn save(&self, credentials: &HashMap<String, Vec<Credential>>) -> Result<()> {
+        let cipher = self.cipher()?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let plaintext = serde_json::to_vec(credentials)?;
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| anyhow!("encrypt credentials failed"))?;
+        let mut data = nonce.to_vec();
+        data.extend(ciphertext);
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, data)?;
+        Ok(())
+    }
+
+    fn host(url: &str) -> Result<String> {
+        url_host(url).ok_or_else(|| anyhow!("can not resolve host of {}", url))
+    }
+
+    /// 保存凭证，同一 host 下相同类型的凭证会被替换
+    pub fn add(&self, url: &str, credential: Credential) -> Result<()> {
+        let host = Self::host(url)?;
+        let _guard = self.lock.lock();
+        let mut credentials = self.load()?;
+        let list = credentials.entry(host).or_default();
+        list.retain(|c| std::mem::discriminant(c) != std::mem::discriminant(&credential));
+        list.push(credential);
+        self.save(&credentials)
+    }
+
+    /// 删除 host 下保存的所有凭证
+    pub fn remove(&self, url: &str) -> Result<()> {
+        let host = Self::host(url)?;
+        let _guard = self.lock.lock();
+        let mut credentials = self.load()?;
+        if credentials.remove(&host).is_some() {
+            self.save(&credentials)?;
+        }
+        Ok(())
+    }
+
+    /// 保存了凭证的 host 列表
+    pub fn hosts(&self) -> Result<Vec<String>> {
+        let _guard = self.lock.lock();
+        let mut hosts = self.load()?.into_keys().collect::<Vec<_>>();
+        hosts.sort();
+        Ok(hosts)
+    }
+}
+
+impl CredentialStore for EncryptedCredentialStore {
+    fn get(&self, url: &str) -> Vec<Credential> {
+        let Some(host) = url_host(url) else {
+            return vec![];
+        };
+        let _guard = self.lock.lock();
+        match self.load() {
+            Ok(mut credentials) => credentials.remove(&host).unwrap_or_default(),
+            Err(e) => {
+                log::error!("load credentials error: {}", e);
+                vec![]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 每个测试使用单独的临时目录
+    fn store(name: &str) -> (PathBuf, EncryptedCredentialStore) {
+        let dir =
+            std::env::temp_dir().join(format!("giter-credential-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        let store = EncryptedCredentialStore::with_paths(
+            dir.join("data/credentials.bin"),
+            dir.join("key/credential.key"),
+        );
+        (dir, store)
+    }
+
+    const URL: &str = "https://github.com/user/repo.git";
+
+    #[test]
+    fn test_round_trip() {
+        let (dir, store) = store("round-trip");
+        store.add(URL, Credential::Token("one".into())).unwrap();
+        store.add(URL, Credential::Token("two".into())).unwrap();
+        store
+            .add(
+                "git@gitlab.com:user/repo.git",
+                Credential::UsernamePassword("u".into(), "p".into()),
+            )
+            .unwrap();
+        let credentials = store.get(URL);
+        assert_eq!(credentials.len(), 1);
+        assert!(matches!(&credentials[0], Credential::Token(t) if t == "two"));
+        assert_eq!(store.hosts().unwrap(), ["github.com", "gitlab.com"]);
+        // 文件中不是明文
+        let data = fs::read(&store.path).unwrap();
+        assert!(!String::from_utf8_lossy(&data).contains("two"));
+
+        store.remove(URL).unwrap();
+        assert!(store.get(URL).is_empty());
+        assert_eq!(store.hosts().unwrap(), ["gitlab.com"]);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_corrupt_key_keeps_credentials() {
+        let (dir, store) = store("corrupt-key");
+        store.add(URL, Credential::Token("token".into())).unwrap();
+        let data = fs::read(&store.path).unwrap();
+        fs::write(&store.key_path, b"broken").unwrap();
+
+        assert!(store.add(URL, Credential::Token("new".into())).is_err());
+        assert!(store.get(URL).is_empty());
+        assert_eq!(fs::read(&store.path).unwrap(), data);
+        assert_eq!(fs::read(&store.key_path).unwrap(), b"broken");
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_missing_key_keeps_credentials_until_reset() {
+        let (dir, store) = store("missing-key");
+        store.add(URL, Credential::Token("token".into())).unwrap();
+        fs::remove_file(&store.key_path).unwrap();
+
+        assert!(store.hosts().is_err());
+        assert!(store.add(URL, Credential::Token("new".into())).is_err());
+        assert!(store.path.exists());
+        assert!(!store.key_path.exists());
+
+        store.reset().unwrap();
+        assert!(store.hosts().unwrap().is_empty());
+        store.add(URL, Credential::Token("new".into())).unwrap();
+        assert_eq!(store.get(URL).len(), 1);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
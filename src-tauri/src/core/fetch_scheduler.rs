@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use giter_utils::types::{
+    fetch::{CancelToken, FetchOptions},
+    git_data_provider::GitDataProvider,
+    status::WorkStatus,
+};
+
+use crate::{core::handle, emit::emit_status_changed, utils::config::get_config_value};
+
+/// 自动拉取间隔(秒)的配置键，为0时关闭自动拉取
+pub const AUTO_FETCH_INTERVAL_KEY: &str = "auto_fetch_interval";
+/// 同时拉取的仓库数量的配置键
+pub const AUTO_FETCH_CONCURRENCY_KEY: &str = "auto_fetch_concurrency";
+/// 单个仓库拉取的超时时间(秒)的配置键
+pub const AUTO_FETCH_TIMEOUT_KEY: &str = "auto_fetch_timeout";
+
+const DEFAULT_INTERVAL: u64 = 10 * 60;
+const DEFAULT_CONCURRENCY: usize = 4;
+const DEFAULT_TIMEOUT: u64 = 2 * 60;
+/// 失败重试的最大间隔
+const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+/// 检查是否有需要拉取的仓库的间隔
+const TICK: Duration = Duration::from_secs(5);
+
+type FetchFn = fn(&str, &CancelToken) -> Result<WorkStatus, String>;
+type FetchResult = (String, Result<WorkStatus, String>);
+
+/// 正在进行的拉取
+#[derive(Debug)]
+struct Running {
+    started: Instant,
+    cancel: CancelToken,
+    /// 已经超时并按失败处理，线程结束后忽略它的结果
+    timed_out: bool,
+}
+
+#[derive(Debug, Default)]
+struct RepoSchedule {
+    next_fetch: Option<Instant>,
+    failures: u32,
+    status: Option<u32>,
+    running: Option<Running>,
+}
+
+impl RepoSchedule {
+    /// 记录拉取的结果，成功并且仓库状态发生变化时返回新的状态
+    fn finish(
+        &mut self,
+        path: &str,
+        result: Result<WorkStatus, String>,
+        interval: u64,
+    ) -> Option<WorkStatus> {
+        match result {
+            Ok(status) => {
+                self.failures = 0;
+                self.next_fetch = Some(Instant::now() + Duration::from_secs(interval));
+                if self.status == Some(status.bits()) {
+                    return None;
+                }
+                self.status = Some(status.bits());
+                Some(status)
+            }
+            Err(e) => {
+                // 失败后按指数退避重试，避免频繁请求无法访问的远程
+                self.failures += 1;
+                let backoff = Duration::from_secs(interval)
+                    .saturating_mul(1 << self.failures.min(6))
+                    .min(MAX_BACKOFF);
+                self.next_fetch = Some(Instant::now() + backoff);
+                log::warn!(
+                    "auto fetch {} failed {} times, retry after {:?}: {}",
+                    path,
+                    self.failures,
+                    backoff,
+                    e
+                );
+                None
+            }
+        }
+    }
+}
+
+/// 后台定时拉取所有开启了监控(has_watch)的仓库，
+/// 拉取后仓库状态(如落后于远程)发生变化时发送 giter://status_changed 事件
+///
+/// 每个仓库在单独的线程中拉取，调度线程不等待拉取结束；
+/// 超过超时时间的拉取会被取消并按失败处理，不会阻塞之后的拉取
+#[derive(Debug)]
+pub struct FetchScheduler {
+    repos: HashMap<String, RepoSchedule>,
+    fetch: FetchFn,
+    sender: Sender<FetchResult>,
+    receiver: Receiver<FetchResult>,
+}
+
+impl FetchScheduler {
+    fn new(fetch: FetchFn) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        FetchScheduler {
+            repos: HashMap::new(),
+            fetch,
+            sender,
+            receiver,
+        }
+    }
+
+    /// 在后台线程中启动定时拉取
+    pub fn start() {
+        thread::spawn(|| {
+            let mut scheduler = FetchScheduler::new(fetch_repo);
+            while !*handle::Handle::global().is_exiting.read() {
+                scheduler.tick();
+                thread::sleep(TICK);
+            }
+        });
+    }
+
+    fn interval() -> u64 {
+        get_config_value(AUTO_FETCH_INTERVAL_KEY)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_INTERVAL)
+    }
+
+    fn timeout() -> Duration {
+        let secs = get_config_value(AUTO_FETCH_TIMEOUT_KEY)
+            .and_then(|v| v.parse().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_TIMEOUT);
+        Duration::from_secs(secs)
+    }
+
+    /// 同时拉取的仓库数量，组操作也使用该数量
+    pub fn concurrency() -> usize {
+        get_config_value(AUTO_FETCH_CONCURRENCY_KEY)
+            .and_then(|v| v.parse().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_CONCURRENCY)
+    }
+
+    fn tick(&mut self) {
+        let interval = Self::interval();
+        let changed = self.collect(interval, Self::timeout());
+        if let Some(app) = handle::Handle::global().app_handle() {
+            for (path, status) in changed {
+                emit_status_changed(&app, &path, status);
+            }
+        }
+        if interval == 0 {
+            return;
+        }
+        let store = match handle::Handle::global().store() {
+            Some(store) => store,
+            None => return,
+        };
+        let repos = match store.get_repos() {
+            Ok(repos) => repos,
+            Err(e) => {
+                log::error!("auto fetch: get repos error: {}", e);
+                return;
+            }
+        };
+        let paths = repos
+            .into_iter()
+            .filter(|r| r.has_watch)
+            .map(|r| r.path)
+            .collect::<Vec<_>>();
+        self.schedule(paths, Self::concurrency());
+    }
+
+    /// 处理已经结束和超时的拉取，返回状态发生变化的仓库
+    fn collect(&mut self, interval: u64, timeout: Duration) -> Vec<(String, WorkStatus)> {
+        let mut changed = vec![];
+        while let Ok((path, result)) = self.receiver.try_recv() {
+            let Some(schedule) = self.repos.get_mut(&path) else {
+                continue;
+            };
+            // 超时的拉取已经按失败处理
+            if schedule.running.take().is_none_or(|r| r.timed_out) {
+                continue;
+            }
+            if let Some(status) = schedule.finish(&path, result, interval) {
+                changed.push((path, status));
+            }
+        }
+        for (path, schedule) in self.repos.iter_mut() {
+            let Some(running) = schedule.running.as_mut() else {
+                continue;
+            };
+            if running.timed_out || running.started.elapsed() < timeout {
+                continue;
+            }
+            // 传输中的拉取在下一次进度回调时中断；卡在连接阶段的线程结束前不会再次拉取这个仓库
+            running.cancel.cancel();
+            running.timed_out = true;
+            schedule.finish(
+                path,
+                Err(format!("timed out after {:?}", timeout)),
+                interval,
+            );
+        }
+        changed
+    }
+
+    /// 开始拉取到期的仓库，没有超时的拉取最多同时进行 concurrency 个
+    fn schedule(&mut self, paths: Vec<String>, concurrency: usize) {
+        // 移除已经删除或者关闭监控的仓库
+        self.repos.retain(|path, _| paths.contains(path));
+        let running = self
+            .repos
+            .values()
+            .filter(|s| s.running.as_ref().is_some_and(|r| !r.timed_out))
+            .count();
+        let now = Instant::now();
+        for path in &paths {
+            self.repos.entry(path.clone()).or_default();
+        }
+        let due = paths
+            .into_iter()
+            .filter(|path| {
+                let schedule = &self.repos[path];
+                schedule.running.is_none() && schedule.next_fetch.is_none_or(|next| next <= now)
+            })
+            .take(concurrency.saturating_sub(running))
+            .collect::<Vec<_>>();
+        for path in due {
+            let cancel = CancelToken::new();
+            if let Some(schedule) = self.repos.get_mut(&path) {
+                schedule.running = Some(Running {
+                    started: now,
+                    cancel: cancel.clone(),
+                    timed_out: false,
+                });
+            }
+            let fetch = self.fetch;
+            let sender = self.sender.clone();
+            thread::spawn(move || {
+                let result = fetch(&path, &cancel);
+                let _ = sender.send((path, result));
+            });
+        }
+    }
+}
+
+/// 拉取仓库的所有远程，返回拉取后的仓库状态
+fn fetch_repo(path: &str, cancel: &CancelToken) -> Result<WorkStatus, String> {
+    let provider = GitDataProvider::new(path).map_err(|e| e.to_string())?;
+    provider
+        .fetch(None, &FetchOptions::default(), None, cancel, |_| {})
+        .map_err(|e| e.to_string())?;
+    provider.work_status().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn behind(_: &str, _: &CancelToken) -> Result<WorkStatus, String> {
+        Ok(WorkStatus::Behind)
+    }
+
+    fn unreachable(_: &str, _: &CancelToken) -> Result<WorkStatus, String> {
+        Err("unreachable".to_string())
+    }
+
+    /// "hang" 一直等到被取消，其他仓库立即成功
+    fn hang(path: &str, cancel: &CancelToken) -> Result<WorkStatus, String> {
+        while path == "hang" && !cancel.is_cancelled() {
+            thread::sleep(Duration::from_millis(5));
+        }
+        Ok(WorkStatus::Ok)
+    }
+
+    /// 等待所有没有超时的拉取结束
+    fn wait(scheduler: &mut FetchScheduler, timeout: Duration) -> Vec<(String, WorkStatus)> {
+        let mut changed = vec![];
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            changed.extend(scheduler.collect(60, timeout));
+            let running = scheduler
+                .repos
+                .values()
+                .any(|s| s.running.as_ref().is_some_and(|r| !r.timed_out));
+            if !running || Instant::now() > deadline {
+                return changed;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_status_change_reported_once() {
+        let mut scheduler = FetchScheduler::new(behind);
+        let timeout = Duration::from_secs(60);
+        scheduler.schedule(paths(&["a"]), 4);
+        let changed = wait(&mut scheduler, timeout);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].1.bits(), WorkStatus::Behind.bits());
+        // 没有到下一次拉取的时间
+        scheduler.schedule(paths(&["a"]), 4);
+        assert!(scheduler.repos["a"].running.is_none());
+        // 状态没有变化时不再报告
+        scheduler.repos.get_mut("a").unwrap().next_fetch = None;
+        scheduler.schedule(paths(&["a"]), 4);
+        assert!(wait(&mut scheduler, timeout).is_empty());
+    }
+
+    #[test]
+    fn test_failure_backoff() {
+        let mut scheduler = FetchScheduler::new(unreachable);
+        scheduler.schedule(paths(&["a", "b"]), 4);
+        assert!(wait(&mut scheduler, Duration::from_secs(60)).is_empty());
+        let schedule = &scheduler.repos["a"];
+        assert_eq!(schedule.failures, 1);
+        let next = schedule.next_fetch.unwrap() - Instant::now();
+        assert!(next > Duration::from_secs(60) && next <= Duration::from_secs(120));
+        // 关闭监控后移除
+        scheduler.schedule(paths(&["b"]), 4);
+        assert!(!scheduler.repos.contains_key("a"));
+    }
+
+    #[test]
+    fn test_timeout_does_not_block_other_repos() {
+        let mut scheduler = FetchScheduler::new(hang);
+        scheduler.schedule(paths(&["hang", "ok"]), 1);
+        assert!(scheduler.repos["hang"].running.is_some());
+        assert!(scheduler.repos["ok"].running.is_none());
+
+        // 超时后取消并按失败处理，不再占用并发数量
+        assert!(scheduler.collect(60, Duration::ZERO).is_empty());
+        assert_eq!(scheduler.repos["hang"].failures, 1);
+        scheduler.schedule(paths(&["hang", "ok"]), 1);
+        assert!(scheduler.repos["ok"].running.is_some());
+
+        let changed = wait(&mut scheduler, Duration::from_secs(60));
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].0, "ok");
+        assert_eq!(changed[0].1.bits(), WorkStatus::Ok.bits());
+        // 超时的拉取结束后忽略结果，之后可以再次拉取
+        thread::sleep(Duration::from_millis(50));
+        assert!(scheduler.collect(60, Duration::from_secs(60)).is_empty());
+        let hang = &scheduler.repos["hang"];
+        assert!(hang.running.is_none());
+        assert_eq!(hang.failures, 1);
+        assert_eq!(hang.status, None);
+    }
+}
@@ -0,0 +1,183 @@
+use std::thread;
+
+use giter_utils::types::{
+    fetch::{CancelToken, FetchOptions},
+    git_data_provider::GitDataProvider,
+    pull::PullOptions,
+    search::{merge_search_results, CommitQuery, CommitSearchPage},
+    status::WorkStatus,
+    summary::RepoSummary,
+};
+use serde::Serialize;
+
+use crate::{core::fetch_scheduler::FetchScheduler, core::handle, emit::emit_status_changed};
+
+/// 对组中单个仓库执行操作的结果，失败时 error 不为空，不影响组中的其他仓库
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoOperationResult {
+    pub path: String,
+    pub status: Option<WorkStatus>,
+    pub error: Option<String>,
+}
+
+/// 单个仓库的概览，获取失败时 error 不为空
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoSummaryResult {
+    pub path: String,
+    pub summary: Option<RepoSummary>,
+    pub error: Option<String>,
+}
+
+/// 组操作
+#[derive(Debug, Clone, Copy)]
+pub enum GroupOperation {
+    /// 拉取所有远程
+    Fetch,
+    /// 只获取仓库状态
+    Status,
+    /// 从上游拉取并整合当前分支
+    Pull,
+}
+
+/// 以与自动拉取相同的并发数对 paths 中的每个仓库执行 f，结果的顺序与 paths 相同；
+/// 单个仓库失败不影响其他仓库，cancel 被取消后还没有开始的仓库返回错误
+pub fn for_each_repo<T: Send>(
+    paths: &[String],
+    cancel: &CancelToken,
+    f: impl Fn(&str) -> Result<T, String> + Sync,
+) -> Vec<Result<T, String>> {
+    let f = &f;
+    let mut results = Vec::with_capacity(paths.len());
+    for chunk in paths.chunks(FetchScheduler::concurrency()) {
+        if cancel.is_cancelled() {
+            results.extend(chunk.iter().map(|_| Err("operation cancelled".to_string())));
+            continue;
+        }
+        thread::scope(|s| {
+            let handles = chunk
+                .iter()
+                .map(|path| s.spawn(move || f(path)))
+                .collect::<Vec<_>>();
+            for (h, path) in handles.into_iter().zip(chunk) {
+                results.push(
+                    h.join()
+                        .unwrap_or_else(|_| Err(format!("{} panicked", path))),
+                );
+            }
+        });
+    }
+    results
+}
+
+/// 按组内顺序对 paths 中的仓库执行操作，操作成功后发送 giter://status_changed 事件
+pub fn run_group(
+    paths: &[String],
+    operation: GroupOperation,
+    cancel: &CancelToken,
+) -> Vec<RepoOperationResult> {
+    let results = for_each_repo(paths, cancel, |path| run_repo(path, operation, cancel));
+    paths
+        .iter()
+        .zip(results)
+        .map(|(path, result)| match result {
+            Ok(status) => {
+                if let Some(app) = handle::Handle::global().app_handle() {
+                    emit_status_changed(&app, path, status.clone());
+                }
+                RepoOperationResult {
+                    path: path.clone(),
+                    status: Some(status),
+                    error: None,
+                }
+            }
+            Err(e) => {
+                log::warn!("group {:?} {} error: {}", operation, path, e);
+                RepoOperationResult {
+                    path: path.clone(),
+                    status: None,
+                    error: Some(e),
+                }
+            }
+        })
+        .collect()
+}
+
+fn run_repo(
+    path: &str,
+    operation: GroupOperation,
+    cancel: &CancelToken,
+) -> Result<WorkStatus, String> {
+    let provider = GitDataProvider::new(path).map_err(|e| e.to_string())?;
+    match operation {
+        GroupOperation::Fetch => {
+            provider
+                .fetch(None, &FetchOptions::default(), None, cancel, |_| {})
+                .map_err(|e| e.to_string())?;
+        }
+        GroupOperation::Status => {}
+        GroupOperation::Pull => {
+            let branch = provider.current_branch().map_err(|e| e.to_string())?;
+            let remote = provider
+                .repository
+                .config()
+                .and_then(|c| c.get_string(&format!("branch.{}.remote", branch.name)))
+                .map_err(|_| format!("branch {} has no upstream", branch.name))?;
+            provider
+                .pull(&remote, &branch.name, None, &PullOptions::default())
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    provider.work_status().map_err(|e| e.to_string())
+}
+
+/// 并行获取 paths 中所有仓库的概览，结果的顺序与 paths 相同
+pub fn summarize(paths: &[String]) -> Vec<RepoSummaryResult> {
+    let results = for_each_repo(paths, &CancelToken::new(), |path| {
+        let provider = GitDataProvider::new(path).map_err(|e| e.to_string())?;
+        provider.summary().map_err(|e| e.to_string())
+    });
+    paths
+        .iter()
+        .zip(results)
+        .map(|(path, result)| match result {
+            Ok(summary) => RepoSummaryResult {
+                path: path.clone(),
+                summary: Some(summary),
+                error: None,
+            },
+            Err(e) => RepoSummaryResult {
+                path: path.clone(),
+                summary: None,
+                error: Some(e),
+            },
+        })
+        .collect()
+}
+
+/// 并行在 paths 中的所有仓库中搜索提交，按时间倒序合并后返回第 offset 个开始的 count 个提交
+pub fn search_commits(
+    paths: &[String],
+    query: &CommitQuery,
+    offset: usize,
+    count: usize,
+) -> CommitSearchPage {
+    // 多取一个用于判断是否还有下一页
+    let limit = offset + count + 1;
+    let results = for_each_repo(paths, &CancelToken::new(), |path| {
+        let provider = GitDataProvider::new(path).map_err(|e| e.to_string())?;
+        provider
+            .search_commits(query, limit)
+            .map_err(|e| e.to_string())
+    });
+    let mut commits = vec![];
+    let mut errors = vec![];
+    for (path, result) in paths.iter().zip(results) {
+        match result {
+            Ok(result) => commits.push(result),
+            Err(e) => errors.push((path.clone(), e)),
+        }
+    }
+    merge_search_results(commits, offset, count, errors)
+}
@@ -1,2 +1,5 @@
+pub mod credential_store;
+pub mod fetch_scheduler;
+pub mod group;
 pub mod handle;
 pub mod store;
@@ -1,11 +1,14 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 
-use rusqlite::params;
+use giter_utils::types::git_data_provider::GitDataProvider;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use rusqlite::{params, Row};
 
 use crate::{
     types::{
         cache::RepoPath,
-        store::{self, Repository},
+        store::{self, RepoGroup, Repository},
     },
     utils::{
         db::conn_db,
@@ -13,6 +16,29 @@ use crate::{
     },
 };
 
+/// 仓库表中 store::Repository 使用的列，按列名读取
+const REPO_COLUMNS: &str = "id, path, alias, has_watch, `order`, top, folder, tags, color, \
+    last_opened, notes, default_branch";
+
+fn repo_from_row(row: &Row) -> rusqlite::Result<Repository> {
+    let tags: String = row.get("tags")?;
+    Ok(Repository {
+        id: row.get("id")?,
+        path: row.get("path")?,
+        alias: row.get::<_, Option<String>>("alias")?.unwrap_or_default(),
+        has_watch: row.get("has_watch")?,
+        order: row.get::<_, Option<i32>>("order")?.unwrap_or(-1),
+        top: row.get("top")?,
+        folder: row.get("folder")?,
+        tags: serde_json::from_str(&tags).unwrap_or_default(),
+        color: row.get("color")?,
+        last_opened: row.get("last_opened")?,
+        notes: row.get("notes")?,
+        default_branch: row.get("default_branch")?,
+        worktree_of: None,
+    })
+}
+
 #[derive(Debug, Clone)]
 pub struct GitStore {
     path: PathBuf,
@@ -85,46 +111,39 @@ impl GitStore {
     }
 
     pub fn get_repos(&self) -> Result<Vec<store::Repository>, String> {
-        let sql = "select * from repository";
+        let sql = format!("select {} from repository", REPO_COLUMNS);
         let conn = conn_db(self.path.clone()).unwrap();
-        let mut stmt = conn.prepare(sql).unwrap();
-        let repos = stmt
-            .query_map([], |row| {
-                Ok(Repository {
-                    id: row.get(0)?,
-                    path: row.get(1)?,
-                    alias: row.get(2)?,
-                    has_watch: row.get(3)?,
-                    order: row.get(4)?,
-                    top: row.get(5)?,
-                })
-            })
-            .unwrap();
+        let mut stmt = conn.prepare(&sql).unwrap();
+        let repos = stmt.query_map([], repo_from_row).unwrap();
         let mut result = Vec::new();
         for repo in repos {
-            result.push(repo.unwrap());
+            let mut repo = repo.unwrap();
+            repo.worktree_of = worktree_of(&repo.path);
+            result.push(repo);
         }
-        Ok(result)
+        Ok(group_worktrees(result))
+    }
+
+    pub fn get_repo(&self, id: i32) -> Option<store::Repository> {
+        let sql = format!("select {} from repository where id = ?1", REPO_COLUMNS);
+        let conn = conn_db(self.path.clone()).unwrap();
+        let mut repo = conn.query_row(&sql, [id], repo_from_row).ok()?;
+        repo.worktree_of = worktree_of(&repo.path);
+        Some(repo)
     }
 
     pub fn get_repo_by_path(&self, path: String) -> Option<store::Repository> {
+        let sql = format!("select {} from repository where path = ?1", REPO_COLUMNS);
         let conn = conn_db(self.path.clone()).unwrap();
-        conn.query_row("SELECT * FROM repository where path = ?", [path], |row| {
-            Ok(Repository {
-                id: row.get(0)?,
-                path: row.get(1)?,
-                alias: row.get(2)?,
-                has_watch: row.get(3)?,
-                order: row.get(4)?,
-                top: row.get(5)?,
-            })
-        })
-        .ok()
+        let mut repo = conn.query_row(&sql, [&path], repo_from_row).ok()?;
+        repo.worktree_of = worktree_of(&path);
+        Some(repo)
     }
 
     pub fn update_repo(&self, repo: store::Repository) -> Result<(), String> {
-        let sql =
-            "update repository set path=?1, alias=?2, has_watch=?3, `order`=?4, top=?5 where id=?6";
+        let sql = "update repository set path=?1, alias=?2, has_watch=?3, `order`=?4, top=?5, \
+            folder=?6, tags=?7, color=?8, last_opened=?9, notes=?10, default_branch=?11 where id=?12";
+        let tags = serde_json::to_string(&repo.tags).map_err(|e| e.to_string())?;
         let conn = conn_db(self.path.clone()).unwrap();
         let stmt = conn.execute(
             sql,
@@ -134,10 +153,17 @@ impl GitStore {
                 repo.has_watch,
                 repo.order,
                 repo.top,
+                repo.folder,
+                tags,
+                repo.color,
+                repo.last_opened,
+                repo.notes,
+                repo.default_branch,
                 repo.id
             ],
         );
         match stmt {
+            Ok(0) => Err(format!("repo not found: {}", repo.id)),
             Ok(_) => {
                 log::info!("update repo success: {}", repo.path);
                 Ok(())
@@ -149,8 +175,32 @@ impl GitStore {
         }
     }
 
+    pub fn rename_repo(&self, id: i32, alias: &str) -> Result<(), String> {
+        let sql = "update repository set alias=?1 where id=?2";
+        let conn = conn_db(self.path.clone()).unwrap();
+        match conn.execute(sql, params![alias, id]) {
+            Ok(0) => Err(format!("repo not found: {}", id)),
+            Ok(_) => Ok(()),
+            Err(e) => {
+                log::error!("rename repo error: {:?}", e);
+                Err(e.to_string())
+            }
+        }
+    }
+
+    /// 记录仓库最后一次打开的时间
+    pub fn touch_repo(&self, path: &RepoPath) -> Result<(), String> {
+        let sql = "update repository set last_opened=?1 where path=?2";
+        let conn = conn_db(self.path.clone()).unwrap();
+        conn.execute(sql, params![chrono::Local::now().timestamp(), path])
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
     pub fn delete_repo(&self, path: &RepoPath) -> Result<(), String> {
         let sql = "delete from repository where path=?1";
+        // 组中的成员通过外键同时删除
+        WORKTREE_OF.lock().remove(path);
         let conn = conn_db(self.path.clone()).unwrap();
         let stmt = conn.execute(sql, [path]);
         match stmt {
@@ -164,4 +214,332 @@ impl GitStore {
             }
         }
     }
+
+    pub fn has_repo(&self, id: i32) -> Result<bool, String> {
+        let conn = conn_db(self.path.clone()).unwrap();
+        conn.query_row(
+            "select exists(select 1 from repository where id=?1)",
+            [id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn has_group(&self, id: i32) -> Result<bool, String> {
+        let conn = conn_db(self.path.clone()).unwrap();
+        conn.query_row(
+            "select exists(select 1 from repo_group where id=?1)",
+            [id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    /// 所有的仓库组，按 order 排列
+    pub fn get_groups(&self) -> Result<Vec<RepoGroup>, String> {
+        let conn = conn_db(self.path.clone()).unwrap();
+        let mut stmt = conn
+            .prepare("select id, name, `order` from repo_group order by `order`, id")
+            .map_err(|e| e.to_string())?;
+        let groups = stmt
+            .query_map([], |row| {
+                Ok(RepoGroup {
+                    id: row.get("id")?,
+                    name: row.get("name")?,
+                    order: row.get("order")?,
+                    repo_ids: vec![],
+                })
+            })
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| e.to_string())?;
+        let mut stmt = conn
+            .prepare("select repo_id from repo_group_member where group_id=?1 order by `order`")
+            .map_err(|e| e.to_string())?;
+        let mut result = Vec::with_capacity(groups.len());
+        for mut group in groups {
+            group.repo_ids = stmt
+                .query_map([group.id], |row| row.get(0))
+                .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+                .map_err(|e| e.to_string())?;
+            result.push(group);
+        }
+        Ok(result)
+    }
+
+    /// 添加仓库组，新的组排在最后
+    pub fn add_group(&self, name: &str) -> Result<RepoGroup, String> {
+        let conn = conn_db(self.path.clone()).unwrap();
+        let order: i32 = conn
+            .query_row(
+                "select coalesce(max(`order`), -1) + 1 from repo_group",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        conn.execute(
+            "insert into repo_group (name, `order`) values (?1, ?2)",
+            params![name, order],
+        )
+        .map_err(|e| e.to_string())?;
+        Ok(RepoGroup {
+            id: conn.last_insert_rowid() as i32,
+            name: name.to_string(),
+            order,
+            repo_ids: vec![],
+        })
+    }
+
+    pub fn rename_group(&self, id: i32, name: &str) -> Result<(), String> {
+        let conn = conn_db(self.path.clone()).unwrap();
+        match conn.execute(
+            "update repo_group set name=?1 where id=?2",
+            params![name, id],
+        ) {
+            Ok(0) => Err(format!("group not found: {}", id)),
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// 删除仓库组，组中的仓库不会被删除，组中的成员通过外键同时删除
+    pub fn delete_group(&self, id: i32) -> Result<(), String> {
+        let conn = conn_db(self.path.clone()).unwrap();
+        conn.execute("delete from repo_group where id=?1", [id])
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    /// 按 ids 的顺序排列仓库组
+    pub fn reorder_groups(&self, ids: &[i32]) -> Result<(), String> {
+        let mut conn = conn_db(self.path.clone()).unwrap();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        for (order, id) in ids.iter().enumerate() {
+            tx.execute(
+                "update repo_group set `order`=?1 where id=?2",
+                params![order as i32, id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        tx.commit().map_err(|e| e.to_string())
+    }
+
+    /// 设置组中的仓库，按 repo_ids 的顺序排列
+    pub fn set_group_repos(&self, group_id: i32, repo_ids: &[i32]) -> Result<(), String> {
+        let mut conn = conn_db(self.path.clone()).unwrap();
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        tx.execute(
+            "delete from repo_group_member where group_id=?1",
+            [group_id],
+        )
+        .map_err(|e| e.to_string())?;
+        for (order, repo_id) in repo_ids.iter().enumerate() {
+            tx.execute(
+                "insert or ignore into repo_group_member (group_id, repo_id, `order`) values (?1, ?2, ?3)",
+                params![group_id, repo_id, order as i32],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        tx.commit().map_err(|e| e.to_string())
+    }
+
+    /// 把仓库添加到组的最后，已经在组中时不做修改
+    pub fn add_repo_to_group(&self, group_id: i32, repo_id: i32) -> Result<(), String> {
+        let conn = conn_db(self.path.clone()).unwrap();
+        conn.execute(
+            "insert or ignore into repo_group_member (group_id, repo_id, `order`) \
+            select ?1, ?2, coalesce(max(`order`), -1) + 1 from repo_group_member where group_id=?1",
+            params![group_id, repo_id],
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn remove_repo_from_group(&self, group_id: i32, repo_id: i32) -> Result<(), String> {
+        let conn = conn_db(self.path.clone()).unwrap();
+        conn.execute(
+            "delete from repo_group_member where group_id=?1 and repo_id=?2",
+            params![group_id, repo_id],
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    /// 组中的仓库，按组内的顺序排列
+    pub fn group_repos(&self, group_id: i32) -> Result<Vec<store::Repository>, String> {
+        let columns = REPO_COLUMNS
+            .split(", ")
+            .map(|c| format!("r.{}", c.trim()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "select {} from repository r join repo_group_member m on m.repo_id = r.id \
+            where m.group_id = ?1 order by m.`order`",
+            columns
+        );
+        let conn = conn_db(self.path.clone()).unwrap();
+        let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+        let repos = stmt
+            .query_map([group_id], repo_from_row)
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| e.to_string())?;
+        Ok(repos
+            .into_iter()
+            .map(|mut repo| {
+                repo.worktree_of = worktree_of(&repo.path);
+                repo
+            })
+            .collect())
+    }
+}
+
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/").trim_end_matches('/').to_string()
+}
+
+/// 仓库路径对应的主工作树目录，获取仓库列表时不需要每次都打开所有的仓库
+static WORKTREE_OF: Lazy<Mutex<HashMap<String, Option<String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 仓库是链接的工作树时返回主工作树的目录
+fn worktree_of(path: &str) -> Option<String> {
+    if let Some(main) = WORKTREE_OF.lock().get(path) {
+        return main.clone();
+    }
+    // 打不开的仓库不缓存，仓库恢复后可以重新获取
+    let provider = GitDataProvider::new(path).ok()?;
+    let main = provider
+        .main_worktree()
+        .map(|main| normalize_path(&main.to_string_lossy()));
+    WORKTREE_OF.lock().insert(path.to_string(), main.clone());
+    main
+}
+
+/// 把链接的工作树排在它所属的仓库后面，所属仓库不在列表中的工作树排在最后
+fn group_worktrees(repos: Vec<Repository>) -> Vec<Repository> {
+    let (mut worktrees, repos): (Vec<_>, Vec<_>) =
+        repos.into_iter().partition(|r| r.worktree_of.is_some());
+    let mut grouped = Vec::with_capacity(repos.len() + worktrees.len());
+    for repo in repos {
+        let path = normalize_path(&repo.path);
+        grouped.push(repo);
+        let (children, rest): (Vec<_>, Vec<_>) = worktrees
+            .into_iter()
+            .partition(|w| w.worktree_of.as_deref() == Some(path.as_str()));
+        grouped.extend(children);
+        worktrees = rest;
+    }
+    grouped.extend(worktrees);
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::migration::{migrate, STORE_MIGRATIONS};
+
+    /// 每个测试使用单独的临时数据库
+    fn store(name: &str) -> GitStore {
+        let path =
+            std::env::temp_dir().join(format!("giter-store-{}-{}.db", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        let mut conn = conn_db(path.clone()).unwrap();
+        migrate(&mut conn, STORE_MIGRATIONS).unwrap();
+        GitStore { path }
+    }
+
+    fn member_count(store: &GitStore) -> i32 {
+        let conn = conn_db(store.path.clone()).unwrap();
+        conn.query_row("select count(*) from repo_group_member", [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_group_crud() {
+        let store = store("group-crud");
+        let a = store.add_group("a").unwrap();
+        let b = store.add_group("b").unwrap();
+        assert_eq!((a.order, b.order), (0, 1));
+        assert!(store.add_group("a").is_err());
+
+        store.rename_group(a.id, "c").unwrap();
+        assert!(store.rename_group(b.id + 1, "d").is_err());
+        store.reorder_groups(&[b.id, a.id]).unwrap();
+        let groups = store.get_groups().unwrap();
+        let names = groups.iter().map(|g| g.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["b", "c"]);
+
+        store.delete_group(b.id).unwrap();
+        assert!(!store.has_group(b.id).unwrap());
+        assert!(store.has_group(a.id).unwrap());
+        let _ = std::fs::remove_file(&store.path);
+    }
+
+    #[test]
+    fn test_group_repos() {
+        let store = store("group-repos");
+        let group = store.add_group("group").unwrap();
+        let r1 = store
+            .insert_repo("/repo1", "repo1", true, 0, false)
+            .unwrap();
+        let r2 = store
+            .insert_repo("/repo2", "repo2", true, 1, false)
+            .unwrap();
+        let r3 = store
+            .insert_repo("/repo3", "repo3", true, 2, false)
+            .unwrap();
+
+        store.set_group_repos(group.id, &[r2.id, r1.id]).unwrap();
+        // 添加到最后，已经在组中时不改变顺序
+        store.add_repo_to_group(group.id, r3.id).unwrap();
+        store.add_repo_to_group(group.id, r2.id).unwrap();
+        let paths = store
+            .group_repos(group.id)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.path)
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["/repo2", "/repo1", "/repo3"]);
+
+        store.remove_repo_from_group(group.id, r1.id).unwrap();
+        let groups = store.get_groups().unwrap();
+        assert_eq!(groups[0].repo_ids, [r2.id, r3.id]);
+        let _ = std::fs::remove_file(&store.path);
+    }
+
+    #[test]
+    fn test_group_members_deleted_with_repo_and_group() {
+        let store = store("group-cascade");
+        let a = store.add_group("a").unwrap();
+        let b = store.add_group("b").unwrap();
+        let r1 = store
+            .insert_repo("/repo1", "repo1", true, 0, false)
+            .unwrap();
+        let r2 = store
+            .insert_repo("/repo2", "repo2", true, 1, false)
+            .unwrap();
+        store.set_group_repos(a.id, &[r1.id, r2.id]).unwrap();
+        store.set_group_repos(b.id, &[r1.id]).unwrap();
+        assert_eq!(member_count(&store), 3);
+
+        store.delete_repo(&"/repo1".to_string()).unwrap();
+        assert_eq!(member_count(&store), 1);
+        assert!(!store.has_repo(r1.id).unwrap());
+        store.delete_group(a.id).unwrap();
+        assert_eq!(member_count(&store), 0);
+        let _ = std::fs::remove_file(&store.path);
+    }
+
+    #[test]
+    fn test_group_member_requires_group_and_repo() {
+        let store = store("group-foreign-key");
+        let group = store.add_group("group").unwrap();
+        let repo = store.insert_repo("/repo", "repo", true, 0, false).unwrap();
+        assert!(store.add_repo_to_group(group.id, repo.id + 1).is_err());
+        assert!(store.add_repo_to_group(group.id + 1, repo.id).is_err());
+        assert!(store.set_group_repos(group.id, &[repo.id + 1]).is_err());
+        assert_eq!(member_count(&store), 0);
+        let _ = std::fs::remove_file(&store.path);
+    }
 }
@@ -1,7 +1,8 @@
 use crate::core::handle;
 use crate::SingleRepoSubmit;
 use giter_utils::types::{
-    contribution::CommitStatistic, git_data_provider::GitDataProvider, status::WorkStatus,
+    contribution::CommitStatistic, fetch::TransferProgress, git_data_provider::GitDataProvider,
+    pickaxe::PickaxeMatch, scan::ScannedRepo, status::WorkStatus,
 };
 use giter_watcher::modify_watcher::ModifyWatcher;
 use notify::Event;
@@ -53,20 +54,24 @@ pub fn satatus_change_emit(event: Arc<Event>) {
         }
         let status = provider.unwrap().work_status();
         if let Ok(status) = status {
-            app.emit(
-                "giter://status_changed",
-                Status {
-                    path: path.display().to_string(),
-                    status,
-                },
-            )
-            .expect("TODO: panic message");
+            emit_status_changed(&app, &path.display().to_string(), status);
         } else {
             log::error!("status: {:?}", status);
         }
     }
 }
 
+/// 发送仓库状态变化事件
+pub fn emit_status_changed(app: &AppHandle, path: &str, status: WorkStatus) {
+    let status = Status {
+        path: path.to_string(),
+        status,
+    };
+    if let Err(e) = app.emit("giter://status_changed", status) {
+        log::error!("emit status changed {} error: {}", path, e);
+    }
+}
+
 pub fn changed_emit(event: Arc<Event>) {
     let app = handle::Handle::global().app_handle();
     if let None = app {
@@ -123,3 +128,21 @@ pub fn emit_branch_contribution(key: &str, value: anyhow::Result<Vec<CommitStati
         );
     }
 }
+
+/// 发送远程操作的传输进度
+pub fn emit_transfer_progress(task_id: &str, progress: TransferProgress) {
+    let app = handle::Handle::global().app_handle().unwrap();
+    let _ = app.emit(&format!("giter://transfer_progress/{}", task_id), progress);
+}
+
+/// 发送扫描到的仓库
+pub fn emit_scanned_repo(task_id: &str, repo: &ScannedRepo) {
+    let app = handle::Handle::global().app_handle().unwrap();
+    let _ = app.emit(&format!("giter://scanned_repo/{}", task_id), repo);
+}
+
+/// 发送内容搜索找到的提交
+pub fn emit_pickaxe_match(task_id: &str, found: &PickaxeMatch) {
+    let app = handle::Handle::global().app_handle().unwrap();
+    let _ = app.emit(&format!("giter://pickaxe_match/{}", task_id), found);
+}
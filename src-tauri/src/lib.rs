@@ -7,21 +7,36 @@ mod utils;
 use std::collections::HashMap;
 
 use crate::{
-    cmd::{get_commit_tree_recursive, get_repo_by_path, get_tree, object_is_binary, save_blob, scan_repos_folder},
+    cmd::{
+        get_commit_tree_recursive, get_repo_by_path, get_tree, object_is_binary, save_blob,
+        scan_repos, scan_repos_folder,
+    },
     utils::resolve,
 };
 use cmd::{
-    add_to_stage, add_watch, authors, before_reference_commits_count, blob_content, branch_commits,
-    branches, checkout_file, commit, commit_content, create_window, current_branch,
-    current_remote_branch, file_diff, file_history, get_branch_commit_contribution,
-    get_changed_files, get_commit, get_db_path, get_driver, get_folders, get_global_author,
-    get_repo_author, get_separator, get_staged_files, is_repo, pull, push,
-    reference_commit_filter_count, reference_commit_filter_details, remove_from_stage,
-    remove_watch, repos, set_repo_ownership, switch_branch, work_status,
+    add_group, add_remote, add_repo, add_repo_to_group, add_to_stage, add_watch, add_worktree,
+    authors, before_reference_commits_count, blob_content, branch_commits, branches,
+    cancel_remote_task, checkout_file, clone_repo, commit, commit_content, commit_graph,
+    create_window, credential_hosts, current_branch, current_remote_branch, delete_group,
+    delete_repo, fetch, fetch_group, file_diff, file_history, find_files,
+    get_branch_commit_contribution, get_changed_files, get_commit, get_db_path, get_driver,
+    get_folders, get_global_author, get_repo_author, get_separator, get_staged_files,
+    gitignore_templates, grep, group_repos, group_status, groups, init_repo, init_submodule,
+    is_repo, list_tree, lock_worktree, open_submodule, pickaxe, prune_worktrees, pull, pull_group,
+    push, reference_commit_filter_count, reference_commit_filter_details, remotes,
+    remove_credential, remove_from_stage, remove_remote, remove_repo_from_group, remove_watch,
+    rename_group, rename_remote, rename_repo, reorder_groups, repos, repos_summary,
+    reset_credentials, save_credential, search_commits, set_group_repos, set_remote_url,
+    set_repo_ownership, submodules, switch_branch, sync_submodule, touch_repo, tree_entry_details,
+    tree_last_commits, tree_paths, unlock_worktree, update_repo, update_submodule, verify_commit,
+    work_status, worktrees, write_commit_graph,
 };
+use giter_utils::types::fetch::CancelToken;
 use parking_lot::RwLock;
 use types::cache::RepoPath;
 pub struct SingleRepoSubmit(RwLock<HashMap<String, i32>>);
+/// 正在进行的远程操作(fetch等)，key为前端传入的任务id
+pub struct RemoteTasks(RwLock<HashMap<String, CancelToken>>);
 
 #[tauri::command]
 fn repo_single_submit(repo: RepoPath, state: tauri::State<SingleRepoSubmit>) {
@@ -61,6 +76,7 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .manage(SingleRepoSubmit(RwLock::new(HashMap::new())))
+        .manage(RemoteTasks(RwLock::new(HashMap::new())))
         .plugin(prevent_default())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_store::Builder::new().build())
@@ -77,6 +93,34 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             add_watch,
             repos,
+            add_repo,
+            update_repo,
+            rename_repo,
+            delete_repo,
+            touch_repo,
+            groups,
+            add_group,
+            rename_group,
+            delete_group,
+            reorder_groups,
+            set_group_repos,
+            add_repo_to_group,
+            remove_repo_from_group,
+            group_repos,
+            fetch_group,
+            group_status,
+            pull_group,
+            repos_summary,
+            search_commits,
+            pickaxe,
+            grep,
+            list_tree,
+            tree_entry_details,
+            tree_paths,
+            find_files,
+            tree_last_commits,
+            commit_graph,
+            write_commit_graph,
             authors,
             branches,
             get_db_path,
@@ -96,6 +140,7 @@ pub fn run() {
             file_diff,
             blob_content,
             get_commit,
+            verify_commit,
             get_branch_commit_contribution,
             get_global_author,
             get_repo_author,
@@ -109,6 +154,30 @@ pub fn run() {
             current_remote_branch,
             push,
             pull,
+            fetch,
+            clone_repo,
+            init_repo,
+            gitignore_templates,
+            cancel_remote_task,
+            save_credential,
+            remove_credential,
+            credential_hosts,
+            reset_credentials,
+            remotes,
+            add_remote,
+            rename_remote,
+            remove_remote,
+            set_remote_url,
+            submodules,
+            init_submodule,
+            sync_submodule,
+            update_submodule,
+            open_submodule,
+            worktrees,
+            add_worktree,
+            lock_worktree,
+            unlock_worktree,
+            prune_worktrees,
             switch_branch,
             repo_single_submit,
             repo_single_unsubmit,
@@ -119,7 +188,8 @@ pub fn run() {
             object_is_binary,
             get_repo_by_path,
             save_blob,
-            scan_repos_folder
+            scan_repos_folder,
+            scan_repos
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
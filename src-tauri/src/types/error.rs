@@ -44,12 +44,13 @@ where
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct("CommandError", 5)?;
+        let mut state = serializer.serialize_struct("CommandError", 6)?;
         state.serialize_field("code", &self.error.as_ref().unwrap().code())?;
         state.serialize_field("message", &self.error.as_ref().unwrap().to_string())?;
         state.serialize_field("func", &self.func)?;
         state.serialize_field("module", &self.error.as_ref().unwrap().module())?;
         state.serialize_field("etype", &self.etype)?;
+        state.serialize_field("data", &self.error.as_ref().unwrap().data())?;
         state.end()
     }
 }
@@ -69,6 +70,16 @@ pub enum CommonErrorCode {
     SetGlobalConfigError(String),
     #[error("Get global config error: {0}")]
     GetGlobalConfigError(String),
+    #[error("Credential store error: {0}")]
+    CredentialStoreError(String),
+    #[error("Repository not found in store: {0}")]
+    RepoNotFound(String),
+    #[error("Store error: {0}")]
+    StoreError(String),
+    #[error("Repository group not found: {0}")]
+    GroupNotFound(String),
+    #[error("Background task failed: {0}")]
+    TaskFailed(String),
 }
 
 impl ExposeError for CommonErrorCode {
@@ -80,3 +91,20 @@ impl ExposeError for CommonErrorCode {
         return "giter";
     }
 }
+
+/// 后台任务没有正常结束(panic)时转换为命令返回的错误
+pub trait FromTaskFailure {
+    fn task_failed(message: String) -> Self;
+}
+
+impl FromTaskFailure for CommonErrorCode {
+    fn task_failed(message: String) -> Self {
+        CommonErrorCode::TaskFailed(message)
+    }
+}
+
+impl FromTaskFailure for giter_utils::types::error::GitUtilsErrorCode {
+    fn task_failed(message: String) -> Self {
+        giter_utils::types::error::GitUtilsErrorCode::OtherError(message)
+    }
+}
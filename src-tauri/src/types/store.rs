@@ -1,6 +1,15 @@
 use serde::{Deserialize, Serialize};
 
+/// 保存在 store.db 中的仓库
+///
+/// - folder: 仓库所在的分组文件夹
+/// - tags: 仓库的标签
+/// - color: 仓库的标记颜色
+/// - last_opened: 最后一次打开的时间戳(秒)
+/// - default_branch: 打开仓库时默认显示的分支
+/// - worktree_of: 仓库是链接的工作树时为主工作树的目录，不保存在数据库中
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Repository {
     pub id: i32,
     pub path: String,
@@ -8,6 +17,20 @@ pub struct Repository {
     pub has_watch: bool,
     pub order: i32,
     pub top: bool,
+    #[serde(default)]
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub last_opened: Option<i64>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    #[serde(default)]
+    pub worktree_of: Option<String>,
 }
 
 impl Repository {
@@ -26,6 +49,23 @@ impl Repository {
             has_watch,
             order,
             top,
+            folder: None,
+            tags: vec![],
+            color: None,
+            last_opened: None,
+            notes: None,
+            default_branch: None,
+            worktree_of: None,
         }
     }
 }
+
+/// 仓库组，repo_ids 按组内的顺序排列
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoGroup {
+    pub id: i32,
+    pub name: String,
+    pub order: i32,
+    pub repo_ids: Vec<i32>,
+}
@@ -0,0 +1,16 @@
+use rusqlite::OptionalExtension;
+
+use crate::utils::{db::conn_db, dirs};
+
+/// 读取 config.db 中的配置，不存在或读取失败时返回None
+pub fn get_config_value(key: &str) -> Option<String> {
+    let conn = conn_db(dirs::config_file().ok()?).ok()?;
+    conn.query_row("select value from config where key = ?1", [key], |row| {
+        row.get::<_, String>(0)
+    })
+    .optional()
+    .unwrap_or_else(|e| {
+        log::error!("get config {} error: {:?}", key, e);
+        None
+    })
+}
@@ -2,6 +2,9 @@ use anyhow::Result;
 use rusqlite::Connection;
 use std::path::PathBuf;
 
+/// 打开数据库，sqlite 默认不检查外键，每个连接都需要开启
 pub fn conn_db(path: PathBuf) -> Result<Connection> {
-    Ok(Connection::open(path)?)
+    let conn = Connection::open(path)?;
+    conn.pragma_update(None, "foreign_keys", true)?;
+    Ok(conn)
 }
@@ -34,6 +34,27 @@ pub fn config_file() -> Result<PathBuf> {
     Ok(database_dir()?.join("config.db"))
 }
 
+pub fn credential_file() -> Result<PathBuf> {
+    Ok(database_dir()?.join("credentials.bin"))
+}
+
+/// 凭证的密钥保存在本地(不漫游)的应用数据目录中，与凭证文件分开
+pub fn credential_key_file() -> Result<PathBuf> {
+    let app_handle = handle::Handle::global().app_handle().unwrap();
+    match app_handle.path().app_local_data_dir() {
+        Ok(dir) => Ok(dir.join("keys").join("credential.key")),
+        Err(e) => {
+            log::error!(target: "app", "Failed to the app local data directory: {}", e);
+            Err(anyhow::anyhow!("Failed to the app local data directory"))
+        }
+    }
+}
+
+/// 旧版本保存密钥的位置
+pub fn legacy_credential_key_file() -> Result<PathBuf> {
+    Ok(app_home_dir()?.join("credential.key"))
+}
+
 pub fn app_logs_dir() -> Result<PathBuf> {
     Ok(app_home_dir()?.join("logs"))
 }
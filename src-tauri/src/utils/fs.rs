@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use git2::Repository;
@@ -76,48 +76,17 @@ pub fn is_submodule(parent_path: &str, submodule_path: &str) -> bool {
         Err(_) => return false,
     };
 
-    // 检查是否有 .gitmodules 文件
-    let workdir = match parent_repo.workdir() {
-        Some(dir) => dir,
-        None => return false,
-    };
-
-    let gitmodules_path = workdir.join(".gitmodules");
-    if !gitmodules_path.exists() {
-        return false;
-    }
-
-    // 读取并解析 .gitmodules 文件
-    let content = match std::fs::read_to_string(&gitmodules_path) {
-        Ok(content) => content,
+    // 子模块路径必须在父仓库中，取相对父仓库的路径
+    let relative_path = match Path::new(submodule_path).strip_prefix(parent_path) {
+        Ok(path) => path,
         Err(_) => return false,
     };
 
-    // 检查子模块路径是否在 .gitmodules 中
-    let submodule_normalized = submodule_path.replace("\\", "/");
-    let parent_normalized = parent_path.replace("\\", "/");
-
-    // 如果 submodule_path 是 parent_path 的子目录，提取相对路径
-    let relative_path = if submodule_normalized.starts_with(&parent_normalized) {
-        let rest = &submodule_normalized[parent_normalized.len()..];
-        // 移除开头的斜杠
-        if rest.starts_with('/') {
-            rest[1..].to_string()
-        } else {
-            rest.to_string()
-        }
-    } else {
-        return false;
-    };
-
-    // 在 .gitmodules 中查找路径
-    content
-        .lines()
-        .filter(|line| line.starts_with("path = "))
-        .any(|line| {
-            let path = line.trim_start_matches("path = ").trim();
-            path == &relative_path || path == &submodule_normalized
-        })
+    // 由 libgit2 解析 .gitmodules，比较子模块的路径
+    parent_repo
+        .submodules()
+        .map(|submodules| submodules.iter().any(|s| s.path() == relative_path))
+        .unwrap_or(false)
 }
 
 /// 扫描指定文件夹下的所有 Git 仓库，不包括子模块
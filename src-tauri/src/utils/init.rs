@@ -1,4 +1,4 @@
-use crate::utils::dirs;
+use crate::utils::{dirs, migration};
 use anyhow::Result;
 use chrono::Local;
 use log::LevelFilter;
@@ -54,27 +54,8 @@ pub fn init_store() -> Result<()> {
     }
     let store_path = database_path.join("store.db");
     println!("{:?}", store_path);
-    let conn = Connection::open(store_path)?;
-    conn.execute(
-        "
-    create table if not exists repository (
-        id integer primary key autoincrement,
-        path text not null unique,
-        alias text default NULL,
-        has_watch integer default 1,
-        `order` integer default NULL,
-        top integer default 0,
-        trusted integer default 0,
-        to_trusted integer default 0
-    )
-    ",
-        (),
-    )
-    .unwrap_or_else(|e| {
-        println!("{}", e);
-        1
-    });
-    Ok(())
+    let mut conn = Connection::open(store_path)?;
+    migration::migrate(&mut conn, migration::STORE_MIGRATIONS)
 }
 
 pub fn init_config() -> Result<()> {
@@ -84,20 +65,6 @@ pub fn init_config() -> Result<()> {
     }
     let config_path = database_path.join("config.db");
     println!("{:?}", config_path);
-    let conn = Connection::open(config_path)?;
-    conn.execute(
-        "
-    create table if not exists config (
-        id integer primary key autoincrement,
-        key varchar(255) not null unique,
-        value text not null
-    )
-    ",
-        (),
-    )
-    .unwrap_or_else(|e| {
-        println!("{}", e);
-        1
-    });
-    Ok(())
+    let mut conn = Connection::open(config_path)?;
+    migration::migrate(&mut conn, migration::CONFIG_MIGRATIONS)
 }
@@ -0,0 +1,137 @@
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+
+/// store.db 的迁移，第 n 个元素把数据库从版本 n 升级到 n + 1
+///
+/// 已经发布的迁移不能修改，只能在末尾追加新的迁移
+pub const STORE_MIGRATIONS: &[&str] = &[
+    // 1: 仓库表，trusted 和 to_trusted 已经不再使用，保留以兼容旧的数据库
+    "
+    create table if not exists repository (
+        id integer primary key autoincrement,
+        path text not null unique,
+        alias text default NULL,
+        has_watch integer default 1,
+        `order` integer default NULL,
+        top integer default 0,
+        trusted integer default 0,
+        to_trusted integer default 0
+    );
+    ",
+    // 2: 仓库的扩展信息，tags 是 JSON 数组
+    "
+    alter table repository add column folder text default NULL;
+    alter table repository add column tags text not null default '[]';
+    alter table repository add column color text default NULL;
+    alter table repository add column last_opened integer default NULL;
+    alter table repository add column notes text default NULL;
+    alter table repository add column default_branch text default NULL;
+    ",
+    // 3: 仓库组，一个仓库可以属于多个组，order 是组内仓库的顺序，
+    // 删除组或者仓库时同时删除组中的成员
+    "
+    create table if not exists repo_group (
+        id integer primary key autoincrement,
+        name text not null unique,
+        `order` integer not null default 0
+    );
+    create table if not exists repo_group_member (
+        group_id integer not null references repo_group(id) on delete cascade,
+        repo_id integer not null references repository(id) on delete cascade,
+        `order` integer not null default 0,
+        primary key (group_id, repo_id)
+    );
+    ",
+];
+
+/// config.db 的迁移
+pub const CONFIG_MIGRATIONS: &[&str] = &[
+    // 1: 键值配置表
+    "
+    create table if not exists config (
+        id integer primary key autoincrement,
+        key varchar(255) not null unique,
+        value text not null
+    );
+    ",
+];
+
+/// 数据库当前的版本，保存在 user_version 中
+pub fn schema_version(conn: &Connection) -> Result<usize> {
+    let version: i64 = conn.query_row("pragma user_version", [], |row| row.get(0))?;
+    Ok(version as usize)
+}
+
+/// 执行还没有执行过的迁移，每个迁移在单独的事务中执行，
+/// 失败时回滚该迁移并返回错误，之前的迁移保留
+pub fn migrate(conn: &mut Connection, migrations: &[&str]) -> Result<()> {
+    let version = schema_version(conn)?;
+    if version > migrations.len() {
+        return Err(anyhow::anyhow!(
+            "database version {} is newer than the application ({})",
+            version,
+            migrations.len()
+        ));
+    }
+    for (i, sql) in migrations.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)
+            .with_context(|| format!("migrate database to version {}", i + 1))?;
+        tx.pragma_update(None, "user_version", (i + 1) as i64)?;
+        tx.commit()?;
+        log::info!("migrate database to version {}", i + 1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_store() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn, STORE_MIGRATIONS).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), STORE_MIGRATIONS.len());
+        // 再次执行不会重复迁移
+        migrate(&mut conn, STORE_MIGRATIONS).unwrap();
+        conn.execute("insert into repository (path) values ('/repo')", [])
+            .unwrap();
+        let tags: String = conn
+            .query_row("select tags from repository", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(tags, "[]");
+    }
+
+    #[test]
+    fn test_migrate_existing_database() {
+        // 没有版本号的旧数据库
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(STORE_MIGRATIONS[0]).unwrap();
+        conn.execute(
+            "insert into repository (path, alias) values ('/repo', 'repo')",
+            [],
+        )
+        .unwrap();
+        migrate(&mut conn, STORE_MIGRATIONS).unwrap();
+        let alias: String = conn
+            .query_row(
+                "select alias from repository where path = '/repo'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(alias, "repo");
+    }
+
+    #[test]
+    fn test_migrate_failed_rollback() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let migrations = [
+            "create table a (id integer);",
+            "create table a (id integer);",
+        ];
+        assert!(migrate(&mut conn, &migrations).is_err());
+        assert_eq!(schema_version(&conn).unwrap(), 1);
+    }
+}
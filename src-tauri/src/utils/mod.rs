@@ -1,5 +1,7 @@
+pub mod config;
 pub mod db;
 pub mod dirs;
 pub mod fs;
 mod init;
+pub mod migration;
 pub mod resolve;
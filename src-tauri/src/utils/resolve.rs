@@ -1,9 +1,12 @@
+use crate::core::credential_store::EncryptedCredentialStore;
+use crate::core::fetch_scheduler::FetchScheduler;
 use crate::emit::{repo_single_emit, satatus_change_emit};
 use crate::utils::init;
 use crate::{core::handle, emit::changed_emit};
 use anyhow::Result;
+use giter_utils::types::credential::set_credential_store;
 use giter_watcher::modify_watcher::ModifyWatcher;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use tauri::menu::{Menu, MenuItem};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 use tauri::{App, Manager};
@@ -17,6 +20,10 @@ pub async fn resolve_setup(app: &mut App) -> Result<()> {
     println!("init store success");
     init::init_config()?;
     println!("init conifg success");
+    // 远程操作时从加密保存的凭证中查找
+    let credential_store = Arc::new(EncryptedCredentialStore::new()?);
+    set_credential_store(credential_store.clone());
+    app.manage(credential_store);
     let mut watcher_center = ModifyWatcher::new();
     watcher_center.add_callback(satatus_change_emit);
     watcher_center.add_callback(changed_emit);
@@ -24,6 +31,8 @@ pub async fn resolve_setup(app: &mut App) -> Result<()> {
     let _ = watcher_center.init();
     // 修改监控器
     app.manage(Mutex::new(watcher_center));
+    // 定时拉取远程，更新仓库落后于远程的状态
+    FetchScheduler::start();
 
     Ok(())
 }
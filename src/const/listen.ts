@@ -8,4 +8,9 @@ export type StatusChangePayloadType = {
 // 获取分支的贡献度，key为键值，不允许重复，用于区分不同的监听事件，这个监听是一次性的
 export const BRANCH_COMMIT_CONTRIBUTION_KEY = (key: String) => `giter://branch_contribution/${key}`
 export const CHANGED_EMIT = 'giter://changed_emit'
-export const SINGLE_REPO_EMIT = 'giter://repo_single_emit'
\ No newline at end of file
+export const SINGLE_REPO_EMIT = 'giter://repo_single_emit'
+// 远程操作(fetch等)的传输进度，taskId为调用命令时传入的任务id
+export const TRANSFER_PROGRESS = (taskId: string) => `giter://transfer_progress/${taskId}`
+// 扫描仓库时发现的仓库，taskId为调用命令时传入的任务id
+export const SCANNED_REPO = (taskId: string) => `giter://scanned_repo/${taskId}`
+export const PICKAXE_MATCH = (taskId: string) => `giter://pickaxe_match/${taskId}`
@@ -34,9 +34,45 @@ export const GitUtilsErrorCode = {
   BuildMergeCommitError: 31,
   CommitBeforePullWouldBeOverwrittenByMerge: 32,
   CantPull: 33,
-  OtherError: 34,
-  Git2Error: 35,
-  AnyhowError: 36
+  NoCommitToAmend: 34,
+  SignCommitError: 35,
+  HookFailed: 36,
+  ConventionalCommitInvalid: 37,
+  FetchError: 38,
+  OperationCancelled: 39,
+  RemoteAuthFailed: 40,
+  HostKeyUnknown: 41,
+  HostKeyMismatch: 42,
+  NonFastForward: 43,
+  RemoteRefLocked: 44,
+  ProtectedBranch: 45,
+  PushRejected: 46,
+  NetworkUnreachable: 47,
+  RemoteTimeout: 48,
+  PushError: 49,
+  StaleRemoteRef: 50,
+  CannotFastForward: 51,
+  RebaseUncommittedChanges: 52,
+  AutostashConflict: 53,
+  InvalidRemoteName: 54,
+  RemoteAlreadyExists: 55,
+  CloneError: 56,
+  DestinationNotEmpty: 57,
+  RepoAlreadyExists: 58,
+  GitignoreTemplateNotFound: 59,
+  InvalidBranchName: 60,
+  SubmoduleNotFound: 61,
+  SubmoduleNotCheckedOut: 62,
+  WorktreeNotFound: 63,
+  WorktreeAlreadyExists: 64,
+  BranchAlreadyCheckedOut: 65,
+  DirectoryNotFound: 66,
+  InvalidPattern: 67,
+  InvalidCursor: 68,
+  CertificateInvalid: 69,
+  OtherError: 70,
+  Git2Error: 71,
+  AnyhowError: 72
 } as const
 
 export const WatcherErrorCode = {
@@ -51,7 +87,12 @@ export const CommonErrorCode = {
   DatabaseInvalid: 2,
   PathInvalid: 3,
   SetGlobalConfigError: 4,
-  GetGlobalConfigError: 5
+  GetGlobalConfigError: 5,
+  CredentialStoreError: 6,
+  RepoNotFound: 7,
+  StoreError: 8,
+  GroupNotFound: 9,
+  TaskFailed: 10
 } as const
 
 export type ErrorCode = typeof CommonErrorCode | typeof GitUtilsErrorCode | typeof WatcherErrorCode
\ No newline at end of file
@@ -11,6 +11,8 @@ export const RepoStatus = {
   Untracked: 1 << 3,
   Uncommitted: 1 << 4,
   Unpushed: 1 << 5,
+  Behind: 1 << 6,
+  Submodule: 1 << 7,
 } as const;
 
 export enum FileStatus {
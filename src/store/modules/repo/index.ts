@@ -1,10 +1,9 @@
 import { STATUS_CHANGE } from "@/const/listen";
 import { parseStatus, RepoStatus, SetupStoreId } from "@/enum";
 import { Repository } from "@/types";
-import { addWatch, isRepo, removeWatch, workStatus } from "@/utils/command";
+import { addWatch, isRepo, workStatus } from "@/utils/command";
 import { readRepos, removeRepo, saveRepo, updateRepo } from "@/utils/store";
 import { listen, UnlistenFn } from "@tauri-apps/api/event";
-import { QueryResult } from "@tauri-apps/plugin-sql";
 import { defineStore } from "pinia";
 import { ref, Ref } from "vue";
 
@@ -131,9 +130,8 @@ export const useRepoStore = defineStore(SetupStoreId.Repo, () => {
 
   const add = async (repo: Repository): Promise<void> => {
     try {
-      const res: QueryResult = await saveRepo(repo);
-      repo.id = res.lastInsertId as number;
-      const result = await commonRepoInit(repo);
+      const added = await saveRepo(repo);
+      const result = await commonRepoInit(added);
       repos.value = [...repos.value, result].sort(repoSort);
     } catch (err: unknown) {
       window.$message.error(`添加仓库失败: ${getErrorMessage(err)}`);
@@ -141,19 +139,20 @@ export const useRepoStore = defineStore(SetupStoreId.Repo, () => {
   };
 
   const update = async (repo: Repository): Promise<void> => {
-    await updateRepo(repo);
     const index = repos.value.findIndex((r) => r.id === repo.id);
+    // 后端更新所有字段，和列表中的仓库合并以免丢失 repo 中没有的字段
+    const merged = index === -1 ? repo : { ...repos.value[index], ...repo };
+    await updateRepo(merged);
     if (index === -1) return;
     repos.value = [
       ...repos.value.slice(0, index),
-      { ...repos.value[index], ...repo },
+      merged,
       ...repos.value.slice(index + 1),
     ].sort(repoSort);
   };
 
   const remove = async (repo: ValidRepository): Promise<void> => {
     await removeRepo(repo.id);
-    void removeWatch(repo.path);
     repos.value = repos.value.filter((r) => r.id !== repo.id);
   };
 
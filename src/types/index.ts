@@ -10,6 +10,199 @@ export type Repository = {
   hasWatch: boolean
   order: number
   top: boolean
+  folder?: string
+  tags?: string[]
+  color?: string
+  // 最后一次打开的时间戳(秒)
+  lastOpened?: number
+  notes?: string
+  defaultBranch?: string
+  // 仓库是链接的工作树时为主工作树的目录
+  worktreeOf?: string
+}
+
+// 仓库组，repoIds 按组内的顺序排列
+export type RepoGroup = {
+  id: number
+  name: string
+  order: number
+  repoIds: number[]
+}
+
+// 对组中单个仓库执行操作的结果，失败时 error 不为空
+export type RepoOperationResult = {
+  path: string
+  status?: number
+  error?: string
+}
+
+// 仓库中正在进行、还没有完成的操作
+export type InProgressOperation = 'merge' | 'rebase' | 'cherryPick' | 'revert' | 'bisect' | 'applyMailbox'
+
+// 仓库概览，ahead/behind 是相对跟踪分支的提交数
+export type RepoSummary = {
+  status: number
+  branch?: string
+  upstream?: string
+  ahead: number
+  behind: number
+  stashCount: number
+  lastCommit?: Commit
+  operation?: InProgressOperation
+}
+
+export type RepoSummaryResult = {
+  path: string
+  summary?: RepoSummary
+  error?: string
+}
+
+// 提交搜索条件，author 匹配作者名称或邮箱，时间为毫秒时间戳，branches 为空时搜索所有本地分支
+export type CommitQuery = {
+  message?: string
+  author?: string
+  startTime?: number
+  endTime?: number
+  path?: string
+  branches?: string[]
+}
+
+// errors 是搜索失败的 [仓库, 错误信息]
+export type CommitSearchPage = {
+  commits: Commit[]
+  hasMore: boolean
+  errors: [string, string][]
+}
+
+// occurrence 与 git log -S 相同，diff 与 git log -G 相同
+export type PickaxeMode = 'occurrence' | 'diff'
+
+export type PickaxeOptions = {
+  pattern: string
+  regex?: boolean
+  ignoreCase?: boolean
+  mode?: PickaxeMode
+  // 为空时从 HEAD 开始搜索
+  reference?: string
+  path?: string
+  // 上一页返回的 cursor，为空时从第一个提交开始
+  cursor?: string
+  count?: number
+}
+
+// origin 为 '+' 新增、'-' 删除或 ' ' 上下文
+export type PickaxeLine = {
+  origin: string
+  content: string
+  oldLineno?: number
+  newLineno?: number
+  matched: boolean
+}
+
+export type PickaxeHunk = {
+  path: string
+  header: string
+  lines: PickaxeLine[]
+}
+
+export type PickaxeMatch = {
+  commit: Commit
+  hunks: PickaxeHunk[]
+}
+
+// hasMore 表示历史还没有遍历完，下一页不一定有匹配的提交
+export type PickaxePage = {
+  matches: PickaxeMatch[]
+  hasMore: boolean
+  cursor?: string
+}
+
+// 'worktree' 工作区，'index' 暂存区，{ commit } 提交或者可以解析为树的引用
+export type GrepSource = 'worktree' | 'index' | { commit: string }
+
+// include/exclude 中不包含 '/' 的通配符匹配文件名，否则匹配相对仓库根目录的路径
+export type GrepOptions = {
+  pattern: string
+  regex?: boolean
+  ignoreCase?: boolean
+  word?: boolean
+  include?: string[]
+  exclude?: string[]
+  context?: number
+  maxResults?: number
+}
+
+export type GrepLine = {
+  lineNumber: number
+  content: string
+}
+
+// ranges 是匹配的内容在行中的字节范围
+export type GrepMatch = GrepLine & {
+  path: string
+  ranges: [number, number][]
+  before: GrepLine[]
+  after: GrepLine[]
+}
+
+// 上一行到这一行的线段，from 是上一行的列，to 是这一行的列
+export type GraphEdge = {
+  from: number
+  to: number
+  color: number
+}
+
+// 提交图中的一行，edges 是从上一行连接到这一行的线段，width 是占用的列数
+export type GraphRow = {
+  commit: Commit
+  column: number
+  color: number
+  edges: GraphEdge[]
+  width: number
+}
+
+// cursor 原样传给下一页，为空时没有更多的提交
+export type GraphPage = {
+  rows: GraphRow[]
+  cursor?: unknown
+}
+
+export type TreeEntryKind = 'file' | 'dir' | 'link' | 'submodule'
+
+// 目录中的一项，path 为相对仓库根目录的路径
+export type TreeEntry = {
+  name: string
+  path: string
+  kind: TreeEntryKind
+  mode: EntryMode
+  objectId: string
+}
+
+// cursor 原样传给下一页，为空时没有更多的项
+export type TreePage = {
+  entries: TreeEntry[]
+  cursor?: string
+}
+
+// size 为文件大小，目录和子模块为空
+export type TreeEntryDetail = {
+  path: string
+  size?: number
+  lastCommit?: Commit
+}
+
+// positions 是匹配的字符在路径中的字节位置
+export type FuzzyMatch = {
+  path: string
+  score: number
+  positions: number[]
+}
+
+export type GrepResult = {
+  matches: GrepMatch[]
+  filesSearched: number
+  // 匹配的行数达到了 maxResults
+  truncated: boolean
 }
 
 export type Error = {
@@ -26,6 +219,8 @@ export type Branch = {
  name: string,
  isRemote: boolean
  reference: string 
+ // 检出该本地分支的工作树目录
+ worktree?: string
 }
 
 export type Commit = {
@@ -38,9 +233,144 @@ export type Commit = {
   message: string,
   datetime: number,
   parents: string[],
-  repo: string
+  repo: string,
+  signatureStatus: SignatureStatus
+}
+
+/** 提交签名验证状态，与 git log 中 %G? 的含义一致 */
+export type SignatureStatus = 'None' | 'Unverified' | 'Good' | 'Bad' | 'UnknownValidity' | 'Expired' | 'ExpiredKey' | 'RevokedKey' | 'CannotCheck'
+
+
+export type CommitOptions = {
+  amend?: boolean,
+  author?: Author,
+  allowEmpty?: boolean,
+  onlyPaths?: string[],
+  sign?: boolean,
+  noVerify?: boolean
+}
+
+export type PushMode = 'Normal' | 'ForceWithLease' | 'Force'
+
+export type PushOptions = {
+  mode?: PushMode,
+  remoteBranch?: string,
+  setUpstream?: boolean,
+  noVerify?: boolean
+}
+
+export type CloneOptions = {
+  branch?: string,
+  depth?: number,
+  recurseSubmodules?: boolean,
+  bare?: boolean
+}
+
+export type InitOptions = {
+  initialBranch?: string,
+  gitignore?: string,
+  initialCommit?: string
+}
+
+export type Submodule = {
+  name: string,
+  path: string,
+  url?: string,
+  branch?: string,
+  recordedId?: string,
+  indexId?: string,
+  checkedOutId?: string,
+  initialized: boolean,
+  checkedOut: boolean,
+  dirty: boolean,
+  outOfDate: boolean
+}
+
+export type Worktree = {
+  name: string,
+  path: string,
+  branch?: string,
+  headId?: string,
+  isMain: boolean,
+  locked: boolean,
+  lockReason?: string,
+  prunable: boolean
+}
+
+export type AddWorktreeOptions = {
+  branch?: string,
+  newBranch?: boolean,
+  lock?: boolean
+}
+
+export type ScanOptions = {
+  maxDepth?: number,
+  exclude?: string[],
+  includeHidden?: boolean,
+  includeBare?: boolean
+}
+
+export type ScannedRepo = {
+  path: string,
+  bare: boolean,
+  branch?: string,
+  remoteUrl?: string,
+  lastCommitTime?: number
+}
+
+export type Remote = {
+  name: string,
+  url: string,
+  pushUrl?: string,
+  fetchRefspecs: string[],
+  pushRefspecs: string[]
 }
 
+export type PullStrategy = 'Merge' | 'Rebase' | 'FastForwardOnly'
+
+export type PullOptions = {
+  strategy?: PullStrategy,
+  autostash?: boolean
+}
+
+/** 钩子执行失败时随错误返回的输出 */
+export type HookOutput = {
+  hook: string,
+  code: number | null,
+  stdout: string,
+  stderr: string
+}
+
+export type FetchOptions = {
+  prune?: boolean,
+  tags?: boolean,
+  depth?: number
+}
+
+export type TransferProgress = {
+  remote: string,
+  totalObjects: number,
+  receivedObjects: number,
+  indexedObjects: number,
+  localObjects: number,
+  totalDeltas: number,
+  indexedDeltas: number,
+  receivedBytes: number,
+  done: boolean
+}
+
+export type Credential =
+  | { type: 'Token', data: string }
+  | { type: 'UsernamePassword', data: [string, string] }
+  | {
+    type: 'SshKey',
+    data: {
+      username?: string,
+      privateKey: string,
+      publicKey?: string,
+      passphrase?: string
+    }
+  }
 
 export type CommitEntry = {
   path: string
@@ -99,10 +429,21 @@ export type FileHistoryItem = {
   file: CommitEntry,
 }
 
+// 最后一次修改目录项的提交，datetime 为毫秒时间戳
+export type LastCommit = {
+  commitId: string
+  title: string
+  authorName: string
+  authorEmail: string
+  datetime: number
+}
+
 export type TreeEntryMetadata = {
   size: number,
   mode: EntryMode,
   object_id: string
+  // 按提交浏览时才有
+  last_commit?: LastCommit
 }
 
 export type TreeFile = {
@@ -1,6 +1,6 @@
-import { BRANCH_COMMIT_CONTRIBUTION_KEY, SINGLE_REPO_EMIT } from "@/const/listen";
+import { BRANCH_COMMIT_CONTRIBUTION_KEY, PICKAXE_MATCH, SCANNED_REPO, SINGLE_REPO_EMIT, TRANSFER_PROGRESS } from "@/const/listen";
 import { RepoStatus } from "@/enum";
-import { Author, Branch, Commit, CommitFilter, CommitStatistic, DiffContent, CommitEntry, ChangedFile, FileHistoryItem, TreeDir, Repository } from "@/types";
+import { Author, Branch, Commit, CommitFilter, CommitStatistic, DiffContent, CommitEntry, ChangedFile, FileHistoryItem, TreeDir, Repository, CommitOptions, FetchOptions, TransferProgress, Credential, PushOptions, PullOptions, Remote, CloneOptions, InitOptions, Submodule, Worktree, AddWorktreeOptions, ScanOptions, ScannedRepo, RepoGroup, RepoOperationResult, RepoSummaryResult, CommitQuery, CommitSearchPage, PickaxeOptions, PickaxeMatch, PickaxePage, GrepSource, GrepOptions, GrepResult, GraphPage, TreePage, TreeEntryDetail, FuzzyMatch, LastCommit, SignatureStatus } from "@/types";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 
@@ -131,6 +131,16 @@ export const getBranchCommits = (repo: RepoPath, branch: Branch, count: Number)
   return bus.invoke<Commit[]>('branch_commits', { repo, branch, count }) 
 }
 
+// 计算多个引用的提交图，cursor 为上一页返回的 cursor
+export const commitGraph = (repo: RepoPath, refs: string[], count: number, cursor?: unknown) => {
+  return bus.invoke<GraphPage>('commit_graph', { repo, refs, count, cursor })
+}
+
+// 生成或更新 commit-graph 文件，加快大仓库中提交的遍历和计数
+export const writeCommitGraph = (repo: RepoPath) => {
+  return bus.invoke<void>('write_commit_graph', { repo })
+}
+
 export const beforeReferenceCommitsCount = (repo: RepoPath, reference: string) => {
   return bus.invoke<number>('before_reference_commits_count', { repo, reference }) 
 }
@@ -155,6 +165,11 @@ export const getCommit = (repo: RepoPath, cid: string) => {
   return bus.invoke<Commit>('get_commit', { repo, cid})
 }
 
+/** 验证提交的签名，提交列表中有签名的提交为 Unverified */
+export const verifyCommit = (repo: RepoPath, cid: string) => {
+  return bus.invoke<SignatureStatus>('verify_commit', { repo, cid })
+}
+
 export const fileDiff = (repo: RepoPath, old_id: string, new_id: string) => {
   return bus.invoke<DiffContent>('file_diff', { repo, old: old_id, 'new': new_id })
 }
@@ -211,19 +226,214 @@ export const checkoutFile = (repo: RepoPath, path: string) => {
   return bus.invoke('checkout_file', { repo, path }) 
 }
 
-export const commit = (repo: RepoPath, message: string, update_ref: string | undefined) => {
-  return bus.invoke('commit', { repo, message, update_ref }) 
+export const commit = (repo: RepoPath, message: string, update_ref: string | undefined, options?: CommitOptions) => {
+  return bus.invoke('commit', { repo, message, update_ref, options }) 
 }
 
 export const currentRemoteBranch = (repo: RepoPath) => {
   return bus.invoke<Branch>('current_remote_branch', { repo })
 }
 
-export const push = (repo: RepoPath, remote: string, branch: string, credentials:[string, String] | undefined) => {
-  return bus.invoke('push', { repo, remote, branch, credentials }) 
+export const push = (repo: RepoPath, remote: string, branch: string, credentials:[string, String] | undefined, options?: PushOptions) => {
+  return bus.invoke('push', { repo, remote, branch, credentials, options }) 
+}
+// 拉取远程更新，remote为空时拉取所有远程，返回的cancel用于取消拉取
+export const fetchRemote = (repo: RepoPath, remote: string | undefined, options?: FetchOptions, credentials?: [string, String], onProgress?: (progress: TransferProgress) => void) => {
+  const taskId = `fetch-${Date.now()}`
+  const unlisten = listen<TransferProgress>(TRANSFER_PROGRESS(taskId), (event) => {
+    onProgress?.(event.payload)
+  })
+  const promise = bus.invoke<void>('fetch', { repo, remote, options, credentials, taskId }).finally(() => {
+    unlisten.then((unsub) => unsub())
+  })
+  return { promise, cancel: () => cancelRemoteTask(taskId) }
+}
+// 克隆远程仓库，成功后仓库会被添加到仓库列表中，返回的cancel用于取消克隆
+export const cloneRepo = (url: string, path: string, options?: CloneOptions, credentials?: [string, String], onProgress?: (progress: TransferProgress) => void) => {
+  const taskId = `clone-${Date.now()}`
+  const unlisten = listen<TransferProgress>(TRANSFER_PROGRESS(taskId), (event) => {
+    onProgress?.(event.payload)
+  })
+  const promise = bus.invoke<Repository>('clone_repo', { url, path, options, credentials, taskId }).finally(() => {
+    unlisten.then((unsub) => unsub())
+  })
+  return { promise, cancel: () => cancelRemoteTask(taskId) }
+}
+// 仓库列表，链接的工作树排在所属仓库的后面
+export const listRepos = () => {
+  return bus.invoke<Repository[]>('repos')
+}
+// 把仓库添加到仓库列表中并开始监控
+export const addRepo = (path: string, alias?: string) => {
+  return bus.invoke<Repository>('add_repo', { path, alias })
+}
+// 更新仓库的信息，包括分组、标签、颜色、备注和默认分支
+export const updateRepoInfo = (repo: Repository) => {
+  return bus.invoke<void>('update_repo', { repo })
+}
+export const renameRepo = (id: number, alias: string) => {
+  return bus.invoke<void>('rename_repo', { id, alias })
+}
+// 从仓库列表中删除仓库，不会删除仓库的文件
+export const deleteRepo = (id: number) => {
+  return bus.invoke<void>('delete_repo', { id })
+}
+// 记录仓库最后一次打开的时间
+export const touchRepo = (path: string) => {
+  return bus.invoke<void>('touch_repo', { path })
+}
+export const listGroups = () => {
+  return bus.invoke<RepoGroup[]>('groups')
 }
-export const pull = (repo: RepoPath, remote: string, branch: string, credentials:[string, String] | undefined) => {
-  return bus.invoke('pull', { repo, remote, branch, credentials }) 
+export const addGroup = (name: string) => {
+  return bus.invoke<RepoGroup>('add_group', { name })
+}
+export const renameGroup = (id: number, name: string) => {
+  return bus.invoke<void>('rename_group', { id, name })
+}
+// 删除仓库组，组中的仓库保留在仓库列表中
+export const deleteGroup = (id: number) => {
+  return bus.invoke<void>('delete_group', { id })
+}
+export const reorderGroups = (ids: number[]) => {
+  return bus.invoke<void>('reorder_groups', { ids })
+}
+// 替换组中的仓库，组内顺序与 repoIds 相同
+export const setGroupRepos = (groupId: number, repoIds: number[]) => {
+  return bus.invoke<void>('set_group_repos', { groupId, repoIds })
+}
+export const addRepoToGroup = (groupId: number, repoId: number) => {
+  return bus.invoke<void>('add_repo_to_group', { groupId, repoId })
+}
+export const removeRepoFromGroup = (groupId: number, repoId: number) => {
+  return bus.invoke<void>('remove_repo_from_group', { groupId, repoId })
+}
+export const groupRepos = (groupId: number) => {
+  return bus.invoke<Repository[]>('group_repos', { groupId })
+}
+// 对组中的所有仓库执行操作，返回每个仓库的结果，cancel 取消还没有开始的仓库
+const groupTask = (command: string, groupId: number) => {
+  const taskId = `${command}-${groupId}-${Date.now()}`
+  const promise = bus.invoke<RepoOperationResult[]>(command, { groupId, taskId })
+  return { promise, cancel: () => cancelRemoteTask(taskId) }
+}
+// 一次获取仓库列表中所有仓库的概览，单个仓库失败时只在该仓库的结果中返回错误
+export const reposSummary = () => {
+  return bus.invoke<RepoSummaryResult[]>('repos_summary')
+}
+// 在所有仓库或者组中的仓库中搜索提交，结果按时间倒序分页
+export const searchCommits = (query: CommitQuery, groupId?: number, offset?: number, count?: number) => {
+  return bus.invoke<CommitSearchPage>('search_commits', { query, groupId, offset, count })
+}
+export const fetchGroup = (groupId: number) => groupTask('fetch_group', groupId)
+export const groupStatus = (groupId: number) => groupTask('group_status', groupId)
+export const pullGroup = (groupId: number) => groupTask('pull_group', groupId)
+// 初始化仓库，成功后仓库会被添加到仓库列表中
+export const initRepo = (path: string, options?: InitOptions) => {
+  return bus.invoke<Repository>('init_repo', { path, options })
+}
+export const gitignoreTemplates = () => {
+  return bus.invoke<string[]>('gitignore_templates')
+}
+export const submodules = (repo: RepoPath) => {
+  return bus.invoke<Submodule[]>('submodules', { repo })
+}
+export const initSubmodule = (repo: RepoPath, path: string, overwrite: boolean = false) => {
+  return bus.invoke<void>('init_submodule', { repo, path, overwrite })
+}
+// path为空时同步所有子模块
+export const syncSubmodule = (repo: RepoPath, path?: string) => {
+  return bus.invoke<void>('sync_submodule', { repo, path })
+}
+// 初始化并更新子模块，path为空时更新所有子模块，返回的cancel用于取消更新
+export const updateSubmodule = (repo: RepoPath, path: string | undefined, recursive: boolean, credentials?: [string, String], onProgress?: (progress: TransferProgress) => void) => {
+  const taskId = `submodule-${Date.now()}`
+  const unlisten = listen<TransferProgress>(TRANSFER_PROGRESS(taskId), (event) => {
+    onProgress?.(event.payload)
+  })
+  const promise = bus.invoke<void>('update_submodule', { repo, path, recursive, credentials, taskId }).finally(() => {
+    unlisten.then((unsub) => unsub())
+  })
+  return { promise, cancel: () => cancelRemoteTask(taskId) }
+}
+// 返回子模块的工作目录，可以作为单独的仓库打开
+export const openSubmodule = (repo: RepoPath, path: string) => {
+  return bus.invoke<string>('open_submodule', { repo, path })
+}
+export const worktrees = (repo: RepoPath) => {
+  return bus.invoke<Worktree[]>('worktrees', { repo })
+}
+// 添加工作树，成功后工作树会作为仓库添加到仓库列表中
+export const addWorktree = (repo: RepoPath, name: string, path: string, options?: AddWorktreeOptions) => {
+  return bus.invoke<Worktree>('add_worktree', { repo, name, path, options })
+}
+export const lockWorktree = (repo: RepoPath, name: string, reason?: string) => {
+  return bus.invoke<void>('lock_worktree', { repo, name, reason })
+}
+export const unlockWorktree = (repo: RepoPath, name: string) => {
+  return bus.invoke<void>('unlock_worktree', { repo, name })
+}
+// 清理目录已经不存在的工作树，返回被清理的工作树名称
+export const pruneWorktrees = (repo: RepoPath) => {
+  return bus.invoke<string[]>('prune_worktrees', { repo })
+}
+// 递归扫描文件夹下的仓库，发现仓库时回调onFound，返回的cancel用于取消扫描
+export const scanRepos = (folderPath: string, options?: ScanOptions, onFound?: (repo: ScannedRepo) => void) => {
+  const taskId = `scan-${Date.now()}`
+  const unlisten = listen<ScannedRepo>(SCANNED_REPO(taskId), (event) => {
+    onFound?.(event.payload)
+  })
+  const promise = bus.invoke<ScannedRepo[]>('scan_repos', { folderPath, options, taskId }).finally(() => {
+    unlisten.then((unsub) => unsub())
+  })
+  return { promise, cancel: () => cancelRemoteTask(taskId) }
+}
+// 在提交历史中搜索新增或删除了匹配内容的提交，onMatch 在找到每个提交时调用
+export const pickaxe = (repo: string, options: PickaxeOptions, onMatch?: (found: PickaxeMatch) => void) => {
+  const taskId = `pickaxe-${Date.now()}`
+  const unlisten = listen<PickaxeMatch>(PICKAXE_MATCH(taskId), (event) => {
+    onMatch?.(event.payload)
+  })
+  const promise = bus.invoke<PickaxePage>('pickaxe', { repo, options, taskId }).finally(() => {
+    unlisten.then((unsub) => unsub())
+  })
+  return { promise, cancel: () => cancelRemoteTask(taskId) }
+}
+export const cancelRemoteTask = (taskId: string) => {
+  return bus.invoke<void>('cancel_remote_task', { taskId })
+}
+export const saveCredential = (url: string, credential: Credential) => {
+  return bus.invoke<void>('save_credential', { url, credential })
+}
+export const removeCredential = (url: string) => {
+  return bus.invoke<void>('remove_credential', { url })
+}
+export const credentialHosts = () => {
+  return bus.invoke<string[]>('credential_hosts')
+}
+// 清除保存的凭证和密钥，密钥丢失或损坏导致凭证无法解密时使用
+export const resetCredentials = () => {
+  return bus.invoke<void>('reset_credentials')
+}
+export const pull = (repo: RepoPath, remote: string, branch: string, credentials:[string, String] | undefined, options?: PullOptions) => {
+  return bus.invoke('pull', { repo, remote, branch, credentials, options }) 
+}
+
+export const remotes = (repo: RepoPath) => {
+  return bus.invoke<Remote[]>('remotes', { repo })
+}
+export const addRemote = (repo: RepoPath, name: string, url: string) => {
+  return bus.invoke<Remote>('add_remote', { repo, name, url })
+}
+// 返回无法自动更新的 fetch refspec
+export const renameRemote = (repo: RepoPath, name: string, newName: string) => {
+  return bus.invoke<string[]>('rename_remote', { repo, name, newName })
+}
+export const removeRemote = (repo: RepoPath, name: string) => {
+  return bus.invoke<void>('remove_remote', { repo, name })
+}
+export const setRemoteUrl = (repo: RepoPath, name: string, url: string, pushUrl?: string) => {
+  return bus.invoke<Remote>('set_remote_url', { repo, name, url, pushUrl })
 }
 
 export const switchBranch = (repo: RepoPath, branch: Branch) => {
@@ -261,8 +471,39 @@ export const commitTree = (repo: RepoPath, commitId: string) => {
   return bus.invoke<TreeDir>('get_commit_tree_recursive', {repo, commitId})
 }
 
-export const getTree = (repo: RepoPath, objectId: string, treePath: undefined | string = undefined) => {
-  return bus.invoke<TreeDir>('get_tree', {repo, objectId, treePath})
+// 传入浏览的提交 commitId 时为每一项添加最后一次修改的提交，需要遍历历史，不传时不会计算
+export const getTree = (repo: RepoPath, objectId: string, treePath: undefined | string = undefined, commitId?: string) => {
+  return bus.invoke<TreeDir>('get_tree', {repo, objectId, treePath, commitId})
+}
+
+// 提交中 dir 目录下每一项最后一次修改的提交，key 为项的名称
+export const treeLastCommits = (repo: RepoPath, commitId: string, dir: string) => {
+  return bus.invoke<Record<string, LastCommit>>('tree_last_commits', { repo, commitId, dir })
+}
+
+// 分页列出 rev 所指的树中 path 目录下的项，cursor 为上一页返回的 cursor
+export const listTree = (repo: RepoPath, rev: string, path: string, count: number, cursor?: string) => {
+  return bus.invoke<TreePage>('list_tree', { repo, rev, path, cursor, count })
+}
+
+// 获取多个路径的大小和最后一次修改的提交
+export const treeEntryDetails = (repo: RepoPath, rev: string, paths: string[]) => {
+  return bus.invoke<TreeEntryDetail[]>('tree_entry_details', { repo, rev, paths })
+}
+
+// 树中所有文件的路径
+export const treePaths = (repo: RepoPath, rev: string) => {
+  return bus.invoke<string[]>('tree_paths', { repo, rev })
+}
+
+// 在树中模糊查找文件
+export const findFiles = (repo: RepoPath, rev: string, query: string, limit: number) => {
+  return bus.invoke<FuzzyMatch[]>('find_files', { repo, rev, query, limit })
+}
+
+// 在工作区、暂存区或者提交的文件中搜索内容
+export const grep = (repo: RepoPath, source: GrepSource, options: GrepOptions) => {
+  return bus.invoke<GrepResult>('grep', {repo, source, options})
 }
 
 export const objectIsBinary = (repo: RepoPath, objectId: string) => {
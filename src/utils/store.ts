@@ -1,45 +1,41 @@
 import { Repository } from "@/types"
-import { get_store_db } from "./storage"
+import { addRepo, deleteRepo, getRepoByPath, listRepos, updateRepoInfo } from "./command"
+
+// 仓库列表通过后端命令读写，新增的字段(分组、标签等)和组成员的清理都由后端处理
 
 // 根据id获取仓库
 export const getRepositoryById = async (id: number) => {
-  const db = await get_store_db()
-  const res = await db.select<Repository[]>(`SELECT * FROM repository WHERE id = ${id}`)
-  if (res.length === 0) {
-    return undefined 
-  }
-  let repo = res[0]
-  // @ts-ignore
-  repo.hasWatch =!!repo.has_watch
-  repo.top =!!repo.top
-  return repo
+  const repos = await listRepos()
+  return repos.find(repo => repo.id === id)
 }
 
 // 根据路径获取仓库
 export const getRepositoryByPath = async (path: string) => {
-  const db = await get_store_db()
-  const res = await db.select<[Repository]>(`SELECT * FROM repository WHERE path = '${path}'`)
-  return res.length > 0? res[0] : undefined
+  const repo = await getRepoByPath(path)
+  return repo ?? undefined
 }
 
+// 添加仓库，后端默认的排序和置顶与 repo 不同时再更新一次
 export const saveRepo = async (repo: Repository) => {
-  const db = await get_store_db()
-  return db.execute('INSERT INTO repository (path, alias, has_watch, `order`, top) VALUES (?, ?, ?, ?, ?)', 
-    [repo.path, repo.alias, Number(repo.hasWatch), repo.order, Number(repo.top)])
+  const added = await addRepo(repo.path, repo.alias)
+  if (added.hasWatch !== repo.hasWatch || added.order !== repo.order || added.top !== repo.top) {
+    const updated = { ...added, hasWatch: repo.hasWatch, order: repo.order, top: repo.top }
+    await updateRepoInfo(updated)
+    return updated
+  }
+  return added
 }
 
-export const readRepos = async () => {
-  const db = await get_store_db()
-  return db.select<[Repository]>('SELECT id, path, alias, has_watch as hasWatch, `order`, top FROM repository order by top desc, `order`, alias')
+// 仓库列表，链接的工作树排在所属仓库的后面
+export const readRepos = () => {
+  return listRepos()
 }
 
-export const updateRepo = async (repo: Repository) => {
-  const db = await get_store_db()
-  return db.execute('UPDATE repository SET path =?, alias =?, has_watch =?, `order` =?, top =? WHERE id =?',
-    [repo.path, repo.alias, Number(repo.hasWatch), repo.order, Number(repo.top), repo.id]) 
+export const updateRepo = (repo: Repository) => {
+  return updateRepoInfo(repo)
 }
 
-export const removeRepo = async (id: number) => {
-  const db = await get_store_db()
-  return db.execute('DELETE FROM repository WHERE id =?', [id]) 
-}
\ No newline at end of file
+// 从仓库列表中删除仓库并停止监控，同时从所在的组中移除
+export const removeRepo = (id: number) => {
+  return deleteRepo(id)
+}